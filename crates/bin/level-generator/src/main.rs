@@ -0,0 +1,57 @@
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use doome_levels::generator::Generator;
+use doome_levels::tileset::Tileset;
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
+
+/// Generates a map and saves it as `.tmj`, so that it can be opened in Tiled
+/// (or checked with `level-validator`).
+///
+/// Maps refer to `tileset.tsx` through a relative path, so they should be
+/// saved next to it - e.g. into `assets/levels`.
+#[derive(Debug, Clone, Parser)]
+#[clap(rename_all = "kebab-case")]
+struct Args {
+    output: PathBuf,
+
+    #[clap(long, default_value = "0")]
+    seed: u64,
+
+    #[clap(long, default_value = "8")]
+    rooms: usize,
+
+    #[clap(long, default_value = "2")]
+    locked_doors: usize,
+
+    #[clap(long, default_value = "2")]
+    enemies_per_room: usize,
+
+    #[clap(long, default_value = "assets")]
+    assets: PathBuf,
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+
+    let tileset = args.assets.join("levels").join("tileset.tsx");
+
+    let tileset = fs::read_to_string(&tileset)
+        .with_context(|| format!("Couldn't read {}", tileset.display()))?;
+
+    let tileset = Tileset::from_tsx(&tileset);
+
+    let map = Generator::default()
+        .with_rooms(args.rooms)
+        .with_locked_doors(args.locked_doors)
+        .with_enemies_per_room(args.enemies_per_room)
+        .generate(&mut SmallRng::seed_from_u64(args.seed), &tileset);
+
+    fs::write(&args.output, map.to_tmj())
+        .with_context(|| format!("Couldn't write {}", args.output.display()))?;
+
+    Ok(())
+}
@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use doome_levels::map::Map;
+use doome_levels::tileset::Tileset;
+use doome_raytracer as rt;
+use glam::{vec3, Vec3};
+use tobj::LoadOptions;
+
+/// Prints how much of the static geometry budget given maps take.
+///
+/// Only the map's geometry (floors, ceilings, walls) is taken into account -
+/// objects such as doors or torches come on top of that.
+#[derive(Debug, Clone, Parser)]
+#[clap(rename_all = "kebab-case")]
+struct Args {
+    /// Maps to analyze; defaults to all the maps from `assets/levels`
+    maps: Vec<PathBuf>,
+
+    #[clap(long, default_value = "assets")]
+    assets: PathBuf,
+}
+
+type Models = HashMap<&'static str, Vec<[Vec3; 3]>>;
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+
+    let tileset = args.assets.join("levels").join("tileset.tsx");
+
+    let tileset = fs::read_to_string(&tileset)
+        .with_context(|| format!("Couldn't read {}", tileset.display()))?;
+
+    let tileset = Tileset::from_tsx(&tileset);
+
+    let mut models = Models::default();
+
+    for name in ["ceiling", "floor", "wall"] {
+        let path = args.assets.join("models").join(format!("{}.obj", name));
+
+        models.insert(
+            name,
+            load_model(&path).with_context(|| {
+                format!("Couldn't load model: {}", path.display())
+            })?,
+        );
+    }
+
+    let maps = if args.maps.is_empty() {
+        find_maps(&args.assets.join("levels"))?
+    } else {
+        args.maps
+    };
+
+    for map in maps {
+        print_stats(&tileset, &models, &map)
+            .with_context(|| format!("Couldn't process {}", map.display()))?;
+    }
+
+    Ok(())
+}
+
+fn find_maps(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut maps = Vec::new();
+
+    for entry in fs::read_dir(dir)
+        .with_context(|| format!("Couldn't read {}", dir.display()))?
+    {
+        let path = entry?.path();
+
+        if path.extension().map_or(false, |ext| ext == "tmj") {
+            maps.push(path);
+        }
+    }
+
+    maps.sort();
+
+    Ok(maps)
+}
+
+fn load_model(path: &Path) -> Result<Vec<[Vec3; 3]>> {
+    let (models, _) = tobj::load_obj(
+        path,
+        &LoadOptions {
+            triangulate: true,
+            ..LoadOptions::default()
+        },
+    )?;
+
+    let triangles = models
+        .iter()
+        .flat_map(|model| {
+            let mesh = &model.mesh;
+
+            mesh.indices.chunks(3).map(|indices| {
+                indices
+                    .iter()
+                    .map(|&index| {
+                        let index = index as usize;
+
+                        vec3(
+                            mesh.positions[3 * index],
+                            mesh.positions[3 * index + 1],
+                            mesh.positions[3 * index + 2],
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .try_into()
+                    .unwrap()
+            })
+        })
+        .collect();
+
+    Ok(triangles)
+}
+
+fn print_stats(tileset: &Tileset, models: &Models, path: &Path) -> Result<()> {
+    let map = fs::read_to_string(path)?;
+    let map = Map::from_tmj(&map).index(tileset).geometrize();
+
+    let mut geometry = Box::<rt::StaticGeometry>::default();
+    let mut triangles = 0;
+
+    for feature in map.features() {
+        let (model, placement) = feature.placement();
+        let xform = placement.matrix();
+
+        for &[v0, v1, v2] in &models[model] {
+            // Keep counting even if we're past the limit, so that we can say
+            // by how much the map doesn't fit
+            if triangles < rt::MAX_STATIC_TRIANGLES {
+                geometry.set(
+                    rt::TriangleId::new_static(triangles),
+                    rt::Triangle::new(v0, v1, v2, rt::MaterialId::new(0))
+                        .with_transform(xform),
+                );
+            }
+
+            triangles += 1;
+        }
+    }
+
+    let index_len = rt::GeometryIndexer::index_len(&geometry);
+
+    println!("{}", path.display());
+    println!("  features: {}", map);
+
+    println!(
+        "  triangles: {} / {}{}",
+        triangles,
+        rt::MAX_STATIC_TRIANGLES,
+        over_budget(triangles, rt::MAX_STATIC_TRIANGLES),
+    );
+
+    println!(
+        "  geometry index: {} / {}{}",
+        index_len,
+        rt::STATIC_GEOMETRY_INDEX_SIZE,
+        over_budget(index_len, rt::STATIC_GEOMETRY_INDEX_SIZE),
+    );
+
+    Ok(())
+}
+
+fn over_budget(value: usize, max: usize) -> &'static str {
+    if value > max {
+        " (over budget!)"
+    } else {
+        ""
+    }
+}
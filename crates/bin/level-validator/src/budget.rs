@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use doome_levels::geometrized::Map;
+use doome_raytracer as rt;
+use glam::{vec3, Vec3};
+use tobj::LoadOptions;
+
+use crate::report::Report;
+
+/// Models the map's geometry is built from (see `Feature::placement()`).
+pub struct Models {
+    models: HashMap<&'static str, Vec<[Vec3; 3]>>,
+}
+
+impl Models {
+    pub fn load(assets: &Path) -> Result<Self> {
+        let mut models = HashMap::new();
+
+        for name in ["ceiling", "floor", "wall"] {
+            let path = assets.join("models").join(format!("{}.obj", name));
+
+            models.insert(
+                name,
+                load_model(&path).with_context(|| {
+                    format!("Couldn't load model: {}", path.display())
+                })?,
+            );
+        }
+
+        Ok(Self { models })
+    }
+}
+
+/// Checks whether map's static geometry fits within the limits imposed by
+/// the raytracer.
+///
+/// Objects (doors, torches etc.) are not taken into account - they come on
+/// top of that, so the limits should be rather treated as upper bounds.
+pub fn check_budget(map: &Map<'_>, models: &Models, report: &mut Report) {
+    let mut geometry = Box::<rt::StaticGeometry>::default();
+    let mut triangles = 0;
+
+    for feature in map.features() {
+        let (model, placement) = feature.placement();
+        let xform = placement.matrix();
+
+        for &[v0, v1, v2] in &models.models[model] {
+            if triangles < rt::MAX_STATIC_TRIANGLES {
+                geometry.set(
+                    rt::TriangleId::new_static(triangles),
+                    rt::Triangle::new(v0, v1, v2, rt::MaterialId::new(0))
+                        .with_transform(xform),
+                );
+            }
+
+            triangles += 1;
+        }
+    }
+
+    if triangles > rt::MAX_STATIC_TRIANGLES {
+        report.error(format!(
+            "map has too many triangles: {} / {}",
+            triangles,
+            rt::MAX_STATIC_TRIANGLES
+        ));
+
+        // The index would be built from truncated geometry, so there's no
+        // point in checking it
+        return;
+    }
+
+    let index_len = rt::GeometryIndexer::index_len(&geometry);
+
+    if index_len > rt::STATIC_GEOMETRY_INDEX_SIZE {
+        report.error(format!(
+            "map's geometry index is too large: {} / {}",
+            index_len,
+            rt::STATIC_GEOMETRY_INDEX_SIZE
+        ));
+    }
+}
+
+fn load_model(path: &Path) -> Result<Vec<[Vec3; 3]>> {
+    let (models, _) = tobj::load_obj(
+        path,
+        &LoadOptions {
+            triangulate: true,
+            ..LoadOptions::default()
+        },
+    )?;
+
+    let triangles = models
+        .iter()
+        .flat_map(|model| {
+            let mesh = &model.mesh;
+
+            mesh.indices.chunks(3).map(|indices| {
+                indices
+                    .iter()
+                    .map(|&index| {
+                        let index = index as usize;
+
+                        vec3(
+                            mesh.positions[3 * index],
+                            mesh.positions[3 * index + 1],
+                            mesh.positions[3 * index + 2],
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .try_into()
+                    .unwrap()
+            })
+        })
+        .collect();
+
+    Ok(triangles)
+}
@@ -0,0 +1,129 @@
+use doome_levels::indexed::{Map, Tile};
+
+use crate::objects::Objects;
+use crate::references::Reference;
+use crate::report::Report;
+
+pub fn check_references(
+    objects: &Objects<'_>,
+    refs: &[Reference],
+    report: &mut Report,
+) {
+    for r in refs {
+        let exists = objects
+            .names
+            .iter()
+            .any(|&(kind, name)| kind == r.kind && r.matches(name));
+
+        if !exists {
+            report.error(format!(
+                "{} refers to {} `{}`, but map contains no such object",
+                r.origin, r.kind, r.pattern
+            ));
+        }
+    }
+}
+
+/// Looks for walls that don't touch any floor - they are never rendered, so
+/// most likely they are leftovers.
+///
+/// Diagonal neighbours are taken into account as well, since walls in
+/// corners are fine.
+pub fn check_walls(map: &Map<'_>, report: &mut Report) {
+    for (x, y, tile) in map.tiles() {
+        // Each wall is indexed as four tiles (one per direction), so let's
+        // look at just one of them
+        if !matches!(tile, Tile::Wall(_, 0)) {
+            continue;
+        }
+
+        let has_floor = (-1..=1)
+            .flat_map(|dx| (-1..=1).map(move |dy| (dx, dy)))
+            .any(|(dx, dy)| map.floor_at(x + dx, y + dy).is_some());
+
+        if !has_floor {
+            report.warn(format!("wall at {},{} faces no floor", x, y));
+        }
+    }
+}
+
+pub fn check_doors(map: &Map<'_>, objects: &Objects<'_>, report: &mut Report) {
+    let has_wall_at = |x, y| map.get(x, y).any(|tile| tile.is_wall());
+
+    for door in &objects.doors {
+        let (x, y) = (door.x, door.y);
+
+        let is_between_walls = (has_wall_at(x - 1, y) && has_wall_at(x + 1, y))
+            || (has_wall_at(x, y - 1) && has_wall_at(x, y + 1));
+
+        if !is_between_walls {
+            report.warn(format!(
+                "door `{}` at {},{} is not placed between two walls",
+                door.name, x, y
+            ));
+        }
+    }
+}
+
+pub fn check_keys(objects: &Objects<'_>, report: &mut Report) {
+    for key in &objects.keys {
+        let door = objects.doors.iter().find(|door| door.name == key.name);
+
+        match door {
+            Some(door) => match door.color {
+                Some(color) if color != key.color => {
+                    report.warn(format!(
+                        "key `{}` is {}, but its door is {}",
+                        key.name, key.color, color
+                    ));
+                }
+
+                Some(_) => (),
+
+                None => {
+                    report.warn(format!(
+                        "key `{}` opens door that's not locked",
+                        key.name
+                    ));
+                }
+            },
+
+            None => {
+                report.error(format!("key `{}` has no door", key.name));
+            }
+        }
+    }
+
+    // Keys can be also given by level's code, so this one is just a warning
+    for door in &objects.doors {
+        let has_key = objects.keys.iter().any(|key| key.name == door.name);
+
+        if door.color.is_some() && !has_key {
+            report.warn(format!(
+                "door `{}` is locked, but map contains no key for it",
+                door.name
+            ));
+        }
+    }
+}
+
+pub fn check_zones(objects: &Objects<'_>, report: &mut Report) {
+    for (idx, a) in objects.zones.iter().enumerate() {
+        if a.x1 >= a.x2 || a.y1 >= a.y2 {
+            report.error(format!("zone `{}` has no area", a.name));
+            continue;
+        }
+
+        for b in &objects.zones[idx + 1..] {
+            let overlaps =
+                a.x1 < b.x2 && b.x1 < a.x2 && a.y1 < b.y2 && b.y1 < a.y2;
+
+            if overlaps {
+                report.warn(format!(
+                    "zones `{}` and `{}` overlap",
+                    a.name, b.name
+                ));
+            }
+        }
+    }
+}
@@ -0,0 +1,184 @@
+mod budget;
+mod checks;
+mod objects;
+mod references;
+mod report;
+
+use std::any::Any;
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use clap::Parser;
+use doome_levels::map::Map;
+use doome_levels::tileset::Tileset;
+
+use self::budget::Models;
+use self::objects::Objects;
+use self::references::Reference;
+use self::report::Report;
+
+/// Loads maps the same way the game does (just without spawning anything)
+/// and looks for problems in them.
+///
+/// Apart from the map itself, the level's code (`<sources>/<map>.rs`) and
+/// scripts (`<map>.script.json`, `<map>.rhai`) are checked for references to
+/// objects the map doesn't contain.
+#[derive(Debug, Clone, Parser)]
+#[clap(rename_all = "kebab-case")]
+struct Args {
+    /// Maps to validate; defaults to all the maps from `assets/levels`
+    maps: Vec<PathBuf>,
+
+    #[clap(long, default_value = "assets")]
+    assets: PathBuf,
+
+    #[clap(long, default_value = "src/levels")]
+    sources: PathBuf,
+
+    /// Treat warnings as errors
+    #[clap(long)]
+    strict: bool,
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+
+    let tileset = args.assets.join("levels").join("tileset.tsx");
+
+    let tileset = fs::read_to_string(&tileset)
+        .with_context(|| format!("Couldn't read {}", tileset.display()))?;
+
+    let tileset = Tileset::from_tsx(&tileset);
+    let models = Models::load(&args.assets)?;
+
+    let maps = if args.maps.is_empty() {
+        find_maps(&args.assets.join("levels"))?
+    } else {
+        args.maps.clone()
+    };
+
+    // Maps report problems by panicking - we catch those panics and print
+    // them as errors, so there's no need for the default panic message
+    panic::set_hook(Box::new(|_| ()));
+
+    let mut errors = 0;
+    let mut warnings = 0;
+
+    for map in maps {
+        let report = validate(&args, &tileset, &models, &map)
+            .with_context(|| format!("Couldn't process {}", map.display()))?;
+
+        println!("{}", map.display());
+        report.print();
+
+        errors += report.errors.len();
+        warnings += report.warnings.len();
+    }
+
+    if errors > 0 || (args.strict && warnings > 0) {
+        bail!("Found {} error(s) and {} warning(s)", errors, warnings);
+    }
+
+    Ok(())
+}
+
+fn find_maps(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut maps = Vec::new();
+
+    for entry in fs::read_dir(dir)
+        .with_context(|| format!("Couldn't read {}", dir.display()))?
+    {
+        let path = entry?.path();
+
+        if path.extension().map_or(false, |ext| ext == "tmj") {
+            maps.push(path);
+        }
+    }
+
+    maps.sort();
+
+    Ok(maps)
+}
+
+fn validate(
+    args: &Args,
+    tileset: &Tileset,
+    models: &Models,
+    path: &Path,
+) -> Result<Report> {
+    let mut report = Report::default();
+    let map = fs::read_to_string(path)?;
+
+    let imap = match panic::catch_unwind(AssertUnwindSafe(|| {
+        Map::from_tmj(&map).index(tileset)
+    })) {
+        Ok(imap) => imap,
+
+        Err(err) => {
+            report.error(format!("couldn't index map: {}", panic_msg(&*err)));
+            return Ok(report);
+        }
+    };
+
+    let objects = Objects::collect(imap.objects(), &mut report);
+    let mut refs = Vec::new();
+
+    if let Some(name) = path.file_stem().and_then(|name| name.to_str()) {
+        let src = args.sources.join(format!("{}.rs", name));
+
+        if src.exists() {
+            refs.extend(Reference::from_source(
+                &src,
+                &fs::read_to_string(&src)?,
+            ));
+        }
+    }
+
+    let script = path.with_extension("script.json");
+
+    if script.exists() {
+        refs.extend(Reference::from_script(
+            &script,
+            &fs::read_to_string(&script)?,
+        )?);
+    }
+
+    let rhai = path.with_extension("rhai");
+
+    if rhai.exists() {
+        refs.extend(Reference::from_rhai(&rhai, &fs::read_to_string(&rhai)?));
+    }
+
+    checks::check_references(&objects, &refs, &mut report);
+    checks::check_walls(&imap, &mut report);
+    checks::check_doors(&imap, &objects, &mut report);
+    checks::check_keys(&objects, &mut report);
+    checks::check_zones(&objects, &mut report);
+
+    match panic::catch_unwind(AssertUnwindSafe(|| imap.geometrize())) {
+        Ok(gmap) => {
+            budget::check_budget(&gmap, models, &mut report);
+        }
+
+        Err(err) => {
+            report.error(format!(
+                "couldn't geometrize map: {}",
+                panic_msg(&*err)
+            ));
+        }
+    }
+
+    Ok(report)
+}
+
+fn panic_msg(err: &(dyn Any + Send)) -> &str {
+    if let Some(msg) = err.downcast_ref::<&str>() {
+        msg
+    } else if let Some(msg) = err.downcast_ref::<String>() {
+        msg
+    } else {
+        "unknown error"
+    }
+}
@@ -0,0 +1,351 @@
+use std::collections::HashSet;
+
+use doome_levels::indexed::{Object, Shape};
+use glam::Vec2;
+
+use crate::report::Report;
+
+/// Entities that zones can track, see `ZoneTarget` in the game.
+const ZONE_TARGETS: &[&str] =
+    &["player", "enemy", "enemies", "bullet", "bullets"];
+
+/// Modes and easings of movers, see `Mover` in the game.
+const MOVER_MODES: &[&str] = &["once", "loop", "ping-pong"];
+const MOVER_EASINGS: &[&str] =
+    &["linear", "ease-in", "ease-out", "ease-in-out"];
+
+/// Map's objects, understood the same way `LevelLocator` understands them.
+#[derive(Debug, Default)]
+pub struct Objects<'a> {
+    pub doors: Vec<Door<'a>>,
+    pub keys: Vec<Key<'a>>,
+    pub zones: Vec<Zone<'a>>,
+
+    /// Objects that can be referred to from level's code or script, e.g.
+    /// `("tag", "gate-2")`
+    pub names: HashSet<(&'static str, &'a str)>,
+}
+
+#[derive(Debug)]
+pub struct Door<'a> {
+    pub name: &'a str,
+    pub color: Option<&'a str>,
+    pub x: i32,
+    pub y: i32,
+}
+
+#[derive(Debug)]
+pub struct Key<'a> {
+    pub name: &'a str,
+    pub color: &'a str,
+}
+
+#[derive(Debug)]
+pub struct Zone<'a> {
+    pub name: &'a str,
+    pub x1: i32,
+    pub y1: i32,
+    pub x2: i32,
+    pub y2: i32,
+}
+
+impl<'a> Objects<'a> {
+    pub fn collect(objects: &'a [Object], report: &mut Report) -> Self {
+        let mut this = Self::default();
+
+        for obj in objects {
+            this.add(obj, report);
+        }
+
+        this
+    }
+
+    fn add(&mut self, obj: &'a Object, report: &mut Report) {
+        let obj_name = obj.name.as_str();
+
+        if let Some(spec) = obj_name.strip_prefix("door:") {
+            let (name, color) = match spec.split_once(',') {
+                Some((name, color)) => (name, Some(color)),
+                None => (spec, None),
+            };
+
+            if let Some(color) = color {
+                check_color(color, report);
+            }
+
+            self.doors.push(Door {
+                name,
+                color,
+                x: obj.x,
+                y: obj.y,
+            });
+
+            self.names.insert(("door", name));
+            return;
+        }
+
+        if let Some(spec) = obj_name.strip_prefix("enemy:") {
+            let (kind, name) = match spec.split_once(':') {
+                Some((kind, name)) => (kind, Some(name)),
+                None => (spec, None),
+            };
+
+            if !["moth-monster", "doome"].contains(&kind) {
+                report.error(format!("unrecognized enemy: {}", spec));
+            }
+
+            if let Some(name) = name {
+                self.names.insert(("enemy", name));
+            }
+
+            return;
+        }
+
+        if obj_name == "gate" || obj_name == "heart" {
+            return;
+        }
+
+        if let Some(spec) = obj_name.strip_prefix("key:") {
+            let Some((name, color)) = spec.split_once(',') else {
+                report.error(format!("invalid key definition: {}", spec));
+                return;
+            };
+
+            check_color(color, report);
+
+            self.keys.push(Key { name, color });
+            self.names.insert(("key", name));
+            return;
+        }
+
+        if let Some(spec) = obj_name.strip_prefix("light:") {
+            let (kind, name) = match spec.split_once(':') {
+                Some((kind, name)) => (kind, Some(name)),
+                None => (spec, None),
+            };
+
+            if !["point", "spot"].contains(&kind) {
+                report.error(format!("invalid light definition: {}", spec));
+            }
+
+            if let Some(color) = obj.props.get("color") {
+                match color.as_str() {
+                    Some(color) => check_color(color, report),
+                    None => report.error(format!(
+                        "light has non-string color: {}",
+                        obj_name
+                    )),
+                }
+            }
+
+            if let Some(name) = name {
+                self.add_unique("light", name, report);
+            }
+
+            return;
+        }
+
+        if let Some(name) = obj_name.strip_prefix("mover:") {
+            check_mover(obj, name, report);
+            self.add_unique("mover", name, report);
+            return;
+        }
+
+        if let Some(spec) = obj_name.strip_prefix("pickup:") {
+            if !["flashlight", "heart", "rifle", "rpg"].contains(&spec) {
+                report.error(format!("unrecognized pickup: {}", spec));
+            }
+
+            return;
+        }
+
+        if let Some(name) = obj_name
+            .strip_prefix("secret-wall:")
+            .or_else(|| obj_name.strip_prefix("destructible-wall:"))
+        {
+            if name.is_empty() {
+                report.error("unnamed wall");
+            }
+
+            if obj.props.get("health").map_or(false, |h| !h.is_number()) {
+                report.error(format!("wall `{}` has non-numeric health", name));
+            }
+
+            self.add_unique("wall", name, report);
+            return;
+        }
+
+        if let Some(name) = obj_name.strip_prefix("tag:") {
+            self.add_unique("tag", name, report);
+            return;
+        }
+
+        if let Some(spec) = obj_name.strip_prefix("torch") {
+            if spec.is_empty() {
+                return;
+            }
+
+            let Some(spec) = spec.strip_prefix(':') else {
+                report.error(format!("unrecognized object: {}", obj_name));
+                return;
+            };
+
+            let mut opts = spec.split(',');
+
+            if let Some(name) = opts.next() {
+                self.add_unique("torch", name, report);
+            }
+
+            for opt in opts {
+                if !["off", "force-active-texture"].contains(&opt) {
+                    report.error(format!("invalid torch definition: {}", spec));
+                }
+            }
+
+            return;
+        }
+
+        if obj_name.starts_with("checkpoint:") || obj_name.starts_with("zone:")
+        {
+            // Checkpoints are zones with a special name, see `LevelLocator`
+            let (name, w, h) = match obj_name.strip_prefix("zone:") {
+                Some(name) => (name, obj.w, obj.h),
+                None => (obj_name, obj.w.max(1), obj.h.max(1)),
+            };
+
+            // Ellipses and polygons are approximated with their bounding
+            // boxes, which is good enough to spot overlapping zones
+            let (x1, y1, x2, y2) = match &obj.shape {
+                Shape::Rect => (obj.x, obj.y, obj.x + w, obj.y + h),
+
+                Shape::Ellipse { center, radius } => {
+                    if (radius.x - radius.y).abs() > 0.01 {
+                        report.error(format!(
+                            "zone `{}` is an ellipse instead of a circle",
+                            name
+                        ));
+                    }
+
+                    bounds([*center - *radius, *center + *radius].iter())
+                }
+
+                Shape::Polygon(points) => bounds(points.iter()),
+            };
+
+            if let Some(filter) = obj.props.get("filter") {
+                let is_valid = filter.as_str().map_or(false, |filter| {
+                    filter
+                        .split(',')
+                        .all(|target| ZONE_TARGETS.contains(&target.trim()))
+                });
+
+                if !is_valid {
+                    report.error(format!(
+                        "zone `{}` has invalid filter: {}",
+                        name, filter
+                    ));
+                }
+            }
+
+            self.zones.push(Zone {
+                name,
+                x1,
+                y1,
+                x2,
+                y2,
+            });
+
+            self.names.insert(("zone", name));
+            return;
+        }
+
+        report.error(format!("unrecognized object: {}", obj_name));
+    }
+
+    fn add_unique(
+        &mut self,
+        kind: &'static str,
+        name: &'a str,
+        report: &mut Report,
+    ) {
+        if !self.names.insert((kind, name)) {
+            report.error(format!("{} defined multiple times: {}", kind, name));
+        }
+    }
+}
+
+/// Checks color in either our `0xrrggbb` notation or Tiled's `#aarrggbb`
+/// notation, see `parse_color()` in the game.
+fn check_color(color: &str, report: &mut Report) {
+    let is_valid = color
+        .strip_prefix("0x")
+        .or_else(|| color.strip_prefix('#'))
+        .map_or(false, |hex| u32::from_str_radix(hex, 16).is_ok());
+
+    if !is_valid {
+        report.error(format!("invalid color: {}", color));
+    }
+}
+
+/// Checks mover's properties, see `spawn_mover()` in the game.
+fn check_mover(obj: &Object, name: &str, report: &mut Report) {
+    let prop = |prop: &str| obj.props.get(prop).map(|value| value.as_str());
+
+    let is_path_valid = prop("path").flatten().map_or(false, |path| {
+        path.split(';').all(|keyframe| {
+            let Some((pose, duration)) = keyframe.trim().split_once('@') else {
+                return false;
+            };
+
+            let is_duration_valid = duration
+                .trim()
+                .parse::<f32>()
+                .map_or(false, |duration| duration >= 0.0);
+
+            let coords: Vec<_> = pose.split(',').collect();
+
+            let is_pose_valid = pose.trim() == "pause"
+                || ((3..=4).contains(&coords.len())
+                    && coords
+                        .iter()
+                        .all(|coord| coord.trim().parse::<f32>().is_ok()));
+
+            is_duration_valid && is_pose_valid
+        })
+    });
+
+    if !is_path_valid {
+        report.error(format!("mover `{}` has invalid path", name));
+    }
+
+    let checks = [
+        ("mode", MOVER_MODES),
+        ("easing", MOVER_EASINGS),
+        ("kind", &["block", "platform"]),
+    ];
+
+    for (prop_name, valid) in checks {
+        if let Some(value) = prop(prop_name) {
+            if !value.map_or(false, |value| valid.contains(&value)) {
+                report.error(format!(
+                    "mover `{}` has invalid {}",
+                    name, prop_name
+                ));
+            }
+        }
+    }
+}
+
+fn bounds<'a>(points: impl Iterator<Item = &'a Vec2>) -> (i32, i32, i32, i32) {
+    points.fold(
+        (i32::MAX, i32::MAX, i32::MIN, i32::MIN),
+        |(x1, y1, x2, y2), point| {
+            (
+                x1.min(point.x.floor() as i32),
+                y1.min(point.y.floor() as i32),
+                x2.max(point.x.ceil() as i32),
+                y2.max(point.y.ceil() as i32),
+            )
+        },
+    )
+}
@@ -0,0 +1,370 @@
+use std::collections::HashSet;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::Value;
+
+/// Calls through which level's code refers to map's objects (mostly
+/// `LevelLocator`'s methods), together with the kind of object they refer to.
+const CALLS: &[(&str, &str)] = &[
+    (".door(", "door"),
+    (".enemy_group(", "enemy"),
+    (".key(", "key"),
+    (".light(", "light"),
+    (".mover(", "mover"),
+    (".tag(", "tag"),
+    (".torch(", "torch"),
+];
+
+/// Events through which level's code learns about zones; zone's name is then
+/// compared with the variable the event got destructured into, e.g.
+/// `ZoneEntered(name) if name == "end"`.
+const ZONE_EVENTS: &[&str] = &["ZoneEntered(", "ZoneLeft("];
+
+/// Functions through which level's Rhai script refers to map's tags.
+const SCRIPT_CALLS: &[&str] = &["tag(", "has_tag("];
+
+/// Object that level's code or script expects the map to contain.
+#[derive(Debug)]
+pub struct Reference {
+    pub kind: &'static str,
+
+    /// Object's name; `*` matches anything, which is how we handle names
+    /// built through `format!()`
+    pub pattern: String,
+
+    /// Where the reference comes from, e.g. `src/levels/level2.rs:199`
+    pub origin: String,
+}
+
+impl Reference {
+    /// Finds references in level's source code.
+    ///
+    /// This is just a textual search for string literals passed to
+    /// `LevelLocator`, so names stored in variables are not found - but it's
+    /// good enough to catch typos and objects removed from the map.
+    pub fn from_source(path: &Path, src: &str) -> Vec<Self> {
+        let mut refs = Vec::new();
+
+        for &(call, kind) in CALLS {
+            for (idx, _) in src.match_indices(call) {
+                let arg = src[idx + call.len()..].trim_start();
+                let arg = arg.strip_prefix("format!(").unwrap_or(arg);
+
+                let Some(arg) = literal(arg) else { continue };
+
+                refs.push(Self {
+                    kind,
+                    pattern: to_pattern(arg),
+                    origin: origin(path, src, idx),
+                });
+            }
+        }
+
+        for event in ZONE_EVENTS {
+            for (idx, _) in src.match_indices(event) {
+                let idx = idx + event.len();
+                let Some(len) = src[idx..].find(')') else { continue };
+                let var = &src[idx..idx + len];
+
+                if var.is_empty() || !var.chars().all(is_ident) {
+                    continue;
+                }
+
+                // Comparisons happen either in the match arm's guard or right
+                // at the beginning of its body, so it's enough to look until
+                // the end of the first statement
+                let idx = idx + len;
+                let len = src[idx..].find(';').unwrap_or(src.len() - idx);
+                let cmp = format!("{} == ", var);
+
+                for (cmp_idx, _) in src[idx..idx + len].match_indices(&cmp) {
+                    let cmp_idx = idx + cmp_idx;
+
+                    let Some(arg) = literal(&src[cmp_idx + cmp.len()..]) else {
+                        continue;
+                    };
+
+                    refs.push(Self {
+                        kind: "zone",
+                        pattern: arg.to_owned(),
+                        origin: origin(path, src, cmp_idx),
+                    });
+                }
+            }
+        }
+
+        refs
+    }
+
+    /// Finds references in level's Rhai script (see `LevelRhaiScript` in the
+    /// game).
+    ///
+    /// Similarly to [`Self::from_source()`], only string literals are found.
+    pub fn from_rhai(path: &Path, script: &str) -> Vec<Self> {
+        let mut refs = Vec::new();
+
+        for call in SCRIPT_CALLS {
+            for (idx, _) in script.match_indices(call) {
+                // Skips e.g. `has_tag(` when looking for `tag(`
+                if script[..idx].chars().next_back().map_or(false, is_ident) {
+                    continue;
+                }
+
+                let arg = script[idx + call.len()..].trim_start();
+                let Some(arg) = literal(arg) else { continue };
+
+                refs.push(Self {
+                    kind: "tag",
+                    pattern: arg.to_owned(),
+                    origin: origin(path, script, idx),
+                });
+            }
+        }
+
+        refs
+    }
+
+    /// Finds references in level's script (see `LevelScript` in the game).
+    pub fn from_script(path: &Path, script: &str) -> Result<Vec<Self>> {
+        #[derive(Deserialize)]
+        struct Script {
+            #[serde(default)]
+            rules: Vec<Rule>,
+        }
+
+        #[derive(Deserialize)]
+        struct Rule {
+            on: Value,
+            #[serde(rename = "do")]
+            actions: Vec<Value>,
+        }
+
+        let script: Script = serde_json::from_str(script)
+            .with_context(|| format!("Couldn't parse {}", path.display()))?;
+
+        let steps: Vec<_> = script
+            .rules
+            .iter()
+            .flat_map(|rule| Some(&rule.on).into_iter().chain(&rule.actions))
+            .collect();
+
+        // Entities spawned by the script can be referred to by their names
+        let spawned: HashSet<_> = steps
+            .iter()
+            .filter(|step| step["type"] == "spawn")
+            .filter_map(|step| step["name"].as_str())
+            .collect();
+
+        let mut refs = Vec::new();
+
+        for (idx, step) in steps.iter().enumerate() {
+            let mut add = |kind, name: &str| {
+                refs.push(Self {
+                    kind,
+                    pattern: name.to_owned(),
+                    origin: format!("{} (step #{})", path.display(), idx + 1),
+                });
+            };
+
+            for (field, kind) in [
+                ("at", "tag"),
+                ("door", "door"),
+                ("key", "key"),
+                ("mover", "mover"),
+                ("wall", "wall"),
+                ("zone", "zone"),
+            ] {
+                if let Some(name) = step[field].as_str() {
+                    add(kind, name);
+                }
+            }
+
+            for field in ["entity", "light"] {
+                let Some(name) = step[field].as_str() else { continue };
+
+                if spawned.contains(name) {
+                    continue;
+                }
+
+                // Mirrors `LevelLocator::entities()`
+                match name.split_once(':') {
+                    Some(("door", name)) => add("door", name),
+                    Some(("enemy", name)) => add("enemy", name),
+                    Some(("key", name)) => add("key", name),
+                    Some(("light", name)) => add("light", name),
+                    Some(("mover", name)) => add("mover", name),
+                    Some(("torch", name)) => add("torch", name),
+                    _ => add("entity", name),
+                }
+            }
+        }
+
+        Ok(refs)
+    }
+
+    pub fn matches(&self, name: &str) -> bool {
+        matches(&self.pattern, name)
+    }
+}
+
+/// Returns contents of the string literal `code` starts with.
+fn literal(code: &str) -> Option<&str> {
+    let code = code.strip_prefix('"')?;
+    let len = code.find('"')?;
+
+    Some(&code[..len])
+}
+
+fn is_ident(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '_'
+}
+
+/// Returns where given index of given file lies, e.g. `level2.rs:199`.
+fn origin(path: &Path, src: &str, idx: usize) -> String {
+    format!(
+        "{}:{}",
+        path.display(),
+        src[..idx].matches('\n').count() + 1
+    )
+}
+
+/// Converts `format!()`'s string into a pattern, e.g. `monster-{}` into
+/// `monster-*`.
+fn to_pattern(fmt: &str) -> String {
+    let mut pattern = String::new();
+    let mut chars = fmt.chars();
+
+    while let Some(ch) = chars.next() {
+        if ch == '{' {
+            for ch in chars.by_ref() {
+                if ch == '}' {
+                    break;
+                }
+            }
+
+            pattern.push('*');
+        } else {
+            pattern.push(ch);
+        }
+    }
+
+    pattern
+}
+
+fn matches(pattern: &str, name: &str) -> bool {
+    match pattern.split_once('*') {
+        Some((prefix, rest)) => {
+            let Some(name) = name.strip_prefix(prefix) else {
+                return false;
+            };
+
+            (0..=name.len())
+                .filter(|&idx| name.is_char_boundary(idx))
+                .any(|idx| matches(rest, &name[idx..]))
+        }
+
+        None => pattern == name,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn patterns(refs: &[Reference]) -> Vec<(&str, &str)> {
+        refs.iter().map(|r| (r.kind, r.pattern.as_str())).collect()
+    }
+
+    #[test]
+    fn pattern_from_plain_string() {
+        assert_eq!("gate", to_pattern("gate"));
+        assert_eq!("", to_pattern(""));
+    }
+
+    #[test]
+    fn pattern_from_format_string() {
+        assert_eq!("monster-*", to_pattern("monster-{}"));
+        assert_eq!("wave*.torch-*", to_pattern("wave{wave}.torch-{:02}"));
+        assert_eq!("**", to_pattern("{}{}"));
+    }
+
+    #[test]
+    fn matching_exact_names() {
+        assert!(matches("gate", "gate"));
+        assert!(!matches("gate", "gates"));
+        assert!(!matches("gate", "gat"));
+        assert!(!matches("gate", ""));
+    }
+
+    #[test]
+    fn matching_wildcards() {
+        assert!(matches("monster-*", "monster-1"));
+        assert!(matches("monster-*", "monster-"));
+        assert!(!matches("monster-*", "monsters"));
+        assert!(matches("*-spawn", "room-a-spawn"));
+        assert!(matches("wave*.torch-*", "wave2.torch-12"));
+        assert!(!matches("wave*.torch-*", "wave2.light-12"));
+        assert!(matches("*", ""));
+        assert!(matches("a*b*c", "abbbc"));
+        assert!(matches("ż*ł", "żółł"));
+    }
+
+    #[test]
+    fn zone_references_in_source() {
+        let src = r#"
+            match event {
+                LevelGameplayEvent::ZoneEntered(name)
+                    if name == "door-a" || name == "door-b" =>
+                {
+                    println!("gotcha");
+                }
+
+                LevelGameplayEvent::ZoneLeft(zone_name) => {
+                    if zone_name == "trap" {
+                        ready = true;
+                    }
+                }
+
+                LevelGameplayEvent::ZoneEntered(name) => {
+                    println!("{}", name);
+                }
+
+                LevelGameplayEvent::KeyPicked(name) if name == "key" => {
+                    //
+                }
+            }
+        "#;
+
+        let refs = Reference::from_source(Path::new("level.rs"), src);
+
+        assert_eq!(
+            vec![("zone", "door-a"), ("zone", "door-b"), ("zone", "trap")],
+            patterns(&refs)
+        );
+
+        assert_eq!("level.rs:4", refs[0].origin);
+        assert_eq!("level.rs:10", refs[2].origin);
+    }
+
+    #[test]
+    fn tag_references_in_rhai_script() {
+        let script = r#"
+            if has_tag("room-a.spawn") {
+                spawn_at("moth-monster", tag("room-a.spawn"));
+            }
+
+            let name = "room-b.spawn";
+            spawn_at("moth-monster", tag(name));
+            my_tag("not-a-tag");
+        "#;
+
+        let refs = Reference::from_rhai(Path::new("level.rhai"), script);
+
+        assert_eq!(
+            vec![("tag", "room-a.spawn"), ("tag", "room-a.spawn")],
+            patterns(&refs)
+        );
+    }
+}
@@ -0,0 +1,35 @@
+use std::fmt::Display;
+
+/// Problems found in a single map.
+///
+/// Errors are things that make the game crash (or the level impossible to
+/// finish), while warnings are things that merely look suspicious.
+#[derive(Debug, Default)]
+pub struct Report {
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+impl Report {
+    pub fn error(&mut self, msg: impl Display) {
+        self.errors.push(msg.to_string());
+    }
+
+    pub fn warn(&mut self, msg: impl Display) {
+        self.warnings.push(msg.to_string());
+    }
+
+    pub fn print(&self) {
+        for error in &self.errors {
+            println!("  error: {}", error);
+        }
+
+        for warning in &self.warnings {
+            println!("  warning: {}", warning);
+        }
+
+        if self.errors.is_empty() && self.warnings.is_empty() {
+            println!("  ok");
+        }
+    }
+}
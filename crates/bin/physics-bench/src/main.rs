@@ -0,0 +1,211 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use doome_geo::sweep::time_of_impact;
+use doome_geo::{Aabb, Circle, Grid, Polygon, Shape, CELL_SIZE};
+use doome_levels::map::Map;
+use doome_levels::tileset::Tileset;
+use glam::{vec2, vec3, Vec2, Vec3Swizzles};
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+
+/// Compares brute-force collision detection with the grid-based broad phase,
+/// by shooting bunch of bullets around given map.
+///
+/// This measures the grid only: both approaches run the same narrow phase on
+/// candidates they find, and the rest of the physics (bodies, resolving
+/// contacts, collision layers) doesn't take part at all.
+///
+/// Only the map's walls (and the bullets themselves) are taken into account,
+/// which roughly matches what happens during level6's boss fight.
+#[derive(Debug, Clone, Parser)]
+#[clap(rename_all = "kebab-case")]
+struct Args {
+    #[clap(default_value = "assets/levels/level6.tmj")]
+    map: PathBuf,
+
+    #[clap(long, default_value = "64")]
+    bullets: usize,
+
+    #[clap(long, default_value = "600")]
+    frames: usize,
+
+    #[clap(long, default_value = "0")]
+    seed: u64,
+
+    #[clap(long, default_value = "assets")]
+    assets: PathBuf,
+}
+
+#[derive(Clone, Debug)]
+struct Bullet {
+    position: Vec2,
+    velocity: Vec2,
+}
+
+/// Bullets are simulated with this step, in seconds
+const DELTA: f32 = 1.0 / 60.0;
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+
+    let tileset = args.assets.join("levels").join("tileset.tsx");
+
+    let tileset = fs::read_to_string(&tileset)
+        .with_context(|| format!("Couldn't read {}", tileset.display()))?;
+
+    let tileset = Tileset::from_tsx(&tileset);
+
+    let map = fs::read_to_string(&args.map)
+        .with_context(|| format!("Couldn't read {}", args.map.display()))?;
+
+    let map = Map::from_tmj(&map).index(&tileset).geometrize();
+
+    let walls: Vec<_> = map
+        .features()
+        .filter_map(|feature| {
+            let (model, placement) = feature.placement();
+
+            if model != "wall" {
+                return None;
+            }
+
+            let xform = placement.matrix();
+            let start = xform.transform_point3(vec3(-1.0, 0.0, 0.0));
+            let end = xform.transform_point3(vec3(1.0, 0.0, 0.0));
+
+            Some(Shape::Polygon(Polygon::line(start.xz(), end.xz())))
+        })
+        .collect();
+
+    if walls.is_empty() {
+        anyhow::bail!("Map has no walls");
+    }
+
+    let bounds = walls.iter().map(Shape::aabb).reduce(Aabb::union).unwrap();
+
+    let mut rng = SmallRng::seed_from_u64(args.seed);
+
+    let mut bullets: Vec<_> = (0..args.bullets)
+        .map(|_| Bullet {
+            position: vec2(
+                rng.gen_range(bounds.min.x..bounds.max.x),
+                rng.gen_range(bounds.min.y..bounds.max.y),
+            ),
+            velocity: Vec2::from_angle(rng.gen_range(0.0..6.28)) * 20.0,
+        })
+        .collect();
+
+    let mut grid = Grid::new(CELL_SIZE);
+
+    for (id, wall) in walls.iter().enumerate() {
+        grid.insert(id, wall.aabb());
+    }
+
+    let mut brute_time = Duration::default();
+    let mut brute_hits = 0;
+    let mut grid_time = Duration::default();
+    let mut grid_hits = 0;
+
+    for _ in 0..args.frames {
+        for bullet in &mut bullets {
+            bullet.position += bullet.velocity * DELTA;
+
+            if !bounds.contains(bullet.position) {
+                bullet.velocity = -bullet.velocity;
+            }
+        }
+
+        let shapes: Vec<_> = walls
+            .iter()
+            .cloned()
+            .chain(bullets.iter().map(Bullet::shape))
+            .collect();
+
+        // ---
+
+        let tt = Instant::now();
+
+        for (bullet_id, bullet) in bullets.iter().enumerate() {
+            let id = walls.len() + bullet_id;
+
+            brute_hits += simulate(
+                bullet,
+                &shapes[id],
+                (0..shapes.len()).filter(|&other_id| other_id != id),
+                &shapes,
+            );
+        }
+
+        brute_time += tt.elapsed();
+
+        // ---
+
+        let tt = Instant::now();
+
+        for (id, shape) in shapes.iter().enumerate().skip(walls.len()) {
+            grid.insert(id, shape.aabb());
+        }
+
+        for (bullet_id, bullet) in bullets.iter().enumerate() {
+            let id = walls.len() + bullet_id;
+            let shape = &shapes[id];
+
+            let motion = bullet.velocity * DELTA;
+            let aabb = shape.aabb();
+
+            let candidates = grid.query(Aabb::new(
+                aabb.min + motion.min(Vec2::ZERO),
+                aabb.max + motion.max(Vec2::ZERO),
+            ));
+
+            grid_hits += simulate(
+                bullet,
+                shape,
+                candidates.into_iter().filter(|&other_id| other_id != id),
+                &shapes,
+            );
+        }
+
+        grid_time += tt.elapsed();
+    }
+
+    println!("{}", args.map.display());
+    println!("  walls: {}", walls.len());
+    println!("  bullets: {}", bullets.len());
+    println!("  frames: {}", args.frames);
+    println!("  brute force: {:?} ({} hits)", brute_time, brute_hits);
+    println!("  grid: {:?} ({} hits)", grid_time, grid_hits);
+
+    if brute_hits != grid_hits {
+        anyhow::bail!("Broad phase missed some collisions");
+    }
+
+    Ok(())
+}
+
+/// Checks given bullet against given colliders, the same way
+/// `resolve_collisions` does for ethereal bodies; returns the number of hits.
+fn simulate(
+    bullet: &Bullet,
+    shape: &Shape,
+    others: impl Iterator<Item = usize>,
+    shapes: &[Shape],
+) -> usize {
+    let motion = bullet.velocity * DELTA;
+
+    others
+        .filter(|&other_id| {
+            time_of_impact(shape, motion, &shapes[other_id]).is_some()
+        })
+        .count()
+}
+
+impl Bullet {
+    fn shape(&self) -> Shape {
+        Shape::Circle(Circle::new(self.position, 0.2))
+    }
+}
@@ -0,0 +1,104 @@
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::anyhow;
+use bevy::prelude::*;
+pub use doome_debug_pass::DebugLine;
+
+use crate::convert::physical_to_graphical;
+
+/// Category of things drawn by the debug pass, each toggleable separately
+/// (see [`RenderingOptions`](crate::rendering_options::RenderingOptions)).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DebugOverlay {
+    Colliders,
+    Aabbs,
+    Contacts,
+    Raycasts,
+    NavGrid,
+    Paths,
+}
+
+impl DebugOverlay {
+    pub const ALL: [Self; 6] = [
+        Self::Colliders,
+        Self::Aabbs,
+        Self::Contacts,
+        Self::Raycasts,
+        Self::NavGrid,
+        Self::Paths,
+    ];
+}
+
+impl FromStr for DebugOverlay {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|overlay| overlay.to_string() == s)
+            .ok_or_else(|| anyhow!("Unknown debug overlay: {}", s))
+    }
+}
+
+impl fmt::Display for DebugOverlay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Colliders => "colliders",
+            Self::Aabbs => "aabbs",
+            Self::Contacts => "contacts",
+            Self::Raycasts => "raycasts",
+            Self::NavGrid => "nav-grid",
+            Self::Paths => "paths",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+/// Lines drawn by the debug pass during the current frame, in addition to the
+/// ones drawn by the engine itself - e.g. enemies' paths.
+#[derive(Resource, Default)]
+pub struct DebugLines {
+    lines: Vec<DebugLine>,
+}
+
+impl DebugLines {
+    pub fn line(&mut self, start: Vec3, end: Vec3, color: Vec3) {
+        self.lines.push(DebugLine::new(start, end, color));
+    }
+
+    /// Same as [`Self::line()`], but takes physical coordinates.
+    pub fn line_2d(&mut self, start: Vec2, end: Vec2, color: Vec3) {
+        self.line(
+            physical_to_graphical(start),
+            physical_to_graphical(end),
+            color,
+        );
+    }
+
+    /// Draws a small cross at given point, in physical coordinates.
+    pub fn point_2d(&mut self, point: Vec2, color: Vec3) {
+        const SIZE: f32 = 0.1;
+
+        self.line_2d(point - SIZE, point + SIZE, color);
+        self.line_2d(
+            point + vec2(-SIZE, SIZE),
+            point + vec2(SIZE, -SIZE),
+            color,
+        );
+    }
+
+    /// Draws a rectangle spanning given corners, in physical coordinates.
+    pub fn rect_2d(&mut self, min: Vec2, max: Vec2, color: Vec3) {
+        let points = [min, vec2(max.x, min.y), max, vec2(min.x, max.y), min];
+
+        for edge in points.windows(2) {
+            self.line_2d(edge[0], edge[1], color);
+        }
+    }
+
+    pub(crate) fn take(&mut self) -> Vec<DebugLine> {
+        std::mem::take(&mut self.lines)
+    }
+}
@@ -12,6 +12,7 @@ use rt::ShaderConstants;
 
 use crate::assets::Assets;
 use crate::components::*;
+use crate::debug::{DebugLines, DebugOverlay};
 use crate::raytracer::DoomeRaytracerPlugin;
 use crate::renderer::RendererState;
 use crate::rendering_options::RenderingOptions;
@@ -44,8 +45,11 @@ impl Plugin for DoomePlugin {
         app.insert_resource(RenderingOptions {
             sse_enabled: false,
             debug_pass_enabled: false,
+            debug_overlays: [DebugOverlay::Colliders].into_iter().collect(),
         });
 
+        app.init_resource::<DebugLines>();
+
         let assets = app.world.resource::<Assets>();
         let renderer = app.world.resource::<RendererState>();
         let windows = app.world.resource::<Windows>();
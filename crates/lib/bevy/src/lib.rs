@@ -5,9 +5,11 @@ pub mod audio;
 pub mod billboard;
 pub mod components;
 pub mod convert;
+pub mod debug;
 pub mod doome;
 pub mod health;
 pub mod model_animation;
+pub mod mover;
 pub mod nav;
 pub mod physics;
 pub mod player;
@@ -25,8 +27,12 @@ pub mod prelude {
     pub use crate::billboard::*;
     pub use crate::components::*;
     pub use crate::health::*;
+    pub use crate::mover::*;
     pub use crate::physics::components::*;
     pub use crate::physics::events::*;
+    pub use crate::physics::{
+        Floor, PhysicsQuery, QueryFilter, ShapeCastHit, Vertical,
+    };
     pub use crate::player::*;
     pub use crate::simple_animations::*;
 }
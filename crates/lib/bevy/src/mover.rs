@@ -0,0 +1,347 @@
+use std::str::FromStr;
+
+use anyhow::anyhow;
+use bevy::prelude::*;
+use doome_geo::contact::resolve_contact;
+
+use crate::convert::physical_to_graphical;
+use crate::physics::components::{Body, Collider};
+use crate::physics::PhysicsTime;
+
+/// Moves an entity through keyframes, e.g. to make an elevator, a crusher or
+/// a sliding wall.
+///
+/// Keyframes are relative to the transform the entity had when the mover got
+/// attached to it. Models moved this way must be spawned as
+/// [`GeometryType::Dynamic`](crate::components::GeometryType), since the
+/// raytracer doesn't update static geometry.
+///
+/// Kinematic bodies get pushed out of the entity's collider or - if it's a
+/// detector - carried along with it on the XZ plane; vertically, bodies with
+/// [`Vertical`](crate::physics::Vertical) get lifted (or lowered) by the
+/// entity's [`Floor`](crate::physics::Floor), if it has one.
+#[derive(Component, Clone, Debug)]
+pub struct Mover {
+    keyframes: Vec<Keyframe>,
+    mode: MoverMode,
+    easing: Easing,
+    pivot: Option<Vec3>,
+    is_active: bool,
+
+    /// Transform the keyframes are relative to; captured on the first update
+    origin: Option<Transform>,
+
+    /// Time along the keyframes, in seconds
+    tt: f32,
+    direction: f32,
+}
+
+impl Mover {
+    pub fn new() -> Self {
+        Self {
+            keyframes: vec![Keyframe {
+                translation: Vec3::ZERO,
+                angle: 0.0,
+                duration: 0.0,
+            }],
+            mode: MoverMode::Once,
+            easing: Easing::Linear,
+            pivot: None,
+            is_active: true,
+            origin: None,
+            tt: 0.0,
+            direction: 1.0,
+        }
+    }
+
+    pub fn with_keyframe(mut self, val: Keyframe) -> Self {
+        assert!(val.duration >= 0.0);
+
+        self.keyframes.push(val);
+        self
+    }
+
+    /// Moves entity to given translation (relative to its origin) without
+    /// rotating it.
+    pub fn with_waypoint(self, translation: Vec3, duration: f32) -> Self {
+        let angle = self.keyframes.last().unwrap().angle;
+
+        self.with_keyframe(Keyframe {
+            translation,
+            angle,
+            duration,
+        })
+    }
+
+    /// Keeps entity in place for given time.
+    pub fn with_pause(self, duration: f32) -> Self {
+        let last = *self.keyframes.last().unwrap();
+
+        self.with_keyframe(Keyframe { duration, ..last })
+    }
+
+    pub fn with_mode(mut self, val: MoverMode) -> Self {
+        self.mode = val;
+        self
+    }
+
+    pub fn with_easing(mut self, val: Easing) -> Self {
+        self.easing = val;
+        self
+    }
+
+    /// Sets point (in world coordinates) the rotation happens around, which
+    /// allows for many entities to rotate as one; defaults to entity's origin.
+    pub fn with_pivot(mut self, val: Vec3) -> Self {
+        self.pivot = Some(val);
+        self
+    }
+
+    pub fn with_active(mut self, val: bool) -> Self {
+        self.is_active = val;
+        self
+    }
+
+    /// Starts (or resumes) moving; in [`MoverMode::Once`], starting a mover
+    /// that has already reached its last keyframe makes it go backwards.
+    pub fn start(&mut self) {
+        self.is_active = true;
+    }
+
+    pub fn stop(&mut self) {
+        self.is_active = false;
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.is_active
+    }
+
+    fn duration(&self) -> f32 {
+        self.keyframes
+            .iter()
+            .map(|keyframe| keyframe.duration)
+            .sum()
+    }
+
+    fn advance(&mut self, dt: f32) {
+        let duration = self.duration();
+
+        self.tt += dt * self.direction;
+
+        match self.mode {
+            MoverMode::Once => {
+                if !(0.0..=duration).contains(&self.tt) {
+                    self.tt = self.tt.clamp(0.0, duration);
+                    self.is_active = false;
+                    self.direction = -self.direction;
+                }
+            }
+
+            MoverMode::Loop => {
+                if duration > 0.0 {
+                    self.tt = self.tt.rem_euclid(duration);
+                }
+            }
+
+            MoverMode::PingPong => {
+                if self.tt > duration {
+                    self.tt = (2.0 * duration - self.tt).max(0.0);
+                    self.direction = -1.0;
+                } else if self.tt < 0.0 {
+                    self.tt = (-self.tt).min(duration);
+                    self.direction = 1.0;
+                }
+            }
+        }
+    }
+
+    /// Returns translation and angle at the current time.
+    fn pose(&self) -> (Vec3, f32) {
+        let mut tt = self.tt;
+
+        for (prev, next) in self.keyframes.iter().zip(&self.keyframes[1..]) {
+            if tt <= next.duration {
+                let t = if next.duration > 0.0 {
+                    self.easing.apply(tt / next.duration)
+                } else {
+                    1.0
+                };
+
+                return (
+                    prev.translation.lerp(next.translation, t),
+                    prev.angle + (next.angle - prev.angle) * t,
+                );
+            }
+
+            tt -= next.duration;
+        }
+
+        let last = self.keyframes.last().unwrap();
+
+        (last.translation, last.angle)
+    }
+
+    fn transform(&self, origin: &Transform) -> Transform {
+        let (translation, angle) = self.pose();
+        let rotation = Quat::from_rotation_y(angle);
+        let pivot = self.pivot.unwrap_or(origin.translation);
+
+        Transform {
+            translation: pivot
+                + rotation * (origin.translation - pivot)
+                + translation,
+            rotation: rotation * origin.rotation,
+            scale: origin.scale,
+        }
+    }
+
+    pub(crate) fn animate(
+        time: Res<PhysicsTime>,
+        mut movers: Query<(&mut Self, &mut Transform, Option<&Collider>)>,
+        mut bodies: Query<
+            (&mut Body, &mut Transform, &Collider),
+            Without<Self>,
+        >,
+    ) {
+        for (mut mover, mut xform, collider) in movers.iter_mut() {
+            let origin = *mover.origin.get_or_insert(*xform);
+
+            if !mover.is_active {
+                continue;
+            }
+
+            mover.advance(time.step());
+
+            let prev_xform = *xform;
+
+            *xform = mover.transform(&origin);
+
+            let Some(collider) = collider else { continue };
+
+            let prev_shape = collider.to_shape(&prev_xform);
+            let shape = collider.to_shape(&xform);
+
+            let delta =
+                xform.compute_matrix() * prev_xform.compute_matrix().inverse();
+
+            for (mut body, mut body_xform, body_collider) in bodies.iter_mut() {
+                if !body.body_type.is_kinematic()
+                    || !collider.interacts_with(body_collider)
+                {
+                    continue;
+                }
+
+                if collider.is_detector() {
+                    let body_shape = body_collider.to_shape(&body_xform);
+
+                    if resolve_contact(&body_shape, &prev_shape).is_none() {
+                        continue;
+                    }
+
+                    let pos = delta.transform_point3(body_xform.translation);
+
+                    body_xform.translation.x = pos.x;
+                    body_xform.translation.z = pos.z;
+                } else {
+                    let body_shape = body_collider.to_shape(&body_xform);
+
+                    let Some(contact) = resolve_contact(&body_shape, &shape)
+                    else {
+                        continue;
+                    };
+
+                    let mtv = contact.mtv();
+
+                    body_xform.translation += physical_to_graphical(mtv);
+
+                    // Don't let the body keep going against the mover, since
+                    // that'd make it slip through thin colliders
+                    let against = body.velocity.dot(mtv);
+
+                    if against < 0.0 {
+                        body.velocity -= mtv * against / mtv.length_squared();
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Default for Mover {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Keyframe {
+    /// Translation relative to the entity's origin
+    pub translation: Vec3,
+
+    /// Rotation around the Y axis, in radians
+    pub angle: f32,
+
+    /// Time it takes to get here from the previous keyframe, in seconds
+    pub duration: f32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MoverMode {
+    /// Goes through the keyframes once and stops
+    Once,
+
+    /// Goes through the keyframes over and over again, jumping from the last
+    /// one back to the first one
+    Loop,
+
+    /// Goes through the keyframes back and forth
+    PingPong,
+}
+
+impl FromStr for MoverMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "once" => Ok(Self::Once),
+            "loop" => Ok(Self::Loop),
+            "ping-pong" => Ok(Self::PingPong),
+            _ => Err(anyhow!("Invalid mover mode: {s}")),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Easing {
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+}
+
+impl Easing {
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Easing::EaseInOut => t * t * (3.0 - 2.0 * t),
+        }
+    }
+}
+
+impl FromStr for Easing {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "linear" => Ok(Self::Linear),
+            "ease-in" => Ok(Self::EaseIn),
+            "ease-out" => Ok(Self::EaseOut),
+            "ease-in-out" => Ok(Self::EaseInOut),
+            _ => Err(anyhow!("Invalid easing: {s}")),
+        }
+    }
+}
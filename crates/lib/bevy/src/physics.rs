@@ -1,12 +1,23 @@
 use bevy::prelude::*;
 
+mod broad_phase;
 mod collision;
 pub mod components;
 pub mod events;
+mod interpolation;
+mod query;
 mod raycasting;
+mod time;
+mod vertical;
 
+pub use self::broad_phase::BroadPhase;
 use self::collision::resolve_collisions;
+pub use self::interpolation::Interpolated;
+pub use self::query::*;
 use self::raycasting::resolve_raycasts;
+pub use self::time::PhysicsTime;
+pub use self::vertical::*;
+use crate::mover::Mover;
 
 #[derive(Default)]
 pub struct PhysicsPlugin;
@@ -14,23 +25,76 @@ pub struct PhysicsPlugin;
 #[derive(Resource)]
 pub struct PhysicsEnabled(pub bool);
 
+/// Stage running the simulation; it's executed in fixed steps, zero or more
+/// times per frame (see [`PhysicsTime`]).
 #[derive(StageLabel, Debug, Clone, PartialEq, Eq, Hash)]
 struct PhysicsStage;
 
+#[derive(StageLabel, Debug, Clone, PartialEq, Eq, Hash)]
+struct InterpolationStage;
+
 impl Plugin for PhysicsPlugin {
     fn build(&self, app: &mut App) {
         app.add_stage_before(
             CoreStage::Update,
             PhysicsStage,
+            SystemStage::single_threaded()
+                .with_run_criteria(PhysicsTime::should_step),
+        );
+
+        app.add_stage_after(
+            PhysicsStage,
+            InterpolationStage,
             SystemStage::single_threaded(),
         );
 
         app.insert_resource(PhysicsEnabled(true));
+        app.init_resource::<BroadPhase>();
+        app.init_resource::<Floors>();
+        app.init_resource::<PhysicsTime>();
 
         app.add_event::<events::Collision>();
 
+        app.add_system_to_stage(CoreStage::PreUpdate, Interpolated::attach);
+        app.add_system_to_stage(
+            CoreStage::PreUpdate,
+            Interpolated::restore.after(Interpolated::attach),
+        );
+
         // app.add_system_to_stage(PhysicsStage, update_physics);
+        app.add_system_to_stage(
+            PhysicsStage,
+            Interpolated::begin_step.before(Mover::animate),
+        );
+        app.add_system_to_stage(
+            PhysicsStage,
+            Mover::animate.before(BroadPhase::sync),
+        );
+        app.add_system_to_stage(
+            PhysicsStage,
+            BroadPhase::sync.before(resolve_collisions),
+        );
+        app.add_system_to_stage(
+            PhysicsStage,
+            Floors::sync.after(Mover::animate).before(Vertical::resolve),
+        );
+        app.add_system_to_stage(
+            PhysicsStage,
+            Vertical::begin_step
+                .after(Mover::animate)
+                .before(resolve_collisions),
+        );
         app.add_system_to_stage(PhysicsStage, resolve_collisions);
-        app.add_system_to_stage(PhysicsStage, resolve_raycasts);
+        app.add_system_to_stage(
+            PhysicsStage,
+            Vertical::resolve.after(resolve_collisions),
+        );
+        app.add_system_to_stage(
+            PhysicsStage,
+            resolve_raycasts.after(Vertical::resolve),
+        );
+        app.add_system_to_stage(InterpolationStage, Interpolated::interpolate);
+        app.add_system_to_stage(CoreStage::Last, BroadPhase::forget_removed);
+        app.add_system_to_stage(CoreStage::Last, Floors::forget_removed);
     }
 }
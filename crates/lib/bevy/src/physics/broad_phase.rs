@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use doome_geo::{Aabb, Grid, Shape, CELL_SIZE};
+
+use super::components::Collider;
+
+/// Broad phase of the collision detection.
+///
+/// Keeps world-space shapes of all colliders (recomputed only when their
+/// transforms change) together with a grid that allows to quickly find
+/// colliders that might overlap with given area.
+#[derive(Resource)]
+pub struct BroadPhase {
+    grid: Grid<Entity>,
+    shapes: HashMap<Entity, Shape>,
+}
+
+impl Default for BroadPhase {
+    fn default() -> Self {
+        Self {
+            grid: Grid::new(CELL_SIZE),
+            shapes: Default::default(),
+        }
+    }
+}
+
+impl BroadPhase {
+    /// Returns world-space shape of given collider, as of the last update.
+    pub fn shape(&self, entity: Entity) -> Option<&Shape> {
+        self.shapes.get(&entity)
+    }
+
+    /// Returns colliders that might overlap with given area.
+    ///
+    /// Colliders that have been removed during the current frame might still
+    /// be returned here, so callers should double-check the entities.
+    pub fn query(&self, aabb: Aabb) -> Vec<Entity> {
+        self.grid.query(aabb)
+    }
+
+    /// Returns colliders that might intersect given segment, ordered (roughly)
+    /// by their distance from `start`; see [`Self::query()`] for caveats.
+    pub fn query_segment(&self, start: Vec2, end: Vec2) -> Vec<Entity> {
+        self.grid.query_segment(start, end)
+    }
+
+    pub fn len(&self) -> usize {
+        self.shapes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.shapes.is_empty()
+    }
+
+    pub(super) fn update(&mut self, entity: Entity, shape: Shape) {
+        self.grid.insert(entity, shape.aabb());
+        self.shapes.insert(entity, shape);
+    }
+
+    fn remove(&mut self, entity: Entity) {
+        self.grid.remove(entity);
+        self.shapes.remove(&entity);
+    }
+
+    pub(super) fn sync(
+        mut this: ResMut<Self>,
+        colliders: Query<
+            (Entity, &Transform, &Collider),
+            Or<(Changed<Transform>, Changed<Collider>)>,
+        >,
+    ) {
+        for (entity, transform, collider) in colliders.iter() {
+            this.update(entity, collider.to_shape(transform));
+        }
+    }
+
+    /// Forgets colliders that have been removed (or despawned) during the
+    /// current frame; runs at the very end of the frame, since that's when
+    /// Bevy clears the list of removed components.
+    pub(super) fn forget_removed(
+        mut this: ResMut<Self>,
+        removed: RemovedComponents<Collider>,
+        colliders: Query<(), With<Collider>>,
+    ) {
+        for entity in removed.iter() {
+            // Collider might've been removed and then inserted back
+            if !colliders.contains(entity) {
+                this.remove(entity);
+            }
+        }
+    }
+}
@@ -1,32 +1,48 @@
 use bevy::prelude::*;
-use doome_geo::diag;
+use doome_geo::contact::resolve_contact;
+use doome_geo::{sweep, Aabb, Shape};
 
+use super::broad_phase::BroadPhase;
 use super::components::{Body, Collider};
 use super::events::Collision;
+use super::time::PhysicsTime;
+use super::vertical::Vertical;
 use super::PhysicsEnabled;
 use crate::convert::physical_to_graphical;
 
 const MIN_VELOCITY: f32 = 0.1;
 const MIN_MTV_LENGTH_TO_DECOMPOSE: f32 = 0.001;
 
+/// Bodies hitting something slower than this don't bounce off it, so that
+/// bodies pressed against walls don't jitter.
+const MIN_BOUNCE_VELOCITY: f32 = 1.0;
+
+/// How far past the time of impact ethereal bodies are probed for contacts -
+/// at the time of impact itself, bodies only just touch.
+const CONTACT_PROBE: f32 = 0.01;
+
 pub fn resolve_collisions(
-    time: Res<Time>,
+    time: Res<PhysicsTime>,
     physics_enabled: Res<PhysicsEnabled>,
+    mut broad_phase: ResMut<BroadPhase>,
     mut collisions: EventWriter<Collision>,
-    mut bodies_with_colliders: Query<(Entity, &mut Body, &Collider)>,
-    colliders: Query<(Entity, &Collider)>,
-    transforms: Query<&mut Transform>,
+    mut bodies: Query<(Entity, &mut Body), With<Collider>>,
+    colliders: Query<&Collider>,
+    verticals: Query<(), With<Vertical>>,
+    mut transforms: Query<&mut Transform>,
 ) {
-    let delta = time.delta_seconds();
+    let delta = time.step();
 
-    for (active_entity, mut body, active_entity_collider) in
-        bodies_with_colliders.iter_mut()
-    {
-        let body = body.as_mut();
+    // Colliding bodies push each other around, so instead of iterating the
+    // query, each body gets copied out and written back when it's done
+    let active_entities: Vec<_> =
+        bodies.iter().map(|(entity, _)| entity).collect();
 
-        // SAFETY: This is safe, because we will validate that the entities are different.
+    for active_entity in active_entities {
+        let mut body = *bodies.get(active_entity).unwrap().1;
+        let active_entity_collider = colliders.get(active_entity).unwrap();
         let mut active_entity_transform =
-            unsafe { transforms.get_unchecked(active_entity).unwrap() };
+            *transforms.get(active_entity).unwrap();
 
         if active_entity_transform.translation.is_nan() {
             log::warn!("body.velocity = {:?}", body.velocity);
@@ -50,71 +66,446 @@ pub fn resolve_collisions(
         }
 
         body.velocity += body.acceleration * delta;
+        body.velocity /= 1.0 + body.linear_damping * delta;
 
-        active_entity_transform.translation +=
-            physical_to_graphical(body.velocity) * delta;
+        let motion = body.velocity * delta;
 
-        if !physics_enabled.0 {
-            continue;
-        }
+        if physics_enabled.0 && body.body_type.is_ethereal() {
+            let shape =
+                active_entity_collider.to_shape(&active_entity_transform);
 
-        for (passive_entity, passive_entity_collider) in colliders.iter() {
-            if active_entity == passive_entity {
-                continue;
-            }
+            let toi = sweep_ethereal(
+                active_entity,
+                active_entity_collider,
+                &shape,
+                motion,
+                &broad_phase,
+                &colliders,
+                &mut collisions,
+            );
+
+            active_entity_transform.translation +=
+                physical_to_graphical(motion * toi);
+
+            broad_phase.update(active_entity, shape.offset(motion * toi));
+        } else {
+            active_entity_transform.translation +=
+                physical_to_graphical(motion);
 
-            // SAFETY: This is safe, because we validate previously that the entities are different.
-            let passive_entity_transform = unsafe {
-                let passive_entity_transform =
-                    transforms.get_unchecked(passive_entity).unwrap();
+            let mut active_entity_shape =
+                active_entity_collider.to_shape(&active_entity_transform);
 
-                passive_entity_transform
+            let passive_entities = if physics_enabled.0 {
+                broad_phase.query(active_entity_shape.aabb())
+            } else {
+                Default::default()
             };
 
-            if let Some(mtv) = are_colliding(
-                &active_entity_transform,
-                active_entity_collider,
-                &passive_entity_transform,
-                passive_entity_collider,
-            ) {
+            for passive_entity in passive_entities {
+                if active_entity == passive_entity {
+                    continue;
+                }
+
+                // Broad phase might still contain colliders removed during
+                // this frame
+                let Ok(passive_entity_collider) = colliders.get(passive_entity)
+                else {
+                    continue;
+                };
+
+                if !active_entity_collider
+                    .interacts_with(passive_entity_collider)
+                {
+                    continue;
+                }
+
+                // Bodies moving vertically can get over low colliders, e.g.
+                // by jumping onto a ledge
+                if verticals.contains(active_entity) {
+                    let passive_entity_top =
+                        passive_entity_collider.height.and_then(|height| {
+                            let xform = transforms.get(passive_entity).ok()?;
+
+                            Some(xform.translation.y + height)
+                        });
+
+                    if passive_entity_top.map_or(false, |top| {
+                        active_entity_transform.translation.y >= top
+                    }) {
+                        continue;
+                    }
+                }
+
+                let Some(passive_entity_shape) =
+                    broad_phase.shape(passive_entity) else { continue };
+
+                let Some(contact) =
+                    resolve_contact(&active_entity_shape, passive_entity_shape)
+                else {
+                    continue;
+                };
+
                 collisions.send(Collision {
                     entity_a: active_entity,
                     entity_b: passive_entity,
+                    time_of_impact: None,
+                    contact: Some(contact),
                 });
 
-                if body.body_type.is_kinematic()
-                    && !passive_entity_collider.is_detector
+                if !body.body_type.is_kinematic()
+                    || passive_entity_collider.is_detector
                 {
-                    active_entity_transform.translation +=
-                        physical_to_graphical(mtv);
+                    continue;
+                }
 
-                    if mtv.length() > MIN_MTV_LENGTH_TO_DECOMPOSE {
-                        body.velocity -=
-                            vector_decomposition(body.velocity, mtv);
-                    }
+                let mut passive_body = bodies
+                    .get(passive_entity)
+                    .ok()
+                    .map(|(_, body)| *body)
+                    .filter(|body| body.body_type.is_kinematic());
+
+                let mtv = contact.mtv();
+                let share = penetration_share(&body, passive_body.as_ref());
+
+                active_entity_transform.translation +=
+                    physical_to_graphical(mtv * share);
+
+                active_entity_shape = active_entity_shape.offset(mtv * share);
+
+                if mtv.length() > MIN_MTV_LENGTH_TO_DECOMPOSE {
+                    resolve_velocities(
+                        &mut body,
+                        passive_body.as_mut(),
+                        contact.normal,
+                    );
+                }
+
+                if let Some(passive_body) = passive_body {
+                    let passive_mtv = -mtv * (1.0 - share);
+
+                    let passive_entity_shape =
+                        passive_entity_shape.clone().offset(passive_mtv);
+
+                    *bodies.get_mut(passive_entity).unwrap().1 = passive_body;
+
+                    transforms.get_mut(passive_entity).unwrap().translation +=
+                        physical_to_graphical(passive_mtv);
+
+                    broad_phase.update(passive_entity, passive_entity_shape);
                 }
             }
-        }
 
-        if body.velocity.length() < MIN_VELOCITY {
-            body.velocity = Default::default();
+            // Other bodies are going to collide with this one during this
+            // frame, so let's make sure they see where it actually is
+            broad_phase.update(active_entity, active_entity_shape);
+
+            if body.velocity.length() < MIN_VELOCITY {
+                body.velocity = Default::default();
+            }
         }
+
+        *bodies.get_mut(active_entity).unwrap().1 = body;
+        *transforms.get_mut(active_entity).unwrap() = active_entity_transform;
+    }
+}
+
+/// Returns which part of the penetration between two colliding bodies should
+/// be resolved by moving body `a` (the rest being resolved by moving body
+/// `b`), depending on their masses.
+///
+/// `b` is `None` for colliders that can't be moved (e.g. walls) - and if both
+/// bodies are immovable, `a` yields anyway so that it doesn't get stuck.
+fn penetration_share(a: &Body, b: Option<&Body>) -> f32 {
+    let inv_mass_a = a.inverse_mass();
+    let inv_mass_b = b.map_or(0.0, Body::inverse_mass);
+
+    if b.is_none() || inv_mass_a + inv_mass_b == 0.0 {
+        1.0
+    } else {
+        inv_mass_a / (inv_mass_a + inv_mass_b)
     }
 }
 
-/// Decomposes the vector c along the vectors a and a.perp, returns the component multiplied by a
-fn vector_decomposition(c: Vec2, a: Vec2) -> Vec2 {
-    a * c.dot(a) / a.length_squared()
+/// Changes velocities of two colliding bodies, so that they stop moving
+/// towards each other (or bounce off, depending on the restitution) and slow
+/// down along the contact (depending on the friction).
+///
+/// `normal` points from `b` towards `a`; `b` is `None` for colliders that
+/// can't be moved (e.g. walls), in which case only `a`'s parameters count.
+fn resolve_velocities(a: &mut Body, mut b: Option<&mut Body>, normal: Vec2) {
+    let share = penetration_share(a, b.as_deref());
+
+    let (restitution, friction) = match &b {
+        Some(b) => (
+            a.restitution.max(b.restitution),
+            (a.friction * b.friction).sqrt(),
+        ),
+        None => (a.restitution, a.friction),
+    };
+
+    let relative_velocity =
+        a.velocity - b.as_ref().map_or(Vec2::ZERO, |b| b.velocity);
+
+    let normal_velocity = relative_velocity.dot(normal);
+
+    // Bodies are separating already
+    if normal_velocity >= 0.0 {
+        return;
+    }
+
+    let restitution = if -normal_velocity < MIN_BOUNCE_VELOCITY {
+        0.0
+    } else {
+        restitution
+    };
+
+    let normal_change = -normal_velocity * (1.0 + restitution);
+
+    // Friction can slow the bodies down along the contact, but not make them
+    // go back
+    let tangent = normal.perp();
+    let tangent_velocity = relative_velocity.dot(tangent);
+
+    let tangent_change = -tangent_velocity.signum()
+        * tangent_velocity.abs().min(friction * normal_change);
+
+    let change = normal * normal_change + tangent * tangent_change;
+
+    a.velocity += change * share;
+
+    if let Some(b) = &mut b {
+        b.velocity -= change * (1.0 - share);
+    }
 }
 
-fn are_colliding(
-    transform_a: &Transform,
-    collider_a: &Collider,
-    transform_b: &Transform,
-    collider_b: &Collider,
-) -> Option<Vec2> {
-    let polygon_a = collider_a.to_polygon(transform_a);
-    let polygon_b = collider_b.to_polygon(transform_b);
+/// Moves an ethereal body (e.g. a bullet) along `motion`, reporting all of the
+/// colliders it passes through on its way - up to (and including) the first
+/// solid one, where the body stops; returns how far along the motion it got.
+///
+/// Checking the entire motion (instead of just where the body ends up) makes
+/// sure that fast bodies don't tunnel through thin walls on slow frames.
+fn sweep_ethereal(
+    entity: Entity,
+    collider: &Collider,
+    shape: &Shape,
+    motion: Vec2,
+    broad_phase: &BroadPhase,
+    colliders: &Query<&Collider>,
+    collisions: &mut EventWriter<Collision>,
+) -> f32 {
+    let aabb = shape.aabb();
+
+    let aabb = Aabb::new(
+        aabb.min + motion.min(Vec2::ZERO),
+        aabb.max + motion.max(Vec2::ZERO),
+    );
+
+    let mut hits: Vec<_> = broad_phase
+        .query(aabb)
+        .into_iter()
+        .filter(|other_entity| *other_entity != entity)
+        .filter_map(|other_entity| {
+            let other_collider = colliders.get(other_entity).ok()?;
+
+            if !collider.interacts_with(other_collider) {
+                return None;
+            }
+
+            let other_shape = broad_phase.shape(other_entity)?;
+            let toi = sweep::time_of_impact(shape, motion, other_shape)?;
+
+            Some((other_entity, other_shape, other_collider.is_detector, toi))
+        })
+        .collect();
+
+    hits.sort_by(|(.., toi_a), (.., toi_b)| toi_a.total_cmp(toi_b));
+
+    let probe = motion.normalize_or_zero() * CONTACT_PROBE;
+
+    for (other_entity, other_shape, is_detector, toi) in hits {
+        let contact = resolve_contact(
+            &shape.clone().offset(motion * toi + probe),
+            other_shape,
+        );
 
-    diag::resolve_diag(&polygon_a, &polygon_b)
+        collisions.send(Collision {
+            entity_a: entity,
+            entity_b: other_entity,
+            time_of_impact: Some(toi),
+            contact,
+        });
+
+        if !is_detector {
+            return toi;
+        }
+    }
+
+    1.0
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const PRECISION: f32 = 0.0001;
+    const UP: Vec2 = Vec2::new(0.0, 1.0);
+
+    fn assert_sim(a: Vec2, b: Vec2) {
+        assert!(
+            (a - b).length() < PRECISION,
+            "Vectors {a:?} and {b:?} should be similar"
+        );
+    }
+
+    fn assert_share(expected: f32, actual: f32) {
+        assert!(
+            (expected - actual).abs() < PRECISION,
+            "Share should be {expected}, but it's {actual}"
+        );
+    }
+
+    #[test]
+    fn share_depends_on_mass() {
+        let light = Body::kinematic();
+        let heavy = Body::kinematic().with_mass(3.0);
+
+        assert_share(0.75, penetration_share(&light, Some(&heavy)));
+        assert_share(0.25, penetration_share(&heavy, Some(&light)));
+    }
+
+    #[test]
+    fn share_against_infinite_mass() {
+        let body = Body::kinematic();
+        let wall = Body::kinematic().with_mass(f32::INFINITY);
+
+        assert_share(1.0, penetration_share(&body, Some(&wall)));
+        assert_share(0.0, penetration_share(&wall, Some(&body)));
+        assert_share(1.0, penetration_share(&body, None));
+    }
+
+    #[test]
+    fn share_between_immovable_bodies() {
+        let a = Body::kinematic().with_mass(f32::INFINITY);
+        let b = Body::kinematic().with_mass(f32::INFINITY);
+
+        assert_share(1.0, penetration_share(&a, Some(&b)));
+        assert_share(1.0, penetration_share(&a, None));
+    }
+
+    #[test]
+    fn sliding_along_wall() {
+        let mut body = Body::kinematic().with_velocity(Vec2::new(3.0, -4.0));
+
+        resolve_velocities(&mut body, None, UP);
+
+        assert_sim(Vec2::new(3.0, 0.0), body.velocity);
+    }
+
+    #[test]
+    fn sliding_along_wall_with_friction() {
+        let mut body = Body::kinematic()
+            .with_velocity(Vec2::new(3.0, -4.0))
+            .with_friction(0.5);
+
+        resolve_velocities(&mut body, None, UP);
+
+        assert_sim(Vec2::new(1.0, 0.0), body.velocity);
+    }
+
+    #[test]
+    fn bouncing_off_wall() {
+        let mut body = Body::kinematic()
+            .with_velocity(Vec2::new(0.0, -4.0))
+            .with_restitution(0.5);
+
+        resolve_velocities(&mut body, None, UP);
+
+        assert_sim(Vec2::new(0.0, 2.0), body.velocity);
+    }
+
+    #[test]
+    fn not_bouncing_below_threshold() {
+        let mut body = Body::kinematic()
+            .with_velocity(Vec2::new(0.0, -MIN_BOUNCE_VELOCITY * 0.9))
+            .with_restitution(1.0);
+
+        resolve_velocities(&mut body, None, UP);
+
+        assert_sim(Vec2::ZERO, body.velocity);
+    }
+
+    #[test]
+    fn bouncing_above_threshold() {
+        let mut body = Body::kinematic()
+            .with_velocity(Vec2::new(0.0, -MIN_BOUNCE_VELOCITY * 1.1))
+            .with_restitution(1.0);
+
+        resolve_velocities(&mut body, None, UP);
+
+        assert_sim(Vec2::new(0.0, MIN_BOUNCE_VELOCITY * 1.1), body.velocity);
+    }
+
+    #[test]
+    fn separating_bodies() {
+        let mut a = Body::kinematic().with_velocity(Vec2::new(1.0, 2.0));
+        let mut b = Body::kinematic();
+
+        resolve_velocities(&mut a, Some(&mut b), UP);
+
+        assert_sim(Vec2::new(1.0, 2.0), a.velocity);
+        assert_sim(Vec2::ZERO, b.velocity);
+    }
+
+    #[test]
+    fn elastic_collision_of_equal_masses() {
+        let mut a = Body::kinematic()
+            .with_velocity(Vec2::new(0.0, -4.0))
+            .with_restitution(1.0);
+
+        let mut b = Body::kinematic();
+
+        resolve_velocities(&mut a, Some(&mut b), UP);
+
+        assert_sim(Vec2::ZERO, a.velocity);
+        assert_sim(Vec2::new(0.0, -4.0), b.velocity);
+    }
+
+    #[test]
+    fn pushing_immovable_body() {
+        let mut a = Body::kinematic().with_velocity(Vec2::new(0.0, -4.0));
+        let mut b = Body::kinematic().with_mass(f32::INFINITY);
+
+        resolve_velocities(&mut a, Some(&mut b), UP);
+
+        assert_sim(Vec2::ZERO, a.velocity);
+        assert_sim(Vec2::ZERO, b.velocity);
+    }
+
+    #[test]
+    fn pushed_by_immovable_body() {
+        let mut a = Body::kinematic()
+            .with_velocity(Vec2::new(0.0, -4.0))
+            .with_mass(f32::INFINITY);
+
+        let mut b = Body::kinematic();
+
+        resolve_velocities(&mut a, Some(&mut b), UP);
+
+        assert_sim(Vec2::new(0.0, -4.0), a.velocity);
+        assert_sim(Vec2::new(0.0, -4.0), b.velocity);
+    }
+
+    #[test]
+    fn both_bodies_immovable() {
+        let mut a = Body::kinematic()
+            .with_velocity(Vec2::new(0.0, -4.0))
+            .with_mass(f32::INFINITY);
+
+        let mut b = Body::kinematic().with_mass(f32::INFINITY);
+
+        resolve_velocities(&mut a, Some(&mut b), UP);
+
+        assert_sim(Vec2::ZERO, a.velocity);
+        assert_sim(Vec2::ZERO, b.velocity);
+    }
 }
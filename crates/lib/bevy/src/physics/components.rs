@@ -1,5 +1,5 @@
 use bevy::prelude::*;
-use doome_geo::Polygon;
+use doome_geo::{Circle, Polygon, Shape};
 
 use crate::convert::{graphical_to_physical, physical_to_graphical};
 
@@ -7,6 +7,8 @@ use crate::convert::{graphical_to_physical, physical_to_graphical};
 pub struct RayCast {
     pub origin: Vec2,
     pub direction: Vec2,
+    /// Layers of colliders this ray can hit (see [`layer`])
+    pub mask: u32,
     pub hit: Option<RayCastHit>,
 }
 
@@ -24,49 +26,115 @@ impl RayCast {
     }
 }
 
+#[derive(Clone, Copy, Debug)]
 pub struct RayCastHit {
     pub entity: Entity,
     pub position: Vec2,
+    /// Distance from ray's origin to the hit
+    pub distance: f32,
 }
 
-/// The collider component for physics
+/// Bits used in [`CollisionLayers`]
+pub mod layer {
+    pub const PLAYER: u32 = 1 << 0;
+    pub const ENEMY: u32 = 1 << 1;
+    pub const PLAYER_PROJECTILE: u32 = 1 << 2;
+    pub const ENEMY_PROJECTILE: u32 = 1 << 3;
+    pub const WORLD: u32 = 1 << 4;
+    pub const TRIGGER: u32 = 1 << 5;
+    pub const ALL: u32 = u32::MAX;
+}
+
+/// Says which layer a collider is on and which layers it collides with.
 ///
-/// Contains a polygon shape of the collider
+/// Two colliders collide only if both of them agree to - i.e. each one's mask
+/// has to contain the other one's layer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CollisionLayers {
+    pub layer: u32,
+    pub mask: u32,
+}
+
+impl CollisionLayers {
+    pub const PLAYER: Self = Self::new(
+        layer::PLAYER,
+        layer::WORLD | layer::ENEMY | layer::ENEMY_PROJECTILE | layer::TRIGGER,
+    );
+
+    pub const ENEMY: Self = Self::new(
+        layer::ENEMY,
+        layer::WORLD
+            | layer::PLAYER
+            | layer::ENEMY
+            | layer::PLAYER_PROJECTILE
+            | layer::TRIGGER,
+    );
+
+    pub const PLAYER_PROJECTILE: Self =
+        Self::new(layer::PLAYER_PROJECTILE, layer::WORLD | layer::ENEMY);
+
+    pub const ENEMY_PROJECTILE: Self =
+        Self::new(layer::ENEMY_PROJECTILE, layer::WORLD | layer::PLAYER);
+
+    pub const WORLD: Self = Self::new(layer::WORLD, layer::ALL);
+
+    pub const TRIGGER: Self =
+        Self::new(layer::TRIGGER, layer::PLAYER | layer::ENEMY);
+
+    pub const fn new(layer: u32, mask: u32) -> Self {
+        Self { layer, mask }
+    }
+
+    pub fn interacts_with(&self, other: &Self) -> bool {
+        (self.mask & other.layer) != 0 && (other.mask & self.layer) != 0
+    }
+}
+
+impl Default for CollisionLayers {
+    fn default() -> Self {
+        Self::WORLD
+    }
+}
+
+/// The collider component for physics
 ///
-/// also contains 2 masks:
-///     - collision_mask: the mask of the collider
-///     - collides_with_mask: the mask of the colliders that this collider can collide with
+/// Contains the shape of the collider together with its collision layers,
+/// which are world (colliding with everything) by default.
 #[derive(Component, Debug)]
 pub struct Collider {
-    pub(super) polygon: Polygon,
+    pub(super) shape: Shape,
     /// Detector colliders are not solid, they only detect collisions
     pub(super) is_detector: bool,
+    pub(super) layers: CollisionLayers,
+    /// How high the collider reaches above its transform; bodies with
+    /// [`Vertical`](super::Vertical) can get over it when they're higher
+    pub(super) height: Option<f32>,
 }
 
 impl Collider {
-    pub fn circle(radius: f32, n: usize) -> Self {
-        Self {
-            polygon: Polygon::circle(radius, n),
-            is_detector: false,
-        }
+    pub fn circle(radius: f32) -> Self {
+        Self::new(Circle::new(Vec2::ZERO, radius).into())
     }
 
     pub fn rect(width: f32, height: f32) -> Self {
-        Self {
-            polygon: Polygon::rect(Vec2::new(width, height)),
-            is_detector: false,
-        }
+        Self::new(Polygon::rect(Vec2::new(width, height)).into())
     }
 
     pub fn line(start: Vec2, end: Vec2) -> Self {
+        Self::new(Polygon::line(start, end).into())
+    }
+
+    fn new(shape: Shape) -> Self {
         Self {
-            polygon: Polygon::line(start, end),
+            shape,
             is_detector: false,
+            layers: Default::default(),
+            height: None,
         }
     }
 
     pub fn offset(mut self, offset: Vec2) -> Self {
-        self.polygon = self.polygon.offset(offset);
+        self.shape = self.shape.offset(offset);
         self
     }
 
@@ -79,29 +147,134 @@ impl Collider {
         self.is_detector
     }
 
-    pub fn to_polygon(&self, transform: &Transform) -> Polygon {
-        let matrix = transform.compute_matrix();
+    pub fn with_layers(mut self, val: CollisionLayers) -> Self {
+        self.layers = val;
+        self
+    }
+
+    pub fn layers(&self) -> CollisionLayers {
+        self.layers
+    }
+
+    pub fn interacts_with(&self, other: &Self) -> bool {
+        self.layers.interacts_with(&other.layers)
+    }
+
+    pub fn with_height(mut self, val: f32) -> Self {
+        self.height = Some(val);
+        self
+    }
+
+    pub fn height(&self) -> Option<f32> {
+        self.height
+    }
 
-        self.polygon.clone().map_points(|p| {
-            graphical_to_physical(
-                matrix.transform_point3(physical_to_graphical(p)),
-            )
-        })
+    pub fn to_shape(&self, transform: &Transform) -> Shape {
+        to_world_shape(&self.shape, transform)
+    }
+
+    /// Same as [`Self::to_shape()`], but approximates circles with polygons;
+    /// useful for navigation and debug rendering.
+    pub fn to_polygon(&self, transform: &Transform) -> Polygon {
+        self.to_shape(transform).to_polygon()
     }
 }
 
-#[derive(Component)]
+/// The rigid body component for physics
+///
+/// By default bodies weigh `1.0` and slide along whatever they hit, without
+/// bouncing off it or slowing down.
+#[derive(Component, Clone, Copy, Debug)]
 pub struct Body {
     pub acceleration: Vec2,
     pub velocity: Vec2,
     pub body_type: BodyType,
+    /// Infinite mass makes the body immovable by other bodies
+    pub mass: f32,
+    /// How much of the velocity is preserved when bouncing off (`0.0` to
+    /// `1.0`)
+    pub restitution: f32,
+    /// How strongly the body resists sliding along whatever it collides with
+    pub friction: f32,
+    /// How quickly the velocity decays on its own, per second
+    pub linear_damping: f32,
 }
 
+impl Body {
+    pub fn new(body_type: BodyType) -> Self {
+        Self {
+            acceleration: Vec2::ZERO,
+            velocity: Vec2::ZERO,
+            body_type,
+            mass: 1.0,
+            restitution: 0.0,
+            friction: 0.0,
+            linear_damping: 0.0,
+        }
+    }
+
+    pub fn kinematic() -> Self {
+        Self::new(BodyType::Kinematic)
+    }
+
+    pub fn ethereal() -> Self {
+        Self::new(BodyType::Ethereal)
+    }
+
+    pub fn with_velocity(mut self, val: Vec2) -> Self {
+        self.velocity = val;
+        self
+    }
+
+    pub fn with_mass(mut self, val: f32) -> Self {
+        assert!(val > 0.0, "Body's mass must be positive");
+
+        self.mass = val;
+        self
+    }
+
+    pub fn with_restitution(mut self, val: f32) -> Self {
+        self.restitution = val;
+        self
+    }
+
+    pub fn with_friction(mut self, val: f32) -> Self {
+        self.friction = val;
+        self
+    }
+
+    pub fn with_linear_damping(mut self, val: f32) -> Self {
+        self.linear_damping = val;
+        self
+    }
+
+    /// Returns `1.0 / mass`, which is zero for immovable bodies.
+    pub fn inverse_mass(&self) -> f32 {
+        if self.mass.is_finite() {
+            1.0 / self.mass
+        } else {
+            0.0
+        }
+    }
+
+    /// Instantly changes body's momentum by `impulse` - e.g. to knock it back
+    /// after an explosion.
+    pub fn apply_impulse(&mut self, impulse: Vec2) {
+        self.velocity += impulse * self.inverse_mass();
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
 pub enum BodyType {
     /// The body is moved by the physics engine and will stop on collisions
     Kinematic,
-    /// The body is moved by the physics engine and will not stop on collisions
+    /// The body is moved by the physics engine without being pushed back by
+    /// collisions - it passes through non-solid colliders (reporting them)
+    /// and stops at the first solid one on its way.
     /// Useful for bullets & maybe enemies
+    ///
+    /// The entire motion is checked, so fast bodies don't tunnel through thin
+    /// colliders.
     Ethereal,
 }
 
@@ -114,3 +287,12 @@ impl BodyType {
         matches!(self, BodyType::Ethereal)
     }
 }
+
+/// Transforms a shape from entity's local space into the physical world.
+pub(super) fn to_world_shape(shape: &Shape, transform: &Transform) -> Shape {
+    let matrix = transform.compute_matrix();
+
+    shape.clone().map_points(|p| {
+        graphical_to_physical(matrix.transform_point3(physical_to_graphical(p)))
+    })
+}
@@ -1,7 +1,18 @@
 use bevy::prelude::Entity;
+use doome_geo::contact::Contact;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Collision {
     pub entity_a: Entity,
     pub entity_b: Entity,
+
+    /// For ethereal bodies (which are checked along their entire motion),
+    /// says at which point of the frame's motion the bodies touched - from
+    /// `0.0` (at the beginning) to `1.0` (at the end).
+    pub time_of_impact: Option<f32>,
+
+    /// Contact between the colliders, with the normal pointing towards
+    /// `entity_a`; missing for ethereal bodies that've only just touched
+    /// something.
+    pub contact: Option<Contact>,
 }
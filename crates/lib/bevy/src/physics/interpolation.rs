@@ -0,0 +1,98 @@
+use bevy::prelude::*;
+
+use super::components::Body;
+use super::time::PhysicsTime;
+use crate::mover::Mover;
+
+/// Smooths out movement of entities simulated by the physics.
+///
+/// Since physics runs at a fixed rate, different from the frame rate, the
+/// rendered translation lags slightly behind and gets interpolated between
+/// the two latest physics steps; physics itself always sees the actual one.
+///
+/// Translation set by other systems (e.g. teleporting the player) is picked up
+/// as-is, without interpolating towards it. Rotation is not interpolated, so
+/// that e.g. looking around stays immediate.
+///
+/// Attached automatically to entities with [`Body`] or [`Mover`].
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Interpolated {
+    /// Translation after the second-to-last physics step
+    previous: Vec3,
+
+    /// Translation after the last physics step
+    current: Vec3,
+
+    /// Translation we've set for rendering
+    rendered: Vec3,
+}
+
+impl Interpolated {
+    fn new(translation: Vec3) -> Self {
+        Self {
+            previous: translation,
+            current: translation,
+            rendered: translation,
+        }
+    }
+
+    pub(super) fn attach(
+        mut commands: Commands,
+        entities: Query<
+            (Entity, &Transform),
+            (Or<(Added<Body>, Added<Mover>)>, Without<Self>),
+        >,
+    ) {
+        for (entity, xform) in entities.iter() {
+            commands.entity(entity).insert(Self::new(xform.translation));
+        }
+    }
+
+    /// Returns the translation physics sees, as opposed to the interpolated
+    /// one - e.g. to spawn stuff exactly where a body has collided.
+    pub fn actual_translation(&self, xform: &Transform) -> Vec3 {
+        if xform.translation == self.rendered {
+            self.current
+        } else {
+            xform.translation
+        }
+    }
+
+    /// Brings back the actual translations before the physics kicks in.
+    pub(super) fn restore(mut entities: Query<(&mut Transform, &mut Self)>) {
+        for (mut xform, mut this) in entities.iter_mut() {
+            if xform.translation == this.rendered {
+                // (checking first, not to trigger change detection in vain)
+                if this.current != this.rendered {
+                    xform.translation = this.current;
+                }
+            } else {
+                *this = Self::new(xform.translation);
+            }
+        }
+    }
+
+    /// Remembers translations from before the upcoming physics step.
+    pub(super) fn begin_step(mut entities: Query<(&Transform, &mut Self)>) {
+        for (xform, mut this) in entities.iter_mut() {
+            this.previous = xform.translation;
+        }
+    }
+
+    /// Replaces actual translations with interpolated ones for rendering.
+    pub(super) fn interpolate(
+        time: Res<PhysicsTime>,
+        mut entities: Query<(&mut Transform, &mut Self)>,
+    ) {
+        let alpha = time.alpha();
+
+        for (mut xform, mut this) in entities.iter_mut() {
+            this.current = xform.translation;
+            this.rendered = this.previous.lerp(this.current, alpha);
+
+            if xform.translation != this.rendered {
+                xform.translation = this.rendered;
+            }
+        }
+    }
+}
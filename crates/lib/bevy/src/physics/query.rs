@@ -0,0 +1,185 @@
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use doome_geo::contact::resolve_contact;
+use doome_geo::{sweep, Aabb, Circle, Shape};
+
+use super::broad_phase::BroadPhase;
+use super::components::{layer, Collider, RayCastHit};
+
+/// Says which colliders a [`PhysicsQuery`] should consider.
+///
+/// By default queries see colliders on all layers, except for detectors.
+#[derive(Clone, Copy, Debug)]
+pub struct QueryFilter {
+    /// Layers of colliders to consider (see [`layer`])
+    pub mask: u32,
+    pub excluded: Option<Entity>,
+    pub detectors: bool,
+}
+
+impl QueryFilter {
+    pub fn new(mask: u32) -> Self {
+        Self {
+            mask,
+            excluded: None,
+            detectors: false,
+        }
+    }
+
+    /// Skips given entity, e.g. the one asking the question.
+    pub fn excluding(mut self, entity: Entity) -> Self {
+        self.excluded = Some(entity);
+        self
+    }
+
+    pub fn with_detectors(mut self) -> Self {
+        self.detectors = true;
+        self
+    }
+
+    fn matches(&self, entity: Entity, collider: &Collider) -> bool {
+        self.excluded != Some(entity)
+            && (collider.layers().layer & self.mask) != 0
+            && (self.detectors || !collider.is_detector())
+    }
+}
+
+impl Default for QueryFilter {
+    fn default() -> Self {
+        Self::new(layer::ALL)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ShapeCastHit {
+    pub entity: Entity,
+    /// How far along the motion the shape got before touching the collider,
+    /// from `0.0` to `1.0`
+    pub time_of_impact: f32,
+}
+
+/// Allows to ask the physics questions such as "what's in front of me?" on
+/// demand, without spawning [`RayCast`](super::components::RayCast)s.
+///
+/// Everything here is in physical coordinates and sees colliders as of the
+/// latest physics step.
+#[derive(SystemParam)]
+pub struct PhysicsQuery<'w, 's> {
+    broad_phase: Res<'w, BroadPhase>,
+    colliders: Query<'w, 's, &'static Collider>,
+}
+
+impl<'w, 's> PhysicsQuery<'w, 's> {
+    /// Returns the closest collider hit by a ray going from `origin` in
+    /// direction `dir`, up to `max` units away.
+    pub fn raycast(
+        &self,
+        origin: Vec2,
+        dir: Vec2,
+        max: f32,
+        filter: QueryFilter,
+    ) -> Option<RayCastHit> {
+        self.raycast_all(origin, dir, max, filter)
+            .into_iter()
+            .next()
+    }
+
+    /// Returns all colliders hit by a ray going from `origin` in direction
+    /// `dir`, up to `max` units away, ordered from the closest one.
+    pub fn raycast_all(
+        &self,
+        origin: Vec2,
+        dir: Vec2,
+        max: f32,
+        filter: QueryFilter,
+    ) -> Vec<RayCastHit> {
+        let ray = dir.normalize_or_zero() * max;
+
+        let mut hits: Vec<_> = self
+            .candidates(
+                self.broad_phase.query_segment(origin, origin + ray),
+                filter,
+            )
+            .filter_map(|(entity, shape)| {
+                let position = shape.raycast(origin, ray)?;
+
+                Some(RayCastHit {
+                    entity,
+                    position,
+                    distance: origin.distance(position),
+                })
+            })
+            .collect();
+
+        hits.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+        hits
+    }
+
+    /// Returns all colliders overlapping given circle.
+    pub fn overlap_circle(
+        &self,
+        center: Vec2,
+        radius: f32,
+        filter: QueryFilter,
+    ) -> Vec<Entity> {
+        self.overlap_shape(&Circle::new(center, radius).into(), filter)
+    }
+
+    /// Returns all colliders overlapping given shape.
+    pub fn overlap_shape(
+        &self,
+        shape: &Shape,
+        filter: QueryFilter,
+    ) -> Vec<Entity> {
+        self.candidates(self.broad_phase.query(shape.aabb()), filter)
+            .filter(|(_, other)| resolve_contact(shape, other).is_some())
+            .map(|(entity, _)| entity)
+            .collect()
+    }
+
+    /// Returns the first collider that given shape would touch if it moved
+    /// by `motion`.
+    pub fn shape_cast(
+        &self,
+        shape: &Shape,
+        motion: Vec2,
+        filter: QueryFilter,
+    ) -> Option<ShapeCastHit> {
+        let aabb = shape.aabb();
+
+        let aabb = Aabb::new(
+            aabb.min + motion.min(Vec2::ZERO),
+            aabb.max + motion.max(Vec2::ZERO),
+        );
+
+        self.candidates(self.broad_phase.query(aabb), filter)
+            .filter_map(|(entity, other)| {
+                let time_of_impact =
+                    sweep::time_of_impact(shape, motion, other)?;
+
+                Some(ShapeCastHit {
+                    entity,
+                    time_of_impact,
+                })
+            })
+            .min_by(|a, b| a.time_of_impact.total_cmp(&b.time_of_impact))
+    }
+
+    fn candidates(
+        &self,
+        entities: Vec<Entity>,
+        filter: QueryFilter,
+    ) -> impl Iterator<Item = (Entity, &Shape)> + '_ {
+        entities.into_iter().filter_map(move |entity| {
+            // Broad phase might still contain colliders removed during this
+            // frame
+            let collider = self.colliders.get(entity).ok()?;
+
+            if !filter.matches(entity, collider) {
+                return None;
+            }
+
+            Some((entity, self.broad_phase.shape(entity)?))
+        })
+    }
+}
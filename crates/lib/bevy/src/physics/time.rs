@@ -0,0 +1,79 @@
+use bevy::ecs::schedule::ShouldRun;
+use bevy::prelude::*;
+
+/// How many times per second the physics gets simulated
+const STEPS_PER_SECOND: f32 = 120.0;
+
+/// Upper limit of steps simulated during a single frame; on slower machines
+/// the game slows down instead of spending more and more time on catching up.
+const MAX_STEPS_PER_FRAME: u32 = 8;
+
+/// Clock of the physics, which - contrary to [`Time`] - advances in fixed
+/// steps, so that the simulation doesn't depend on the frame rate.
+#[derive(Resource, Debug)]
+pub struct PhysicsTime {
+    step: f32,
+    accumulator: f32,
+    steps_this_frame: u32,
+    is_stepping: bool,
+}
+
+impl Default for PhysicsTime {
+    fn default() -> Self {
+        Self {
+            step: 1.0 / STEPS_PER_SECOND,
+            accumulator: 0.0,
+            steps_this_frame: 0,
+            is_stepping: false,
+        }
+    }
+}
+
+impl PhysicsTime {
+    /// Duration of a single physics step, in seconds.
+    pub fn step(&self) -> f32 {
+        self.step
+    }
+
+    /// How far (from `0.0` to `1.0`) we're between the last physics step and
+    /// the next one; used to interpolate transforms for rendering.
+    pub fn alpha(&self) -> f32 {
+        self.accumulator / self.step
+    }
+
+    /// Run criteria of the physics stage - keeps running it as long as there
+    /// are full steps left to simulate.
+    pub(super) fn should_step(
+        time: Res<Time>,
+        mut this: ResMut<Self>,
+    ) -> ShouldRun {
+        if !this.is_stepping {
+            this.accumulator += time.delta_seconds();
+            this.steps_this_frame = 0;
+        }
+
+        if this.accumulator >= this.step {
+            if this.steps_this_frame >= MAX_STEPS_PER_FRAME {
+                log::warn!(
+                    "Physics is falling behind; dropping {:.3}s",
+                    this.accumulator
+                );
+
+                this.accumulator %= this.step;
+                this.is_stepping = false;
+
+                return ShouldRun::No;
+            }
+
+            this.accumulator -= this.step;
+            this.steps_this_frame += 1;
+            this.is_stepping = true;
+
+            ShouldRun::YesAndCheckAgain
+        } else {
+            this.is_stepping = false;
+
+            ShouldRun::No
+        }
+    }
+}
@@ -0,0 +1,446 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use doome_geo::{Polygon, Shape};
+
+use super::broad_phase::BroadPhase;
+use super::components::{to_world_shape, Body, Collider};
+use super::time::PhysicsTime;
+use super::PhysicsEnabled;
+use crate::convert::{graphical_to_physical, physical_to_graphical};
+
+/// Default downwards acceleration, in units per second squared
+pub const GRAVITY: f32 = 20.0;
+
+/// Default height of a floor's edge which bodies can walk onto without
+/// jumping; matches steps considered passable by the maps
+pub const STEP_HEIGHT: f32 = 0.5;
+
+/// Opts a body into moving along the vertical axis - falling down with the
+/// gravity, jumping, and standing on [`Floor`]s.
+///
+/// Body's position on the vertical axis is its transform's `y`, which is
+/// where its feet are; the rest of the physics (walls, other bodies) stays 2D,
+/// except that the body can get over colliders lower than itself (see
+/// [`Collider::with_height()`]).
+///
+/// Places without any floor underneath are pits - body falls into them
+/// indefinitely, it's up to the game to decide when it's gone.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Vertical {
+    /// Vertical velocity, positive going up
+    pub velocity: f32,
+    pub gravity: f32,
+    /// How high the body can walk onto without jumping
+    pub step_height: f32,
+    on_ground: bool,
+    /// Position on the XZ plane from before the latest physics step
+    last_position: Vec2,
+}
+
+impl Vertical {
+    pub fn new() -> Self {
+        Self {
+            velocity: 0.0,
+            gravity: GRAVITY,
+            step_height: STEP_HEIGHT,
+            on_ground: false,
+            last_position: Vec2::ZERO,
+        }
+    }
+
+    pub fn with_gravity(mut self, val: f32) -> Self {
+        self.gravity = val;
+        self
+    }
+
+    pub fn with_step_height(mut self, val: f32) -> Self {
+        self.step_height = val;
+        self
+    }
+
+    pub fn is_on_ground(&self) -> bool {
+        self.on_ground
+    }
+
+    /// Makes the body jump with given initial speed; returns `false` if the
+    /// body can't jump at the moment, because it's midair.
+    pub fn jump(&mut self, speed: f32) -> bool {
+        if !self.on_ground {
+            return false;
+        }
+
+        self.velocity = speed;
+        self.on_ground = false;
+
+        true
+    }
+
+    /// Remembers positions from before the upcoming physics step.
+    pub(super) fn begin_step(mut bodies: Query<(&Transform, &mut Self)>) {
+        for (xform, mut this) in bodies.iter_mut() {
+            this.last_position = graphical_to_physical(xform.translation);
+        }
+    }
+
+    /// Moves bodies along the vertical axis, after they've been moved on the
+    /// XZ plane.
+    pub(super) fn resolve(
+        time: Res<PhysicsTime>,
+        physics_enabled: Res<PhysicsEnabled>,
+        mut broad_phase: ResMut<BroadPhase>,
+        floors: Res<Floors>,
+        mut bodies: Query<(
+            Entity,
+            &mut Self,
+            &mut Transform,
+            Option<&mut Body>,
+            Option<&Collider>,
+        )>,
+    ) {
+        if !physics_enabled.0 {
+            return;
+        }
+
+        let delta = time.step();
+
+        for (entity, mut this, mut xform, body, collider) in bodies.iter_mut() {
+            let position = graphical_to_physical(xform.translation);
+            let y = xform.translation.y;
+
+            let ground = match Ground::find(&floors, position, y, &this) {
+                Ground::TooHigh(_) if position != this.last_position => {
+                    // Floor at the new position is too high to walk onto, so
+                    // it's like a wall
+                    let revert = this.last_position - position;
+
+                    xform.translation += physical_to_graphical(revert);
+
+                    if let Some(mut body) = body {
+                        body.velocity = Vec2::ZERO;
+                    }
+
+                    if let Some(collider) = collider {
+                        broad_phase.update(entity, collider.to_shape(&xform));
+                    }
+
+                    Ground::find(&floors, this.last_position, y, &this).height()
+                }
+
+                ground => ground.height(),
+            };
+
+            let new_y = this.fall(y, ground, delta);
+
+            if xform.translation.y != new_y {
+                xform.translation.y = new_y;
+            }
+        }
+    }
+
+    /// Applies gravity to the body standing (or flying) at `y` above `ground`
+    /// (`None` meaning a pit) and returns body's `y` after the step.
+    fn fall(&mut self, y: f32, ground: Option<f32>, delta: f32) -> f32 {
+        self.velocity -= self.gravity * delta;
+
+        let mut new_y = y + self.velocity * delta;
+
+        if let Some(ground) = ground {
+            // Sticking to the ground, so that walking down the stairs or
+            // standing on a descending platform doesn't turn into a series of
+            // tiny falls
+            let snaps = self.on_ground
+                && self.velocity <= 0.0
+                && ground >= y - self.step_height;
+
+            if new_y <= ground || snaps {
+                new_y = ground;
+                self.velocity = 0.0;
+            }
+
+            self.on_ground = new_y <= ground;
+        } else {
+            self.on_ground = false;
+        }
+
+        new_y
+    }
+}
+
+impl Default for Vertical {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// World-space shapes of all [`Floor`]s, recomputed only when their
+/// transforms change.
+///
+/// Unlike colliders, floors are not put into a grid - there are just a few of
+/// them per level, but they tend to be huge.
+#[derive(Resource, Default)]
+pub(super) struct Floors {
+    floors: HashMap<Entity, (Shape, f32)>,
+}
+
+impl Floors {
+    pub(super) fn sync(
+        mut this: ResMut<Self>,
+        floors: Query<
+            (Entity, &Transform, &Floor),
+            (Or<(Changed<Transform>, Changed<Floor>)>, Without<Vertical>),
+        >,
+    ) {
+        for (entity, transform, floor) in floors.iter() {
+            this.floors.insert(entity, floor.to_world(transform));
+        }
+    }
+
+    /// Forgets floors that have been removed (or despawned) during the current
+    /// frame; see [`BroadPhase::forget_removed()`].
+    pub(super) fn forget_removed(
+        mut this: ResMut<Self>,
+        removed: RemovedComponents<Floor>,
+        floors: Query<(), With<Floor>>,
+    ) {
+        for entity in removed.iter() {
+            if !floors.contains(entity) {
+                this.floors.remove(&entity);
+            }
+        }
+    }
+}
+
+/// Surface which bodies with [`Vertical`] can stand on.
+///
+/// Floor's shape is expressed in the entity's local space (just like for
+/// [`Collider`]) and it lies at the transform's `y`, plus an optional offset.
+#[derive(Component, Clone, Debug)]
+pub struct Floor {
+    shape: Shape,
+    offset: f32,
+}
+
+impl Floor {
+    pub fn rect(width: f32, height: f32) -> Self {
+        Self {
+            shape: Polygon::rect(Vec2::new(width, height)).into(),
+            offset: 0.0,
+        }
+    }
+
+    pub fn with_offset(mut self, val: f32) -> Self {
+        self.offset = val;
+        self
+    }
+
+    fn to_world(&self, transform: &Transform) -> (Shape, f32) {
+        (
+            to_world_shape(&self.shape, transform),
+            transform.translation.y + self.offset,
+        )
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Ground {
+    /// Height of the floor the body can stand on
+    Reachable(f32),
+
+    /// There are floors at this point, but they are too high to walk onto;
+    /// contains the lowest of them
+    TooHigh(f32),
+
+    Pit,
+}
+
+impl Ground {
+    fn find(floors: &Floors, point: Vec2, y: f32, body: &Vertical) -> Self {
+        let mut reachable: Option<f32> = None;
+        let mut too_high: Option<f32> = None;
+
+        for (shape, height) in floors.floors.values() {
+            if !shape.contains(point) {
+                continue;
+            }
+
+            if *height <= y + body.step_height {
+                reachable = Some(reachable.map_or(*height, |h| h.max(*height)));
+            } else {
+                too_high = Some(too_high.map_or(*height, |h| h.min(*height)));
+            }
+        }
+
+        match (reachable, too_high) {
+            (Some(height), _) => Self::Reachable(height),
+            (None, Some(height)) => Self::TooHigh(height),
+            (None, None) => Self::Pit,
+        }
+    }
+
+    /// Returns height the body should land on; when the body is already
+    /// below the floors (e.g. because it's been teleported there), it gets
+    /// lifted onto them.
+    fn height(&self) -> Option<f32> {
+        match self {
+            Self::Reachable(height) | Self::TooHigh(height) => Some(*height),
+            Self::Pit => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const PRECISION: f32 = 0.0001;
+    const DELTA: f32 = 1.0 / 120.0;
+
+    fn assert_sim(a: f32, b: f32) {
+        assert!(
+            (a - b).abs() < PRECISION,
+            "Heights {a} and {b} should be similar"
+        );
+    }
+
+    /// Returns floors made of 2x2 squares centered at given points, lying at
+    /// given heights.
+    fn floors(floors: &[(Vec2, f32)]) -> Floors {
+        let floors = floors
+            .iter()
+            .enumerate()
+            .map(|(id, &(center, height))| {
+                let shape = Polygon::rect(Vec2::ONE).offset(center).into();
+
+                (Entity::from_raw(id as u32), (shape, height))
+            })
+            .collect();
+
+        Floors { floors }
+    }
+
+    fn standing() -> Vertical {
+        let mut body = Vertical::new();
+        body.on_ground = true;
+        body
+    }
+
+    #[test]
+    fn ground_at_reachable_floor() {
+        let floors = floors(&[(Vec2::ZERO, 0.0)]);
+        let ground = Ground::find(&floors, Vec2::ZERO, 0.0, &Vertical::new());
+
+        assert_eq!(Ground::Reachable(0.0), ground);
+    }
+
+    #[test]
+    fn ground_at_reachable_step() {
+        let floors = floors(&[(Vec2::ZERO, 0.0), (Vec2::ZERO, 0.5)]);
+        let ground = Ground::find(&floors, Vec2::ZERO, 0.0, &Vertical::new());
+
+        assert_eq!(Ground::Reachable(0.5), ground);
+    }
+
+    #[test]
+    fn ground_at_too_high_step() {
+        let floors = floors(&[(Vec2::ZERO, 1.0), (Vec2::ZERO, 2.0)]);
+        let ground = Ground::find(&floors, Vec2::ZERO, 0.0, &Vertical::new());
+
+        assert_eq!(Ground::TooHigh(1.0), ground);
+
+        let ground = Ground::find(
+            &floors,
+            Vec2::ZERO,
+            0.0,
+            &Vertical::new().with_step_height(1.0),
+        );
+
+        assert_eq!(Ground::Reachable(1.0), ground);
+    }
+
+    #[test]
+    fn ground_at_pit() {
+        let floors = floors(&[(Vec2::ZERO, 0.0), (Vec2::new(5.0, 0.0), 0.0)]);
+        let ground =
+            Ground::find(&floors, Vec2::new(2.5, 0.0), 0.0, &Vertical::new());
+
+        assert_eq!(Ground::Pit, ground);
+        assert_eq!(None, ground.height());
+    }
+
+    #[test]
+    fn landing() {
+        let mut body = Vertical::new();
+        body.velocity = -10.0;
+
+        let y = body.fall(0.05, Some(0.0), DELTA);
+
+        assert_sim(0.0, y);
+        assert_sim(0.0, body.velocity);
+        assert!(body.is_on_ground());
+    }
+
+    #[test]
+    fn falling_onto_ground() {
+        let mut body = Vertical::new();
+
+        let y = body.fall(1.0, Some(0.0), DELTA);
+
+        assert!(y < 1.0 && y > 0.0);
+        assert!(!body.is_on_ground());
+    }
+
+    #[test]
+    fn falling_into_pit() {
+        let mut body = standing();
+
+        let y = body.fall(0.0, None, DELTA);
+
+        assert!(y < 0.0);
+        assert!(body.velocity < 0.0);
+        assert!(!body.is_on_ground());
+    }
+
+    #[test]
+    fn snapping_down_step() {
+        let mut body = standing();
+
+        let y = body.fall(0.5, Some(0.0), DELTA);
+
+        assert_sim(0.0, y);
+        assert!(body.is_on_ground());
+    }
+
+    #[test]
+    fn not_snapping_down_cliff() {
+        let mut body = standing();
+
+        let y = body.fall(1.0, Some(0.0), DELTA);
+
+        assert!(y > 0.0);
+        assert!(!body.is_on_ground());
+    }
+
+    #[test]
+    fn not_snapping_while_jumping() {
+        let mut body = standing();
+
+        assert!(body.jump(5.0));
+
+        let y = body.fall(0.0, Some(0.0), DELTA);
+
+        assert!(y > 0.0);
+        assert!(!body.is_on_ground());
+        assert!(!body.jump(5.0));
+    }
+
+    #[test]
+    fn lifting_onto_floor_below() {
+        let mut body = standing();
+
+        let y = body.fall(-0.25, Some(0.0), DELTA);
+
+        assert_sim(0.0, y);
+        assert!(body.is_on_ground());
+    }
+}
@@ -13,12 +13,28 @@ use self::geometry_manager::*;
 use self::materials_manager::*;
 use crate::assets::{AssetHandle, Assets, Model};
 use crate::components::*;
-use crate::convert::physical_to_graphical;
+use crate::debug::{DebugLines, DebugOverlay};
 use crate::doome::DoomeRenderer;
-use crate::physics::components::Collider;
+use crate::physics::components::{Collider, RayCast};
+use crate::physics::events::Collision;
+use crate::physics::BroadPhase;
 use crate::renderer::RendererState;
 use crate::rendering_options::RenderingOptions;
 
+const DEBUG_COLLIDER_COLOR: Vec3 = vec3(1.0, 0.0, 0.0);
+const DEBUG_DETECTOR_COLOR: Vec3 = vec3(1.0, 1.0, 0.0);
+const DEBUG_AABB_COLOR: Vec3 = vec3(0.0, 0.5, 1.0);
+const DEBUG_NORMAL_COLOR: Vec3 = vec3(0.0, 1.0, 0.0);
+const DEBUG_MTV_COLOR: Vec3 = vec3(1.0, 0.0, 1.0);
+const DEBUG_RAYCAST_COLOR: Vec3 = vec3(0.0, 1.0, 1.0);
+const DEBUG_HIT_COLOR: Vec3 = vec3(1.0, 1.0, 1.0);
+
+/// Length of contact normals drawn by the debug pass
+const DEBUG_NORMAL_LENGTH: f32 = 0.5;
+
+/// MTVs are usually tiny, so the debug pass draws them magnified
+const DEBUG_MTV_SCALE: f32 = 10.0;
+
 pub struct DoomeRaytracerPlugin;
 
 #[derive(StageLabel)]
@@ -82,7 +98,7 @@ impl Plugin for DoomeRaytracerPlugin {
         );
         app.add_system_to_stage(
             DoomeRaytracingStage::Update,
-            update_collider_data_for_debug_pass,
+            update_debug_pass_data,
         );
 
         app.add_system_to_stage(DoomeRaytracingStage::Update, sync_lights);
@@ -274,32 +290,87 @@ fn sync_camera(
     }
 }
 
-fn update_collider_data_for_debug_pass(
+fn update_debug_pass_data(
     rendering_options: Res<RenderingOptions>,
     renderer: Res<DoomeRenderer>,
     renderer_state: Res<RendererState>,
-    colliders: Query<(&Collider, &Transform)>,
+    broad_phase: Res<BroadPhase>,
+    mut debug_lines: ResMut<DebugLines>,
+    mut collisions: EventReader<Collision>,
+    colliders: Query<(Entity, &Collider, &Transform)>,
+    raycasts: Query<(&RayCast, &Transform)>,
 ) {
+    // (taking the lines even if we're not going to draw them, so that they
+    // don't pile up)
+    let mut lines = std::mem::take(&mut *debug_lines);
+
     if !rendering_options.debug_pass_enabled {
         return;
     }
 
     let queue = &renderer_state.queue;
 
-    let mut lines = vec![];
+    if rendering_options.shows_overlay(DebugOverlay::Colliders) {
+        for (_, collider, transform) in colliders.iter() {
+            let polygon = collider.to_polygon(transform);
+
+            let color = if collider.is_detector() {
+                DEBUG_DETECTOR_COLOR
+            } else {
+                DEBUG_COLLIDER_COLOR
+            };
 
-    for (collider, transform) in colliders.iter() {
-        let polygon = collider.to_polygon(transform);
+            for (start, end) in polygon.iter_edges() {
+                lines.line_2d(start, end, color);
+            }
+        }
+    }
 
-        for (start, end) in polygon.iter_edges() {
-            lines.push(physical_to_graphical(start));
-            lines.push(physical_to_graphical(end));
+    if rendering_options.shows_overlay(DebugOverlay::Aabbs) {
+        for (entity, ..) in colliders.iter() {
+            if let Some(shape) = broad_phase.shape(entity) {
+                let aabb = shape.aabb();
+
+                lines.rect_2d(aabb.min, aabb.max, DEBUG_AABB_COLOR);
+            }
+        }
+    }
+
+    if rendering_options.shows_overlay(DebugOverlay::Contacts) {
+        for contact in collisions.iter().filter_map(|c| c.contact) {
+            for point in contact.points() {
+                lines.point_2d(*point, DEBUG_HIT_COLOR);
+
+                lines.line_2d(
+                    *point,
+                    *point + contact.normal * DEBUG_NORMAL_LENGTH,
+                    DEBUG_NORMAL_COLOR,
+                );
+
+                lines.line_2d(
+                    *point,
+                    *point + contact.mtv() * DEBUG_MTV_SCALE,
+                    DEBUG_MTV_COLOR,
+                );
+            }
+        }
+    }
+
+    if rendering_options.shows_overlay(DebugOverlay::Raycasts) {
+        for (raycast, transform) in raycasts.iter() {
+            let (origin, dir) =
+                raycast.transformed_origin_and_dir(&transform.compute_matrix());
+
+            if let Some(hit) = &raycast.hit {
+                lines.line_2d(origin, hit.position, DEBUG_RAYCAST_COLOR);
+                lines.point_2d(hit.position, DEBUG_HIT_COLOR);
+            } else {
+                lines.line_2d(origin, origin + dir, DEBUG_RAYCAST_COLOR);
+            }
         }
     }
 
-    renderer
-        .debug_pass
-        .update_data(queue, &lines, lines.len() / 2);
+    renderer.debug_pass.update_data(queue, &lines.take());
 }
 
 fn render(
@@ -1,7 +1,19 @@
+use std::collections::HashSet;
+
 use bevy::prelude::Resource;
 
+use crate::debug::DebugOverlay;
+
 #[derive(Resource)]
 pub struct RenderingOptions {
     pub sse_enabled: bool,
     pub debug_pass_enabled: bool,
+    pub debug_overlays: HashSet<DebugOverlay>,
+}
+
+impl RenderingOptions {
+    /// Returns whether given overlay should be drawn by the debug pass.
+    pub fn shows_overlay(&self, overlay: DebugOverlay) -> bool {
+        self.debug_pass_enabled && self.debug_overlays.contains(&overlay)
+    }
 }
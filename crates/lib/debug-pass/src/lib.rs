@@ -5,6 +5,22 @@ use doome_wgpu_ext::AllocatedUniform;
 use glam::{Mat4, Vec3};
 use wgpu::util::DeviceExt;
 
+/// Maximum number of lines drawn at once; the rest gets skipped.
+const MAX_LINES: usize = 8192;
+
+#[derive(Clone, Copy, Debug)]
+pub struct DebugLine {
+    pub start: Vec3,
+    pub end: Vec3,
+    pub color: Vec3,
+}
+
+impl DebugLine {
+    pub fn new(start: Vec3, end: Vec3, color: Vec3) -> Self {
+        Self { start, end, color }
+    }
+}
+
 pub struct DebugPass {
     pub render_pipeline: wgpu::RenderPipeline,
     projection: AllocatedUniform<Projection>,
@@ -32,16 +48,22 @@ impl DebugPass {
                 push_constant_ranges: &[],
             });
 
+        // Each vertex consists of its position and color
         let vertex_buffer_layout = wgpu::VertexBufferLayout {
-            array_stride: std::mem::size_of::<[f32; 3]>() as u64,
+            array_stride: std::mem::size_of::<[[f32; 3]; 2]>() as u64,
             step_mode: wgpu::VertexStepMode::Vertex,
-            attributes: &wgpu::vertex_attr_array![0 => Float32x3],
+            attributes: &wgpu::vertex_attr_array![
+                0 => Float32x3,
+                1 => Float32x3
+            ],
         };
 
         let vertex_buffer =
             device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
                 label: Some("debug_vertex_buffer"),
-                contents: bytemuck::cast_slice(&[[-1.0, -1.0, 0.0]; 4056]),
+                contents: bytemuck::cast_slice(
+                    &[[[-1.0, -1.0, 0.0], [0.0, 0.0, 0.0]]; MAX_LINES * 2],
+                ),
                 usage: wgpu::BufferUsages::VERTEX
                     | wgpu::BufferUsages::COPY_DST,
             });
@@ -85,15 +107,22 @@ impl DebugPass {
         self.projection.write0(queue, &Projection::new(view_proj));
     }
 
-    pub fn update_data(
-        &self,
-        queue: &wgpu::Queue,
-        data: &[Vec3],
-        num_lines: usize,
-    ) {
+    pub fn update_data(&self, queue: &wgpu::Queue, lines: &[DebugLine]) {
+        let lines = &lines[..lines.len().min(MAX_LINES)];
+
+        let data: Vec<_> = lines
+            .iter()
+            .flat_map(|line| {
+                [
+                    [line.start.to_array(), line.color.to_array()],
+                    [line.end.to_array(), line.color.to_array()],
+                ]
+            })
+            .collect();
+
         queue.write_buffer(&self.vertex_buffer, 0, bytemuck::cast_slice(&data));
         self.num_lines
-            .store(num_lines, std::sync::atomic::Ordering::Relaxed);
+            .store(lines.len(), std::sync::atomic::Ordering::Relaxed);
     }
 
     pub fn render(
@@ -0,0 +1,70 @@
+use glam::Vec2;
+
+/// Axis-aligned bounding box
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Aabb {
+    pub min: Vec2,
+    pub max: Vec2,
+}
+
+impl Aabb {
+    pub fn new(min: Vec2, max: Vec2) -> Self {
+        Self { min, max }
+    }
+
+    pub fn from_points(points: impl IntoIterator<Item = Vec2>) -> Self {
+        points.into_iter().fold(
+            Self::new(Vec2::splat(f32::MAX), Vec2::splat(f32::MIN)),
+            |aabb, point| Self::new(aabb.min.min(point), aabb.max.max(point)),
+        )
+    }
+
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.min.x <= other.max.x
+            && other.min.x <= self.max.x
+            && self.min.y <= other.max.y
+            && other.min.y <= self.max.y
+    }
+
+    pub fn contains(&self, point: Vec2) -> bool {
+        point.cmpge(self.min).all() && point.cmple(self.max).all()
+    }
+
+    pub fn grow(self, by: f32) -> Self {
+        Self::new(self.min - by, self.max + by)
+    }
+
+    pub fn union(self, other: Self) -> Self {
+        Self::new(self.min.min(other.min), self.max.max(other.max))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use glam::vec2;
+
+    use super::*;
+
+    #[test]
+    fn intersection() {
+        let a = Aabb::new(vec2(0.0, 0.0), vec2(2.0, 2.0));
+
+        assert!(a.intersects(&Aabb::new(vec2(1.0, 1.0), vec2(3.0, 3.0))));
+        assert!(a.intersects(&Aabb::new(vec2(2.0, 0.0), vec2(3.0, 1.0))));
+        assert!(!a.intersects(&Aabb::new(vec2(2.5, 0.0), vec2(3.0, 1.0))));
+        assert!(!a.intersects(&Aabb::new(vec2(0.0, -2.0), vec2(2.0, -0.5))));
+    }
+
+    #[test]
+    fn from_points() {
+        let aabb = Aabb::from_points([
+            vec2(1.0, -1.0),
+            vec2(-2.0, 3.0),
+            vec2(0.5, 0.5),
+        ]);
+
+        assert_eq!(Aabb::new(vec2(-2.0, -1.0), vec2(1.0, 3.0)), aabb);
+        assert!(aabb.contains(vec2(0.0, 0.0)));
+        assert!(!aabb.contains(vec2(0.0, 3.5)));
+    }
+}
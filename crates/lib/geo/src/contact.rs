@@ -0,0 +1,408 @@
+use glam::Vec2;
+
+use crate::{Circle, Polygon, Shape};
+
+/// How far below the reference face a point can be and still count as a
+/// contact point; absorbs floating-point errors on resting contacts.
+const CONTACT_TOLERANCE: f32 = 0.001;
+
+/// Narrow-phase result of two overlapping shapes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Contact {
+    /// Direction in which the first shape has to be moved to get out of the
+    /// second one (i.e. pointing from the second shape towards the first one)
+    pub normal: Vec2,
+
+    /// How deep the shapes overlap along `normal`
+    pub depth: f32,
+
+    points: [Vec2; 2],
+    len: usize,
+}
+
+impl Contact {
+    fn new(
+        normal: Vec2,
+        depth: f32,
+        points: impl IntoIterator<Item = Vec2>,
+    ) -> Self {
+        let mut this = Self {
+            normal,
+            depth,
+            points: Default::default(),
+            len: 0,
+        };
+
+        for point in points.into_iter().take(2) {
+            this.points[this.len] = point;
+            this.len += 1;
+        }
+
+        this
+    }
+
+    /// Minimum translation vector - adding it to the first shape resolves the
+    /// collision.
+    pub fn mtv(&self) -> Vec2 {
+        self.normal * self.depth
+    }
+
+    /// Points (one or two) where the shapes touch, in world coordinates.
+    pub fn points(&self) -> &[Vec2] {
+        &self.points[..self.len]
+    }
+
+    fn flip(mut self) -> Self {
+        self.normal = -self.normal;
+        self
+    }
+}
+
+/// Resolves collision between two shapes; returns `None` if they don't
+/// overlap.
+///
+/// Polygons (including lines) are checked using the separating axis theorem,
+/// with contact points found by clipping the incident edge against the
+/// reference one; circles are handled analytically.
+pub fn resolve_contact(a: &Shape, b: &Shape) -> Option<Contact> {
+    match (a, b) {
+        (Shape::Polygon(a), Shape::Polygon(b)) => polygon_polygon(a, b),
+        (Shape::Circle(a), Shape::Polygon(b)) => circle_polygon(a, b),
+        (Shape::Polygon(a), Shape::Circle(b)) => {
+            circle_polygon(b, a).map(Contact::flip)
+        }
+        (Shape::Circle(a), Shape::Circle(b)) => circle_circle(a, b),
+    }
+}
+
+fn polygon_polygon(a: &Polygon, b: &Polygon) -> Option<Contact> {
+    // (normal, depth, whether the axis comes from `a`)
+    let mut best: Option<(Vec2, f32, bool)> = None;
+
+    for (polygon, is_a) in [(a, true), (b, false)] {
+        for axis in polygon.iter_edge_vectors() {
+            let Some(axis) = axis.perp().try_normalize() else { continue };
+
+            let (a_min, a_max) = project(a, axis);
+            let (b_min, b_max) = project(b, axis);
+
+            // How far `a` has to be moved along / against the axis to get out
+            let along = b_max - a_min;
+            let against = a_max - b_min;
+
+            if along <= 0.0 || against <= 0.0 {
+                return None;
+            }
+
+            let (normal, depth) = if along < against {
+                (axis, along)
+            } else {
+                (-axis, against)
+            };
+
+            if best.map_or(true, |(_, best_depth, _)| depth < best_depth) {
+                best = Some((normal, depth, is_a));
+            }
+        }
+    }
+
+    let (normal, depth, is_a) = best?;
+
+    let points = if is_a {
+        clip(a, b, -normal)
+    } else {
+        clip(b, a, normal)
+    };
+
+    Some(Contact::new(normal, depth, points))
+}
+
+/// Finds contact points by clipping the incident polygon's edge against the
+/// reference polygon's face pointing towards `ref_normal`.
+fn clip(
+    reference: &Polygon,
+    incident: &Polygon,
+    ref_normal: Vec2,
+) -> Vec<Vec2> {
+    let ref_edge = best_edge(reference, |n| n.dot(ref_normal));
+    let inc_edge = best_edge(incident, |n| -n.dot(ref_normal));
+
+    let (Some(ref_edge), Some(inc_edge)) = (ref_edge, inc_edge) else {
+        return Default::default();
+    };
+
+    let tangent = (ref_edge.1 - ref_edge.0).normalize();
+    let ref_len = (ref_edge.1 - ref_edge.0).length();
+
+    let mut points = vec![inc_edge.0, inc_edge.1];
+
+    for (offset, dir) in [(0.0, tangent), (-ref_len, -tangent)] {
+        points = clip_segment(&points, |p| (p - ref_edge.0).dot(dir) - offset);
+    }
+
+    let points: Vec<_> = points
+        .into_iter()
+        .filter(|p| (*p - ref_edge.0).dot(ref_normal) <= CONTACT_TOLERANCE)
+        .collect();
+
+    if points.is_empty() {
+        // Might happen due to floating-point errors on grazing contacts
+        vec![support(incident, -ref_normal)]
+    } else {
+        points
+    }
+}
+
+/// Keeps the part of the segment where `dist(point) >= 0`.
+fn clip_segment(points: &[Vec2], dist: impl Fn(Vec2) -> f32) -> Vec<Vec2> {
+    let [p0, p1] = points else { return points.to_vec() };
+    let (d0, d1) = (dist(*p0), dist(*p1));
+
+    let mut out = Vec::with_capacity(2);
+
+    if d0 >= 0.0 {
+        out.push(*p0);
+    }
+
+    if d1 >= 0.0 {
+        out.push(*p1);
+    }
+
+    if d0 * d1 < 0.0 {
+        out.push(*p0 + (*p1 - *p0) * (d0 / (d0 - d1)));
+    }
+
+    out
+}
+
+/// Returns the edge whose outward normal maximizes given score.
+fn best_edge(
+    polygon: &Polygon,
+    score: impl Fn(Vec2) -> f32,
+) -> Option<(Vec2, Vec2)> {
+    let center = polygon.avg();
+
+    polygon
+        .iter_edges()
+        .filter_map(|edge| {
+            let mut normal = (edge.1 - edge.0).perp().try_normalize()?;
+
+            // Lines have their center on the edge, but there each of the two
+            // edges already has the opposite normal
+            if normal.dot((edge.0 + edge.1) / 2.0 - center) < 0.0 {
+                normal = -normal;
+            }
+
+            Some((edge, score(normal)))
+        })
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(edge, _)| edge)
+}
+
+/// Returns polygon's point that's the furthest along given direction.
+fn support(polygon: &Polygon, dir: Vec2) -> Vec2 {
+    polygon
+        .points()
+        .iter()
+        .copied()
+        .max_by(|a, b| a.dot(dir).total_cmp(&b.dot(dir)))
+        .unwrap()
+}
+
+fn circle_polygon(circle: &Circle, polygon: &Polygon) -> Option<Contact> {
+    let (closest, edge) = polygon
+        .iter_edges()
+        .map(|edge| (closest_point(circle.center, edge), edge))
+        .min_by(|(a, _), (b, _)| {
+            a.distance_squared(circle.center)
+                .total_cmp(&b.distance_squared(circle.center))
+        })?;
+
+    let dist = closest.distance(circle.center);
+
+    if polygon.contains(circle.center) {
+        let normal = (closest - circle.center)
+            .try_normalize()
+            .unwrap_or_else(|| edge_normal(polygon, edge));
+
+        return Some(Contact::new(normal, circle.radius + dist, [closest]));
+    }
+
+    if dist >= circle.radius {
+        return None;
+    }
+
+    let normal = (circle.center - closest)
+        .try_normalize()
+        .unwrap_or_else(|| -edge_normal(polygon, edge));
+
+    Some(Contact::new(normal, circle.radius - dist, [closest]))
+}
+
+fn circle_circle(a: &Circle, b: &Circle) -> Option<Contact> {
+    let dist = a.center.distance(b.center);
+    let depth = a.radius + b.radius - dist;
+
+    if depth <= 0.0 {
+        return None;
+    }
+
+    let normal = (a.center - b.center).try_normalize().unwrap_or(Vec2::X);
+
+    Some(Contact::new(normal, depth, [b.center + normal * b.radius]))
+}
+
+fn project(polygon: &Polygon, axis: Vec2) -> (f32, f32) {
+    polygon
+        .points()
+        .iter()
+        .map(|point| point.dot(axis))
+        .fold((f32::MAX, f32::MIN), |(min, max), proj| {
+            (min.min(proj), max.max(proj))
+        })
+}
+
+fn closest_point(point: Vec2, (start, end): (Vec2, Vec2)) -> Vec2 {
+    let dir = end - start;
+    let len_sq = dir.length_squared();
+
+    if len_sq == 0.0 {
+        return start;
+    }
+
+    start + dir * ((point - start).dot(dir) / len_sq).clamp(0.0, 1.0)
+}
+
+/// Returns edge's normal pointing inside the polygon.
+fn edge_normal(polygon: &Polygon, (start, end): (Vec2, Vec2)) -> Vec2 {
+    let normal = (end - start).perp().normalize_or_zero();
+
+    if normal.dot(polygon.avg() - start) < 0.0 {
+        -normal
+    } else {
+        normal
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use glam::vec2;
+
+    use super::*;
+
+    const PRECISION: f32 = 0.0001;
+
+    fn assert_sim(a: Vec2, b: Vec2) {
+        assert!(
+            (a - b).length() < PRECISION,
+            "Vectors {a:?} and {b:?} should be similar"
+        );
+    }
+
+    fn sorted(points: &[Vec2]) -> Vec<Vec2> {
+        let mut points = points.to_vec();
+        points.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+        points
+    }
+
+    #[test]
+    fn rect_on_rect() {
+        let a = Shape::Polygon(
+            Polygon::rect(vec2(1.0, 1.0)).offset(vec2(0.0, 1.8)),
+        );
+
+        let b = Shape::Polygon(Polygon::rect(vec2(2.0, 1.0)));
+        let contact = resolve_contact(&a, &b).unwrap();
+
+        assert_sim(vec2(0.0, 1.0), contact.normal);
+        assert!((contact.depth - 0.2).abs() < PRECISION);
+
+        let points = sorted(contact.points());
+
+        assert_eq!(2, points.len());
+        assert_sim(vec2(-1.0, 1.0), points[0]);
+        assert_sim(vec2(1.0, 1.0), points[1]);
+
+        let contact = resolve_contact(&b, &a).unwrap();
+
+        assert_sim(vec2(0.0, -1.0), contact.normal);
+        assert_eq!(2, contact.points().len());
+    }
+
+    #[test]
+    fn rect_on_line() {
+        let rect = Shape::Polygon(
+            Polygon::rect(vec2(0.5, 0.5)).offset(vec2(0.0, 0.3)),
+        );
+
+        let line =
+            Shape::Polygon(Polygon::line(vec2(-10.0, 0.0), vec2(10.0, 0.0)));
+
+        let contact = resolve_contact(&rect, &line).unwrap();
+
+        assert_sim(vec2(0.0, 1.0), contact.normal);
+        assert!((contact.depth - 0.2).abs() < PRECISION);
+
+        let points = sorted(contact.points());
+
+        assert_eq!(2, points.len());
+        assert_sim(vec2(-0.5, 0.0), points[0]);
+        assert_sim(vec2(0.5, 0.0), points[1]);
+    }
+
+    #[test]
+    fn circle_on_line() {
+        let line =
+            Shape::Polygon(Polygon::line(vec2(0.0, -1.0), vec2(0.0, 1.0)));
+
+        let circle = Shape::Circle(Circle::new(vec2(1.0, -0.25), 0.5));
+
+        assert_eq!(None, resolve_contact(&circle, &line));
+
+        let circle = Shape::Circle(Circle::new(vec2(0.3, 0.0), 0.5));
+        let contact = resolve_contact(&circle, &line).unwrap();
+
+        assert_sim(vec2(1.0, 0.0), contact.normal);
+        assert!((contact.depth - 0.2).abs() < PRECISION);
+        assert_eq!(&[vec2(0.0, 0.0)], contact.points());
+
+        let contact = resolve_contact(&line, &circle).unwrap();
+
+        assert_sim(vec2(-1.0, 0.0), contact.normal);
+    }
+
+    #[test]
+    fn circle_in_rect() {
+        let circle = Shape::Circle(Circle::new(vec2(0.8, 0.0), 0.5));
+        let rect = Shape::Polygon(Polygon::rect(vec2(1.0, 1.0)));
+        let contact = resolve_contact(&circle, &rect).unwrap();
+
+        assert_sim(vec2(1.0, 0.0), contact.normal);
+        assert!((contact.depth - 0.7).abs() < PRECISION);
+        assert_eq!(&[vec2(1.0, 0.0)], contact.points());
+    }
+
+    #[test]
+    fn circle_on_circle() {
+        let a = Shape::Circle(Circle::new(vec2(0.0, 1.5), 1.0));
+        let b = Shape::Circle(Circle::new(vec2(0.0, 0.0), 1.0));
+        let contact = resolve_contact(&a, &b).unwrap();
+
+        assert_sim(vec2(0.0, 1.0), contact.normal);
+        assert!((contact.depth - 0.5).abs() < PRECISION);
+        assert_eq!(&[vec2(0.0, 1.0)], contact.points());
+
+        assert_eq!(None, resolve_contact(&a.offset(Vec2::Y), &b));
+    }
+
+    #[test]
+    fn separated() {
+        let a = Shape::Polygon(
+            Polygon::rect(vec2(1.0, 1.0)).offset(vec2(2.5, 0.0)),
+        );
+
+        let b = Shape::Polygon(Polygon::rect(vec2(1.0, 1.0)));
+
+        assert_eq!(None, resolve_contact(&a, &b));
+    }
+}
@@ -0,0 +1,323 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+use glam::Vec2;
+
+use crate::Aabb;
+
+/// Cell size the game's broad phase uses, in world units; a bit larger than
+/// our biggest bodies, so that they don't span too many cells.
+pub const CELL_SIZE: f32 = 4.0;
+
+/// Uniform grid used as the broad phase of collision detection.
+///
+/// Each item is put into all of the cells its bounding box overlaps, so that
+/// looking for items that might collide with something boils down to checking
+/// a few nearby cells instead of all of the items.
+#[derive(Clone, Debug)]
+pub struct Grid<T> {
+    cell_size: f32,
+    cells: HashMap<(i32, i32), Vec<(T, Aabb)>>,
+    items: HashMap<T, Aabb>,
+}
+
+impl<T> Grid<T>
+where
+    T: Copy + Eq + Hash,
+{
+    pub fn new(cell_size: f32) -> Self {
+        assert!(cell_size > 0.0);
+
+        Self {
+            cell_size,
+            cells: Default::default(),
+            items: Default::default(),
+        }
+    }
+
+    /// Inserts item or, if it's already present, updates its bounding box.
+    pub fn insert(&mut self, item: T, aabb: Aabb) {
+        if let Some(prev_aabb) = self.items.insert(item, aabb) {
+            // Most updates come from things moving just a bit, so it's worth
+            // to avoid re-bucketing the item if possible
+            if self.cell_range(prev_aabb) == self.cell_range(aabb) {
+                for cell in self.cells_of(aabb) {
+                    if let Some(entry) =
+                        self.cells.get_mut(&cell).and_then(|items| {
+                            items.iter_mut().find(|(other, _)| *other == item)
+                        })
+                    {
+                        entry.1 = aabb;
+                    }
+                }
+
+                return;
+            }
+
+            self.remove_from_cells(item, prev_aabb);
+        }
+
+        for cell in self.cells_of(aabb) {
+            self.cells.entry(cell).or_default().push((item, aabb));
+        }
+    }
+
+    pub fn remove(&mut self, item: T) -> bool {
+        if let Some(aabb) = self.items.remove(&item) {
+            self.remove_from_cells(item, aabb);
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn get(&self, item: T) -> Option<Aabb> {
+        self.items.get(&item).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn items(&self) -> impl Iterator<Item = T> + '_ {
+        self.items.keys().copied()
+    }
+
+    /// Returns items whose bounding boxes intersect given one.
+    pub fn query(&self, aabb: Aabb) -> Vec<T> {
+        let (query_min, _) = self.cell_range(aabb);
+
+        self.cells_of(aabb)
+            .filter_map(|cell| Some((cell, self.cells.get(&cell)?)))
+            .flat_map(|(cell, items)| {
+                items.iter().filter_map(move |(item, item_aabb)| {
+                    if !item_aabb.intersects(&aabb) {
+                        return None;
+                    }
+
+                    // Items spanning many cells are reported only once, from
+                    // the first cell shared with the query
+                    let (item_min, _) = self.cell_range(*item_aabb);
+
+                    let first_cell = (
+                        item_min.0.max(query_min.0),
+                        item_min.1.max(query_min.1),
+                    );
+
+                    (cell == first_cell).then_some(*item)
+                })
+            })
+            .collect()
+    }
+
+    /// Returns items whose bounding boxes might intersect segment going from
+    /// `start` to `end`, ordered (roughly) by their distance from `start`.
+    pub fn query_segment(&self, start: Vec2, end: Vec2) -> Vec<T> {
+        let segment_aabb = Aabb::from_points([start, end]);
+        let mut seen = HashSet::new();
+
+        self.traverse(start, end)
+            .into_iter()
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .filter(|(_, item_aabb)| item_aabb.intersects(&segment_aabb))
+            .filter(|(item, _)| seen.insert(*item))
+            .map(|(item, _)| *item)
+            .collect()
+    }
+
+    fn remove_from_cells(&mut self, item: T, aabb: Aabb) {
+        for cell in self.cells_of(aabb) {
+            if let Some(items) = self.cells.get_mut(&cell) {
+                items.retain(|(candidate, _)| *candidate != item);
+
+                if items.is_empty() {
+                    self.cells.remove(&cell);
+                }
+            }
+        }
+    }
+
+    fn cell_of(&self, point: Vec2) -> (i32, i32) {
+        let cell = (point / self.cell_size).floor();
+
+        (cell.x as i32, cell.y as i32)
+    }
+
+    fn cell_range(&self, aabb: Aabb) -> ((i32, i32), (i32, i32)) {
+        (self.cell_of(aabb.min), self.cell_of(aabb.max))
+    }
+
+    fn cells_of(&self, aabb: Aabb) -> impl Iterator<Item = (i32, i32)> {
+        let ((x1, y1), (x2, y2)) = self.cell_range(aabb);
+
+        (x1..=x2).flat_map(move |x| (y1..=y2).map(move |y| (x, y)))
+    }
+
+    /// Returns cells crossed by given segment, in order.
+    ///
+    /// http://www.cse.yorku.ca/~amana/research/grid.pdf
+    fn traverse(&self, start: Vec2, end: Vec2) -> Vec<(i32, i32)> {
+        let mut cell = self.cell_of(start);
+        let last = self.cell_of(end);
+        let dir = end - start;
+
+        let step = (dir.x.signum() as i32, dir.y.signum() as i32);
+
+        let boundary = |pos: f32, cell: i32, dir: f32| {
+            if dir > 0.0 {
+                (cell + 1) as f32 * self.cell_size - pos
+            } else {
+                cell as f32 * self.cell_size - pos
+            }
+        };
+
+        let mut t_max = Vec2::new(
+            if dir.x != 0.0 {
+                boundary(start.x, cell.0, dir.x) / dir.x
+            } else {
+                f32::INFINITY
+            },
+            if dir.y != 0.0 {
+                boundary(start.y, cell.1, dir.y) / dir.y
+            } else {
+                f32::INFINITY
+            },
+        );
+
+        // (zero-length components yield infinities, which is what we want)
+        let t_delta = Vec2::splat(self.cell_size) / dir.abs();
+
+        let steps = (last.0 - cell.0).abs() + (last.1 - cell.1).abs();
+        let mut cells = Vec::with_capacity(steps as usize + 1);
+
+        cells.push(cell);
+
+        for _ in 0..steps {
+            // Floating-point errors could make us overshoot an axis, so once
+            // we're at the last cell along it, we just go along the other one
+            let go_x = if cell.0 == last.0 {
+                false
+            } else if cell.1 == last.1 {
+                true
+            } else {
+                t_max.x < t_max.y
+            };
+
+            if go_x {
+                cell.0 += step.0;
+                t_max.x += t_delta.x;
+            } else {
+                cell.1 += step.1;
+                t_max.y += t_delta.y;
+            }
+
+            cells.push(cell);
+        }
+
+        cells
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use glam::vec2;
+
+    use super::*;
+
+    fn square(center: Vec2, half_size: f32) -> Aabb {
+        Aabb::new(center - half_size, center + half_size)
+    }
+
+    fn sorted(mut items: Vec<u32>) -> Vec<u32> {
+        items.sort();
+        items
+    }
+
+    #[test]
+    fn query() {
+        let mut grid = Grid::new(2.0);
+
+        grid.insert(1, square(vec2(0.0, 0.0), 0.5));
+        grid.insert(2, square(vec2(1.5, 0.5), 0.5));
+        grid.insert(3, square(vec2(10.0, 10.0), 0.5));
+        grid.insert(4, Aabb::new(vec2(-10.0, -0.1), vec2(10.0, 0.1)));
+
+        assert_eq!(
+            vec![1, 2, 4],
+            sorted(grid.query(square(vec2(0.5, 0.5), 0.6)))
+        );
+
+        assert_eq!(vec![3], grid.query(square(vec2(9.0, 9.0), 0.6)));
+        assert_eq!(vec![4], grid.query(square(vec2(-9.0, 0.0), 0.5)));
+        assert!(grid.query(square(vec2(-9.0, 5.0), 0.5)).is_empty());
+    }
+
+    #[test]
+    fn update_and_remove() {
+        let mut grid = Grid::new(2.0);
+
+        grid.insert(1, square(vec2(0.0, 0.0), 0.5));
+        grid.insert(1, square(vec2(0.2, 0.0), 0.5));
+
+        assert_eq!(vec![1], grid.query(square(vec2(0.6, 0.0), 0.1)));
+
+        grid.insert(1, square(vec2(20.0, 0.0), 0.5));
+
+        assert!(grid.query(square(vec2(0.0, 0.0), 1.0)).is_empty());
+        assert_eq!(vec![1], grid.query(square(vec2(20.0, 0.0), 1.0)));
+
+        assert!(grid.remove(1));
+        assert!(!grid.remove(1));
+        assert!(grid.is_empty());
+        assert!(grid.cells.is_empty());
+    }
+
+    #[test]
+    fn traverse() {
+        let grid = Grid::<u32>::new(1.0);
+
+        assert_eq!(
+            vec![(0, 0), (1, 0), (2, 0), (3, 0)],
+            grid.traverse(vec2(0.5, 0.5), vec2(3.5, 0.5))
+        );
+
+        assert_eq!(
+            vec![(0, 0), (0, -1), (0, -2)],
+            grid.traverse(vec2(0.5, 0.5), vec2(0.5, -1.5))
+        );
+
+        assert_eq!(
+            vec![(0, 0), (0, 1), (1, 1), (2, 1), (2, 2)],
+            grid.traverse(vec2(0.1, 0.5), vec2(2.9, 2.5))
+        );
+
+        assert_eq!(
+            vec![(-1, -1), (-1, -2), (-2, -2)],
+            grid.traverse(vec2(-0.5, -0.9), vec2(-1.5, -1.5))
+        );
+    }
+
+    #[test]
+    fn query_segment() {
+        let mut grid = Grid::new(1.0);
+
+        grid.insert(1, square(vec2(5.0, 0.5), 0.5));
+        grid.insert(2, square(vec2(2.0, 0.5), 0.5));
+        grid.insert(3, square(vec2(2.0, 3.5), 0.5));
+
+        assert_eq!(
+            vec![2, 1],
+            grid.query_segment(vec2(0.5, 0.5), vec2(8.5, 0.5))
+        );
+
+        assert_eq!(vec![2], grid.query_segment(vec2(0.5, 0.5), vec2(3.0, 0.5)));
+        assert!(grid
+            .query_segment(vec2(0.5, 2.0), vec2(8.5, 2.0))
+            .is_empty());
+    }
+}
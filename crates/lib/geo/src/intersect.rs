@@ -2,6 +2,12 @@ use glam::Vec2;
 
 // https://stackoverflow.com/a/565282
 pub fn intersect(p: Vec2, r: Vec2, q: Vec2, s: Vec2) -> Option<Vec2> {
+    intersect_t(p, r, q, s).map(|t| p + r * t)
+}
+
+/// Same as [`intersect()`], but returns how far along `r` the intersection
+/// happens (`0.0` = at `p`, `1.0` = at `p + r`).
+pub fn intersect_t(p: Vec2, r: Vec2, q: Vec2, s: Vec2) -> Option<f32> {
     let rs = cross(r, s);
 
     let qp = q - p;
@@ -20,7 +26,7 @@ pub fn intersect(p: Vec2, r: Vec2, q: Vec2, s: Vec2) -> Option<Vec2> {
     } else if rs != 0.0 && (0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u)
     {
         // The vectors intersect
-        Some(p + r * t)
+        Some(t)
     } else {
         // Lines intersect, but vectors do not
         None
@@ -1,6 +1,14 @@
+mod aabb;
+pub mod contact;
 pub mod diag;
+mod grid;
 pub mod intersect;
 mod polygon;
 pub mod sat;
+mod shape;
+pub mod sweep;
 
+pub use self::aabb::Aabb;
+pub use self::grid::{Grid, CELL_SIZE};
 pub use self::polygon::Polygon;
+pub use self::shape::{Circle, Shape};
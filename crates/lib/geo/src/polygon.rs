@@ -2,6 +2,8 @@ use std::f32::consts::PI;
 
 use glam::Vec2;
 
+use crate::Aabb;
+
 /// A 2D convex polygon
 #[derive(Debug, Clone)]
 pub struct Polygon {
@@ -68,6 +70,10 @@ impl Polygon {
         &self.points
     }
 
+    pub fn aabb(&self) -> Aabb {
+        Aabb::from_points(self.points.iter().copied())
+    }
+
     /// Iterates over "edge vectors" i.e. 2d vectors derived from edges.
     pub fn iter_edge_vectors(&self) -> impl Iterator<Item = Vec2> + '_ {
         self.iter_edges().map(|(a, b)| b - a)
@@ -84,6 +90,22 @@ impl Polygon {
         })
     }
 
+    /// Returns whether given point lies inside (or on the boundary of) this
+    /// polygon, which is assumed to be convex.
+    pub fn contains(&self, point: Vec2) -> bool {
+        if self.points.len() < 3 {
+            return false;
+        }
+
+        let sides: Vec<_> = self
+            .iter_edges()
+            .map(|(start, end)| (end - start).perp_dot(point - start))
+            .collect();
+
+        sides.iter().all(|side| *side >= 0.0)
+            || sides.iter().all(|side| *side <= 0.0)
+    }
+
     pub fn num_edges(&self) -> usize {
         self.points.len()
     }
@@ -0,0 +1,206 @@
+use glam::Vec2;
+
+use crate::intersect::intersect;
+use crate::{Aabb, Polygon};
+
+/// Number of points used when a circle has to be approximated with a polygon
+/// (e.g. for navigation or debug rendering).
+const CIRCLE_POINTS: usize = 16;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Circle {
+    pub center: Vec2,
+    pub radius: f32,
+}
+
+impl Circle {
+    pub fn new(center: Vec2, radius: f32) -> Self {
+        Self { center, radius }
+    }
+}
+
+/// Shape of a collider - either a convex polygon (with lines being polygons
+/// of two points) or a circle.
+#[derive(Clone, Debug)]
+pub enum Shape {
+    Polygon(Polygon),
+    Circle(Circle),
+}
+
+impl Shape {
+    pub fn aabb(&self) -> Aabb {
+        match self {
+            Shape::Polygon(polygon) => polygon.aabb(),
+            Shape::Circle(circle) => Aabb::new(
+                circle.center - circle.radius,
+                circle.center + circle.radius,
+            ),
+        }
+    }
+
+    pub fn center(&self) -> Vec2 {
+        match self {
+            Shape::Polygon(polygon) => polygon.avg(),
+            Shape::Circle(circle) => circle.center,
+        }
+    }
+
+    pub fn offset(self, offset: Vec2) -> Self {
+        match self {
+            Shape::Polygon(polygon) => Shape::Polygon(polygon.offset(offset)),
+
+            Shape::Circle(circle) => Shape::Circle(Circle::new(
+                circle.center + offset,
+                circle.radius,
+            )),
+        }
+    }
+
+    /// Transforms the shape point-by-point; circles are assumed to be scaled
+    /// uniformly.
+    pub fn map_points<F>(self, m: F) -> Self
+    where
+        F: Fn(Vec2) -> Vec2,
+    {
+        match self {
+            Shape::Polygon(polygon) => Shape::Polygon(polygon.map_points(m)),
+
+            Shape::Circle(circle) => {
+                let center = m(circle.center);
+                let edge = m(circle.center + Vec2::X * circle.radius);
+
+                Shape::Circle(Circle::new(center, center.distance(edge)))
+            }
+        }
+    }
+
+    /// Returns whether given point lies inside (or on the boundary of) this
+    /// shape.
+    pub fn contains(&self, point: Vec2) -> bool {
+        match self {
+            Shape::Polygon(polygon) => polygon.contains(point),
+            Shape::Circle(circle) => {
+                circle.center.distance_squared(point) <= circle.radius.powi(2)
+            }
+        }
+    }
+
+    /// Returns the shape as a polygon, approximating circles.
+    pub fn to_polygon(&self) -> Polygon {
+        match self {
+            Shape::Polygon(polygon) => polygon.clone(),
+
+            Shape::Circle(circle) => {
+                Polygon::circle(circle.radius, CIRCLE_POINTS)
+                    .offset(circle.center)
+            }
+        }
+    }
+
+    /// Returns the closest point where ray going from `origin` to
+    /// `origin + dir` hits this shape's boundary.
+    pub fn raycast(&self, origin: Vec2, dir: Vec2) -> Option<Vec2> {
+        match self {
+            Shape::Polygon(polygon) => polygon
+                .iter_edges()
+                .filter_map(|edge| {
+                    intersect(origin, dir, edge.0, edge.1 - edge.0)
+                })
+                .reduce(|curr, next| {
+                    if curr.distance(origin) < next.distance(origin) {
+                        curr
+                    } else {
+                        next
+                    }
+                }),
+
+            Shape::Circle(circle) => {
+                // Solving `|origin + dir * t - center| = radius` for `t`
+                let oc = origin - circle.center;
+                let a = dir.length_squared();
+                let b = 2.0 * oc.dot(dir);
+                let c = oc.length_squared() - circle.radius.powi(2);
+                let delta = b * b - 4.0 * a * c;
+
+                if a == 0.0 || delta < 0.0 {
+                    return None;
+                }
+
+                let t1 = (-b - delta.sqrt()) / (2.0 * a);
+                let t2 = (-b + delta.sqrt()) / (2.0 * a);
+
+                [t1, t2]
+                    .into_iter()
+                    .find(|t| (0.0..=1.0).contains(t))
+                    .map(|t| origin + dir * t)
+            }
+        }
+    }
+}
+
+impl From<Polygon> for Shape {
+    fn from(polygon: Polygon) -> Self {
+        Shape::Polygon(polygon)
+    }
+}
+
+impl From<Circle> for Shape {
+    fn from(circle: Circle) -> Self {
+        Shape::Circle(circle)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use glam::vec2;
+
+    use super::*;
+
+    #[test]
+    fn raycast_circle() {
+        let circle = Shape::Circle(Circle::new(vec2(5.0, 0.0), 1.0));
+
+        assert_eq!(
+            Some(vec2(4.0, 0.0)),
+            circle.raycast(vec2(0.0, 0.0), vec2(10.0, 0.0))
+        );
+
+        assert_eq!(
+            Some(vec2(6.0, 0.0)),
+            circle.raycast(vec2(5.0, 0.0), vec2(10.0, 0.0))
+        );
+
+        assert_eq!(None, circle.raycast(vec2(0.0, 0.0), vec2(3.0, 0.0)));
+        assert_eq!(None, circle.raycast(vec2(0.0, 2.0), vec2(10.0, 0.0)));
+    }
+
+    #[test]
+    fn contains() {
+        let rect = Shape::Polygon(Polygon::rect(vec2(1.0, 2.0)));
+
+        assert!(rect.contains(vec2(0.0, 0.0)));
+        assert!(rect.contains(vec2(1.0, -2.0)));
+        assert!(!rect.contains(vec2(1.5, 0.0)));
+
+        let line =
+            Shape::Polygon(Polygon::line(vec2(0.0, 0.0), vec2(1.0, 0.0)));
+
+        assert!(!line.contains(vec2(0.5, 0.0)));
+
+        let circle = Shape::Circle(Circle::new(vec2(5.0, 0.0), 1.0));
+
+        assert!(circle.contains(vec2(5.5, 0.5)));
+        assert!(!circle.contains(vec2(6.0, 1.0)));
+    }
+
+    #[test]
+    fn map_points() {
+        let circle = Shape::Circle(Circle::new(vec2(1.0, 0.0), 0.5))
+            .map_points(|p| p * 2.0 + vec2(0.0, 1.0));
+
+        let Shape::Circle(circle) = circle else { unreachable!() };
+
+        assert_eq!(vec2(2.0, 1.0), circle.center);
+        assert_eq!(1.0, circle.radius);
+    }
+}
@@ -0,0 +1,101 @@
+use glam::Vec2;
+
+use crate::contact::resolve_contact;
+use crate::intersect::intersect_t;
+use crate::Shape;
+
+/// Returns when (if at all) shape `a`, moving by `motion`, first touches
+/// shape `b` - `0.0` means the shapes overlap already, `1.0` means they touch
+/// at the very end of the motion.
+///
+/// Since both shapes are convex, it's enough to cast rays from vertices of
+/// `a` along the motion (and from vertices of `b` against it) and see which
+/// edge gets hit first; circles are approximated with polygons here.
+pub fn time_of_impact(a: &Shape, motion: Vec2, b: &Shape) -> Option<f32> {
+    if resolve_contact(a, b).is_some() {
+        return Some(0.0);
+    }
+
+    if motion == Vec2::ZERO {
+        return None;
+    }
+
+    let a = a.to_polygon();
+    let b = b.to_polygon();
+
+    let a_to_b = a.points().iter().flat_map(|point| {
+        b.iter_edges().filter_map(move |edge| {
+            intersect_t(*point, motion, edge.0, edge.1 - edge.0)
+        })
+    });
+
+    let b_to_a = b.points().iter().flat_map(|point| {
+        a.iter_edges().filter_map(move |edge| {
+            intersect_t(*point, -motion, edge.0, edge.1 - edge.0)
+        })
+    });
+
+    a_to_b.chain(b_to_a).reduce(f32::min)
+}
+
+#[cfg(test)]
+mod test {
+    use glam::vec2;
+
+    use super::*;
+    use crate::{Circle, Polygon};
+
+    #[test]
+    fn tunneling_through_line() {
+        let bullet = Shape::Polygon(Polygon::rect(vec2(0.1, 0.1)));
+        let wall =
+            Shape::Polygon(Polygon::line(vec2(5.0, -1.0), vec2(5.0, 1.0)));
+
+        let toi = time_of_impact(&bullet, vec2(10.0, 0.0), &wall).unwrap();
+
+        assert!((toi - 0.49).abs() < 0.001, "toi = {}", toi);
+    }
+
+    #[test]
+    fn hitting_vertex() {
+        let bullet = Shape::Polygon(Polygon::rect(vec2(0.5, 0.5)));
+        let wall = Shape::Polygon(
+            Polygon::rect(vec2(1.0, 1.0)).offset(vec2(4.0, 1.2)),
+        );
+
+        let toi = time_of_impact(&bullet, vec2(4.0, 0.0), &wall).unwrap();
+
+        assert!((toi - 0.625).abs() < 0.001, "toi = {}", toi);
+    }
+
+    #[test]
+    fn circle_through_line() {
+        let bullet = Shape::Circle(Circle::new(Vec2::ZERO, 0.5));
+        let wall =
+            Shape::Polygon(Polygon::line(vec2(5.0, -1.0), vec2(5.0, 1.0)));
+
+        let toi = time_of_impact(&bullet, vec2(10.0, 0.0), &wall).unwrap();
+
+        assert!((toi - 0.45).abs() < 0.001, "toi = {}", toi);
+    }
+
+    #[test]
+    fn missing() {
+        let bullet = Shape::Polygon(Polygon::rect(vec2(0.1, 0.1)));
+        let wall =
+            Shape::Polygon(Polygon::line(vec2(5.0, 1.0), vec2(5.0, 2.0)));
+
+        assert_eq!(None, time_of_impact(&bullet, vec2(10.0, 0.0), &wall));
+        assert_eq!(None, time_of_impact(&bullet, vec2(-10.0, 0.0), &wall));
+    }
+
+    #[test]
+    fn overlapping() {
+        let bullet = Shape::Polygon(Polygon::rect(vec2(0.5, 0.5)));
+        let wall = Shape::Polygon(
+            Polygon::rect(vec2(1.0, 1.0)).offset(vec2(1.0, 0.0)),
+        );
+
+        assert_eq!(Some(0.0), time_of_impact(&bullet, Vec2::ZERO, &wall));
+    }
+}
@@ -0,0 +1,539 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::{map, tileset};
+
+/// Colors of locked doors (and their keys), in the order they are used.
+pub const KEY_COLORS: [&str; 6] = [
+    "0xff0000", "0x00ff00", "0xffff00", "0x00ffff", "0xff00ff", "0xffd700",
+];
+
+/// How many times we try to attach a new room before giving up.
+const MAX_ATTEMPTS: usize = 500;
+
+/// Generates dungeons - rooms joined by corridors, some of them closed by
+/// locked doors with keys placed in rooms reachable before them.
+///
+/// Generated maps use the same objects as hand-made ones (`door:...`,
+/// `key:...`, `torch`, `heart`), plus:
+///
+/// - `tag:start` - where the player starts,
+/// - `tag:exit` - the room farthest from the start,
+/// - `zone:room-<n>` - covers n-th room,
+/// - `tag:room-<n>.spawn-<m>` - where n-th room's enemies should spawn.
+#[derive(Clone, Debug)]
+pub struct Generator {
+    rooms: usize,
+    locked_doors: usize,
+    enemies_per_room: usize,
+    heart_chance: f64,
+}
+
+impl Default for Generator {
+    fn default() -> Self {
+        Self {
+            rooms: 8,
+            locked_doors: 2,
+            enemies_per_room: 2,
+            heart_chance: 0.35,
+        }
+    }
+}
+
+impl Generator {
+    pub fn with_rooms(mut self, rooms: usize) -> Self {
+        assert!(rooms > 0, "Map must contain at least one room");
+
+        self.rooms = rooms;
+        self
+    }
+
+    pub fn with_locked_doors(mut self, locked_doors: usize) -> Self {
+        self.locked_doors = locked_doors.min(KEY_COLORS.len());
+        self
+    }
+
+    pub fn with_enemies_per_room(mut self, enemies_per_room: usize) -> Self {
+        self.enemies_per_room = enemies_per_room;
+        self
+    }
+
+    pub fn with_heart_chance(mut self, heart_chance: f64) -> Self {
+        self.heart_chance = heart_chance.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Generates a map; the same state of `rng` always yields the same map.
+    ///
+    /// The map can be either indexed (see [`map::Map::index()`]) or saved
+    /// (see [`map::Map::to_tmj()`]) and opened in Tiled.
+    pub fn generate(
+        &self,
+        rng: &mut impl Rng,
+        tileset: &tileset::Tileset,
+    ) -> map::Map {
+        let layout = Layout::generate(self.rooms, rng);
+        let mut map = map::Map::new();
+
+        let texture = |name: &str| {
+            tileset.id(name).unwrap_or_else(|| {
+                panic!("Tileset contains no texture: {}", name)
+            })
+        };
+
+        let floor =
+            texture(["floor.basic", "floor.stone.mossy"].choose(rng).unwrap());
+
+        let wall = texture(["wall.basic", "wall.stone"].choose(rng).unwrap());
+
+        let ceiling = texture("ceiling.basic");
+
+        let tiles = |tile| {
+            layout
+                .floors
+                .iter()
+                .map(|&point| (point, tile))
+                .collect::<BTreeMap<_, _>>()
+        };
+
+        map.add_tiles("floors", &tiles(floor));
+        map.add_tiles("ceilings", &tiles(ceiling));
+        map.add_tiles("walls", &layout.walls().map(|p| (p, wall)).collect());
+
+        self.place_objects(&layout, rng, &mut map);
+
+        map
+    }
+
+    fn place_objects(
+        &self,
+        layout: &Layout,
+        rng: &mut impl Rng,
+        map: &mut map::Map,
+    ) {
+        let rooms = &layout.rooms;
+        let mut taken = BTreeSet::new();
+
+        let start = rooms[0].center();
+        let exit = rooms.iter().max_by_key(|room| room.depth).unwrap().center();
+
+        for (name, (x, y)) in [("tag:start", start), ("tag:exit", exit)] {
+            map.add_object(name, x, y, 0, 0);
+            taken.insert((x, y));
+        }
+
+        // Torches hang on the side walls, unless there's a corridor
+        for room in rooms {
+            let (_, y) = room.center();
+
+            for (x, wall_x) in [(room.x1, room.x1 - 1), (room.x2, room.x2 + 1)]
+            {
+                if !layout.floors.contains(&(wall_x, y)) {
+                    map.add_object("torch", x, y, 0, 0);
+                    taken.insert((x, y));
+                }
+            }
+        }
+
+        // Keys are placed in rooms with lower numbers than their doors - since
+        // each room is attached to a room with a lower number, the player can
+        // always get to the key before reaching its door
+        let mut locked: Vec<_> = (1..rooms.len()).collect();
+
+        locked.shuffle(rng);
+        locked.truncate(self.locked_doors);
+        locked.sort_unstable();
+
+        for (id, room) in rooms.iter().enumerate().skip(1) {
+            let (x, y) = room.door;
+
+            if let Some(lock) = locked.iter().position(|&room| room == id) {
+                let color = KEY_COLORS[lock];
+                let key_room = &rooms[rng.gen_range(0..id)];
+
+                map.add_object(
+                    format!("door:room-{},{}", id, color),
+                    x,
+                    y,
+                    0,
+                    0,
+                );
+
+                if let Some((x, y)) = key_room.free_tile(rng, &mut taken) {
+                    map.add_object(
+                        format!("key:room-{},{}", id, color),
+                        x,
+                        y,
+                        0,
+                        0,
+                    );
+                }
+            } else {
+                map.add_object(format!("door:room-{}", id), x, y, 0, 0);
+            }
+
+            map.add_object(
+                format!("zone:room-{}", id),
+                room.x1,
+                room.y1,
+                room.x2 - room.x1 + 1,
+                room.y2 - room.y1 + 1,
+            );
+
+            if rng.gen_bool(self.heart_chance) {
+                if let Some((x, y)) = room.free_tile(rng, &mut taken) {
+                    map.add_object("heart", x, y, 0, 0);
+                }
+            }
+
+            for spawn in 1..=self.enemies_per_room {
+                if let Some((x, y)) = room.free_tile(rng, &mut taken) {
+                    map.add_object(
+                        format!("tag:room-{}.spawn-{}", id, spawn),
+                        x,
+                        y,
+                        0,
+                        0,
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Rooms and corridors, before they get turned into tiles and objects.
+#[derive(Debug, Default)]
+struct Layout {
+    rooms: Vec<Room>,
+    floors: BTreeSet<(i32, i32)>,
+}
+
+impl Layout {
+    /// Starts with a single room and then keeps attaching new rooms (through
+    /// corridors) to random already existing ones, making sure that there's
+    /// always a wall between rooms.
+    fn generate(rooms: usize, rng: &mut impl Rng) -> Self {
+        let mut this = Self::default();
+        let (w, h) = Room::random_size(rng);
+
+        this.add(Room::new(-w / 2, -h / 2, w, h), &[]);
+
+        for _ in 0..MAX_ATTEMPTS {
+            if this.rooms.len() >= rooms {
+                break;
+            }
+
+            let parent_id = rng.gen_range(0..this.rooms.len());
+            let parent = &this.rooms[parent_id];
+            let (w, h) = Room::random_size(rng);
+            let len = rng.gen_range(2..=5);
+
+            // Corridor starts right next to the parent and the new room starts
+            // right where the corridor ends
+            let (corridor, mut room) = match rng.gen_range(0..4) {
+                0 => {
+                    let x = rng.gen_range(parent.x1..=parent.x2);
+                    let y = parent.y1 - 1;
+                    let x1 = x - rng.gen_range(0..w);
+
+                    (
+                        (0..len).map(|i| (x, y - i)).collect::<Vec<_>>(),
+                        Room::new(x1, y - len - h + 1, w, h),
+                    )
+                }
+
+                1 => {
+                    let x = parent.x1 - 1;
+                    let y = rng.gen_range(parent.y1..=parent.y2);
+                    let y1 = y - rng.gen_range(0..h);
+
+                    (
+                        (0..len).map(|i| (x - i, y)).collect(),
+                        Room::new(x - len - w + 1, y1, w, h),
+                    )
+                }
+
+                2 => {
+                    let x = rng.gen_range(parent.x1..=parent.x2);
+                    let y = parent.y2 + 1;
+                    let x1 = x - rng.gen_range(0..w);
+
+                    (
+                        (0..len).map(|i| (x, y + i)).collect(),
+                        Room::new(x1, y + len, w, h),
+                    )
+                }
+
+                _ => {
+                    let x = parent.x2 + 1;
+                    let y = rng.gen_range(parent.y1..=parent.y2);
+                    let y1 = y - rng.gen_range(0..h);
+
+                    (
+                        (0..len).map(|i| (x + i, y)).collect(),
+                        Room::new(x + len, y1, w, h),
+                    )
+                }
+            };
+
+            let collides = |(x, y): (i32, i32)| {
+                (-1..=1).any(|dx| {
+                    (-1..=1).any(|dy| {
+                        let point = (x + dx, y + dy);
+
+                        this.floors.contains(&point) && !parent.contains(point)
+                    })
+                })
+            };
+
+            if corridor.iter().copied().any(collides)
+                || room.tiles().any(collides)
+            {
+                continue;
+            }
+
+            room.depth = parent.depth + 1;
+            room.door = corridor[corridor.len() / 2];
+
+            this.add(room, &corridor);
+        }
+
+        this
+    }
+
+    fn add(&mut self, room: Room, corridor: &[(i32, i32)]) {
+        self.floors.extend(room.tiles());
+        self.floors.extend(corridor);
+        self.rooms.push(room);
+    }
+
+    /// Returns tiles that touch the floor, including diagonally (so that
+    /// corners are filled as well).
+    fn walls(&self) -> impl Iterator<Item = (i32, i32)> + '_ {
+        self.floors
+            .iter()
+            .flat_map(|&(x, y)| {
+                (-1..=1).flat_map(move |dx| {
+                    (-1..=1).map(move |dy| (x + dx, y + dy))
+                })
+            })
+            .filter(|point| !self.floors.contains(point))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Room {
+    x1: i32,
+    y1: i32,
+    x2: i32,
+    y2: i32,
+
+    /// How many rooms the player has to go through to get here
+    depth: usize,
+
+    /// Where the door leading to this room is (in the middle of the corridor)
+    door: (i32, i32),
+}
+
+impl Room {
+    fn new(x1: i32, y1: i32, w: i32, h: i32) -> Self {
+        Self {
+            x1,
+            y1,
+            x2: x1 + w - 1,
+            y2: y1 + h - 1,
+            depth: 0,
+            door: Default::default(),
+        }
+    }
+
+    fn random_size(rng: &mut impl Rng) -> (i32, i32) {
+        (rng.gen_range(4..=8), rng.gen_range(4..=8))
+    }
+
+    fn center(&self) -> (i32, i32) {
+        ((self.x1 + self.x2) / 2, (self.y1 + self.y2) / 2)
+    }
+
+    fn contains(&self, (x, y): (i32, i32)) -> bool {
+        (self.x1..=self.x2).contains(&x) && (self.y1..=self.y2).contains(&y)
+    }
+
+    fn tiles(&self) -> impl Iterator<Item = (i32, i32)> {
+        let (x1, y1, x2, y2) = (self.x1, self.y1, self.x2, self.y2);
+
+        (y1..=y2).flat_map(move |y| (x1..=x2).map(move |x| (x, y)))
+    }
+
+    /// Returns a random tile that's not next to the walls and doesn't contain
+    /// any object yet.
+    fn free_tile(
+        &self,
+        rng: &mut impl Rng,
+        taken: &mut BTreeSet<(i32, i32)>,
+    ) -> Option<(i32, i32)> {
+        let tiles: Vec<_> = self
+            .tiles()
+            .filter(|&(x, y)| {
+                x > self.x1 && x < self.x2 && y > self.y1 && y < self.y2
+            })
+            .filter(|tile| !taken.contains(tile))
+            .collect();
+
+        let tile = *tiles.choose(rng)?;
+
+        taken.insert(tile);
+
+        Some(tile)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::SmallRng;
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::indexed;
+
+    fn tileset() -> tileset::Tileset {
+        tileset::Tileset::from_tsx(include_str!(
+            "../../../../assets/levels/tileset.tsx"
+        ))
+    }
+
+    fn generate(seed: u64) -> map::Map {
+        Generator::default()
+            .with_rooms(12)
+            .with_locked_doors(4)
+            .generate(&mut SmallRng::seed_from_u64(seed), &tileset())
+    }
+
+    /// Walks through the map (picking up keys and opening doors on the way)
+    /// and returns how many rooms and keys have been reached.
+    fn explore(map: &indexed::Map<'_>) -> (usize, usize, bool) {
+        let object_at = |x, y| {
+            map.objects()
+                .iter()
+                .find(|obj| obj.x == x && obj.y == y && obj.w == 0)
+                .map(|obj| obj.name.as_str())
+        };
+
+        let start = map
+            .objects()
+            .iter()
+            .find(|obj| obj.name == "tag:start")
+            .unwrap();
+
+        let mut keys = BTreeSet::new();
+        let mut visited = BTreeSet::new();
+        let mut is_exit_reached = false;
+
+        // Each time we pick up a key, we start over, since some previously
+        // locked doors might be now open
+        loop {
+            let keys_before = keys.len();
+            let mut pending = vec![(start.x, start.y)];
+
+            visited.clear();
+
+            while let Some((x, y)) = pending.pop() {
+                if map.floor_at(x, y).is_none() || !visited.insert((x, y)) {
+                    continue;
+                }
+
+                match object_at(x, y) {
+                    Some("tag:exit") => {
+                        is_exit_reached = true;
+                    }
+
+                    Some(name) if name.starts_with("key:") => {
+                        keys.insert(name["key:".len()..].to_owned());
+                    }
+
+                    Some(name) if name.starts_with("door:") => {
+                        let door = &name["door:".len()..];
+
+                        if door.contains(',') && !keys.contains(door) {
+                            continue;
+                        }
+                    }
+
+                    _ => (),
+                }
+
+                pending.extend([
+                    (x - 1, y),
+                    (x + 1, y),
+                    (x, y - 1),
+                    (x, y + 1),
+                ]);
+            }
+
+            if keys.len() == keys_before {
+                break;
+            }
+        }
+
+        let rooms = map
+            .objects()
+            .iter()
+            .filter(|obj| obj.name.starts_with("zone:room-"))
+            .filter(|obj| visited.contains(&(obj.x, obj.y)))
+            .count();
+
+        (rooms, keys.len(), is_exit_reached)
+    }
+
+    #[test]
+    fn generated_maps_are_completable() {
+        let tileset = tileset();
+
+        for seed in 0..32 {
+            let map = generate(seed);
+            let map = map.index(&tileset);
+
+            let total_rooms = map
+                .objects()
+                .iter()
+                .filter(|obj| obj.name.starts_with("zone:room-"))
+                .count();
+
+            let total_keys = map
+                .objects()
+                .iter()
+                .filter(|obj| obj.name.starts_with("key:"))
+                .count();
+
+            let (rooms, keys, is_exit_reached) = explore(&map);
+
+            assert_eq!(total_rooms, rooms, "seed={}", seed);
+            assert_eq!(total_keys, keys, "seed={}", seed);
+            assert!(is_exit_reached, "seed={}", seed);
+
+            map.geometrize();
+        }
+    }
+
+    #[test]
+    fn generated_maps_are_deterministic() {
+        assert_eq!(generate(1).to_tmj(), generate(1).to_tmj());
+        assert_ne!(generate(1).to_tmj(), generate(2).to_tmj());
+    }
+
+    #[test]
+    fn generated_maps_survive_saving() {
+        let tileset = tileset();
+        let map = generate(3);
+        let loaded = map::Map::from_tmj(&map.to_tmj());
+
+        assert_eq!(
+            map.index(&tileset).tiles().collect::<Vec<_>>(),
+            loaded.index(&tileset).tiles().collect::<Vec<_>>(),
+        );
+    }
+}
@@ -0,0 +1,161 @@
+use std::fmt;
+
+use glam::Vec2;
+
+use crate::Placement;
+
+#[derive(Default)]
+pub struct Map<'a> {
+    features: Vec<Feature<'a>>,
+}
+
+impl<'a> Map<'a> {
+    pub fn add(&mut self, item: Feature<'a>) {
+        self.features.push(item);
+    }
+
+    pub fn features(&self) -> impl Iterator<Item = &Feature<'a>> + '_ {
+        self.features.iter()
+    }
+
+    pub fn into_features(self) -> impl Iterator<Item = Feature<'a>> {
+        self.features.into_iter()
+    }
+}
+
+impl fmt::Display for Map<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut ceilings = 0;
+        let mut floors = 0;
+        let mut walls = 0;
+        let mut steps = 0;
+        let mut segments = 0;
+
+        for feature in &self.features {
+            match feature {
+                Feature::Ceiling { .. } => ceilings += 1,
+                Feature::Floor { .. } => floors += 1,
+                Feature::Wall { .. } => walls += 1,
+                Feature::Step { .. } => steps += 1,
+                Feature::Segment { .. } => segments += 1,
+            }
+        }
+
+        write!(
+            f,
+            "{} ceiling(s), {} floor(s), {} wall(s), {} step(s), {} segment(s)",
+            ceilings, floors, walls, steps, segments
+        )
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum Feature<'a> {
+    Ceiling {
+        x1: i32,
+        y1: i32,
+        x2: i32,
+        y2: i32,
+        height: f32,
+        tex: &'a str,
+    },
+
+    Floor {
+        x1: i32,
+        y1: i32,
+        x2: i32,
+        y2: i32,
+        height: f32,
+        tex: &'a str,
+    },
+
+    Wall {
+        x1: i32,
+        y1: i32,
+        x2: i32,
+        y2: i32,
+        rot: u8,
+        bottom: f32,
+        top: f32,
+        tex: &'a str,
+    },
+
+    /// Vertical fill between two floors (or two ceilings) of different
+    /// heights
+    Step {
+        x1: i32,
+        y1: i32,
+        x2: i32,
+        y2: i32,
+        rot: u8,
+        bottom: f32,
+        top: f32,
+        tex: &'a str,
+        is_solid: bool,
+    },
+
+    /// Wall that doesn't have to be axis-aligned (coordinates are in world
+    /// units)
+    Segment {
+        start: Vec2,
+        end: Vec2,
+        bottom: f32,
+        top: f32,
+        tex: &'a str,
+    },
+}
+
+impl Feature<'_> {
+    /// Returns name of the model this feature is built from, together with
+    /// the model's placement.
+    pub fn placement(&self) -> (&'static str, Placement) {
+        match *self {
+            Feature::Ceiling {
+                x1,
+                y1,
+                x2,
+                y2,
+                height,
+                ..
+            } => ("ceiling", Placement::ceiling(x1, y1, x2, y2, height)),
+
+            Feature::Floor {
+                x1,
+                y1,
+                x2,
+                y2,
+                height,
+                ..
+            } => ("floor", Placement::floor(x1, y1, x2, y2, height)),
+
+            Feature::Wall {
+                x1,
+                y1,
+                x2,
+                y2,
+                rot,
+                bottom,
+                top,
+                ..
+            }
+            | Feature::Step {
+                x1,
+                y1,
+                x2,
+                y2,
+                rot,
+                bottom,
+                top,
+                ..
+            } => ("wall", Placement::wall(x1, y1, x2, y2, rot, bottom, top)),
+
+            Feature::Segment {
+                start,
+                end,
+                bottom,
+                top,
+                ..
+            } => ("wall", Placement::segment(start, end, bottom, top)),
+        }
+    }
+}
@@ -0,0 +1,501 @@
+mod grow;
+
+use std::collections::{BTreeSet, HashMap};
+
+use glam::{vec2, Vec2};
+
+use crate::{geometrized, tileset};
+
+/// Floor steps up to this high (in centimeters) can be walked over, higher
+/// ones block the player.
+const MAX_STEP_HEIGHT: i32 = 50;
+
+#[derive(Clone, Debug)]
+pub struct Map<'a> {
+    min_x: i32,
+    min_y: i32,
+    max_x: i32,
+    max_y: i32,
+    width: u16,
+    tiles: Vec<Vec<Tile<'a>>>,
+    segments: Vec<Segment<'a>>,
+    objects: Vec<Object>,
+}
+
+impl<'a> Map<'a> {
+    pub fn new(min_x: i32, min_y: i32, max_x: i32, max_y: i32) -> Self {
+        let width = (max_x - min_x).abs();
+        let height = (max_y - min_y).abs();
+
+        Self {
+            min_x,
+            min_y,
+            max_x,
+            max_y,
+            width: width as _,
+            tiles: vec![Default::default(); (width * height) as usize],
+            segments: Default::default(),
+            objects: Default::default(),
+        }
+    }
+
+    pub fn add_segment(&mut self, segment: Segment<'a>) {
+        self.segments.push(segment);
+    }
+
+    pub fn add_object(&mut self, object: Object) {
+        self.objects.push(object);
+    }
+
+    pub fn add(
+        &mut self,
+        x: i32,
+        y: i32,
+        tiles: impl IntoIterator<Item = Tile<'a>>,
+    ) {
+        let idx = self
+            .xy_to_idx(x, y)
+            .unwrap_or_else(|| panic!("Invalid coordinates: {},{}", x, y));
+
+        for tile in tiles {
+            self.tiles[idx].push(tile);
+        }
+    }
+
+    pub fn get(&self, x: i32, y: i32) -> impl Iterator<Item = Tile<'a>> + '_ {
+        self.xy_to_idx(x, y)
+            .into_iter()
+            .flat_map(|idx| self.tiles.get(idx))
+            .flatten()
+            .copied()
+    }
+
+    /// Returns all the tiles, together with their coordinates.
+    pub fn tiles(&self) -> impl Iterator<Item = (i32, i32, Tile<'a>)> + '_ {
+        self.tiles.iter().enumerate().flat_map(|(idx, tiles)| {
+            let (x, y) = self.idx_to_xy(idx);
+
+            tiles.iter().copied().map(move |tile| (x, y, tile))
+        })
+    }
+
+    pub fn objects(&self) -> &[Object] {
+        &self.objects
+    }
+
+    pub fn geometrize(&self) -> geometrized::Map<'a> {
+        let mut map = geometrized::Map::default();
+
+        let mut pending_points: BTreeSet<_> = self.tiles().collect();
+
+        let mut pending_steps = BTreeSet::new();
+
+        for &(x, y, tile) in &pending_points {
+            self.find_steps(&mut pending_steps, x, y, tile);
+        }
+
+        while let Some((x, y, tile)) = pending_points.pop_first() {
+            match tile {
+                Tile::Ceiling(..) | Tile::Floor(..) => {
+                    let mut points = BTreeSet::from([(x, y)]);
+
+                    pending_points.retain(|&(x, y, other)| {
+                        if other == tile {
+                            points.insert((x, y));
+                            false
+                        } else {
+                            true
+                        }
+                    });
+
+                    for (x1, y1, x2, y2) in grow::rects(points) {
+                        map.add(match tile {
+                            Tile::Ceiling(tex, height) => {
+                                geometrized::Feature::Ceiling {
+                                    x1,
+                                    y1,
+                                    x2,
+                                    y2,
+                                    height: height.units(),
+                                    tex,
+                                }
+                            }
+
+                            Tile::Floor(tex, height) => {
+                                geometrized::Feature::Floor {
+                                    x1,
+                                    y1,
+                                    x2,
+                                    y2,
+                                    height: height.units(),
+                                    tex,
+                                }
+                            }
+
+                            _ => unreachable!(),
+                        });
+                    }
+                }
+
+                Tile::Wall(tex, rot) => {
+                    let (dx, dy) = Self::rot_to_dir(rot);
+
+                    let Some(span) = self.span_at(x + dx, y + dy) else {
+                        continue;
+                    };
+
+                    let (x1, y1, x2, y2) =
+                        grow::line(&mut pending_points, x, y, tile, |x, y| {
+                            self.span_at(x + dx, y + dy) == Some(span)
+                        });
+
+                    map.add(geometrized::Feature::Wall {
+                        x1,
+                        y1,
+                        x2,
+                        y2,
+                        rot,
+                        bottom: span.0.units(),
+                        top: span.1.units(),
+                        tex,
+                    });
+                }
+
+                Tile::Diagonal(tex) => {
+                    let (start, end, (bottom, top)) = self.diagonal_at(x, y);
+
+                    map.add(geometrized::Feature::Segment {
+                        start,
+                        end,
+                        bottom: bottom.units(),
+                        top: top.units(),
+                        tex,
+                    });
+                }
+            }
+        }
+
+        for segment in &self.segments {
+            map.add(geometrized::Feature::Segment {
+                start: segment.start,
+                end: segment.end,
+                bottom: segment.bottom,
+                top: segment.top,
+                tex: segment.tex,
+            });
+        }
+
+        while let Some((x, y, step)) = pending_steps.pop_first() {
+            let (x1, y1, x2, y2) =
+                grow::line(&mut pending_steps, x, y, step, |x2, y2| {
+                    if step.rot % 2 == 0 {
+                        y2 == y
+                    } else {
+                        x2 == x
+                    }
+                });
+
+            map.add(geometrized::Feature::Step {
+                x1,
+                y1,
+                x2,
+                y2,
+                rot: step.rot,
+                bottom: step.bottom.units(),
+                top: step.top.units(),
+                tex: step.tex,
+                is_solid: step.is_solid,
+            });
+        }
+
+        log::info!("... found features: {}", map);
+
+        map
+    }
+
+    /// Looks for height differences between given floor or ceiling tile and
+    /// its neighbours, so that the gaps can be filled with steps.
+    ///
+    /// Floor steps are generated on the higher tile, facing the lower one;
+    /// ceiling steps are generated on the lower tile, facing the higher one -
+    /// this way all steps face the player.
+    fn find_steps(
+        &self,
+        steps: &mut BTreeSet<(i32, i32, Step<'a>)>,
+        x: i32,
+        y: i32,
+        tile: Tile<'a>,
+    ) {
+        for rot in 0..4 {
+            let (dx, dy) = Self::rot_to_dir(rot);
+
+            let step = match tile {
+                Tile::Floor(tex, height) => self
+                    .floor_at(x + dx, y + dy)
+                    .filter(|&neighbour| neighbour < height)
+                    .map(|neighbour| Step {
+                        rot,
+                        bottom: neighbour,
+                        top: height,
+                        tex,
+                        is_solid: height.0 - neighbour.0 > MAX_STEP_HEIGHT,
+                    }),
+
+                Tile::Ceiling(tex, height) => self
+                    .ceiling_at(x + dx, y + dy)
+                    .filter(|&neighbour| neighbour > height)
+                    .map(|neighbour| Step {
+                        rot,
+                        bottom: height,
+                        top: neighbour,
+                        tex,
+                        is_solid: false,
+                    }),
+
+                Tile::Wall(..) | Tile::Diagonal(..) => None,
+            };
+
+            if let Some(step) = step {
+                steps.insert((x, y, step));
+            }
+        }
+    }
+
+    /// Returns the corners (and the vertical span) of a diagonal wall at given
+    /// tile.
+    ///
+    /// Diagonal walls cut tiles in half and their orientation is inferred from
+    /// the neighbourhood: exactly two adjacent sides (e.g. north and west) must
+    /// lead to floors, with the other two being closed.
+    fn diagonal_at(&self, x: i32, y: i32) -> (Vec2, Vec2, (Height, Height)) {
+        let span = self.span_at(x, y).unwrap_or_else(|| {
+            panic!("Map contains diagonal wall without floor at {},{}", x, y)
+        });
+
+        let is_open = |rot: u8| {
+            let (dx, dy) = Self::rot_to_dir(rot % 4);
+
+            self.span_at(x + dx, y + dy).is_some()
+        };
+
+        let mut rots = (0..4).filter(|&rot| {
+            is_open(rot)
+                && is_open(rot + 1)
+                && !is_open(rot + 2)
+                && !is_open(rot + 3)
+        });
+
+        let (Some(rot), None) = (rots.next(), rots.next()) else {
+            panic!(
+                "Map contains ambiguous diagonal wall at {},{}",
+                x, y
+            );
+        };
+
+        let corner = |a: u8, b: u8| {
+            let (ax, ay) = Self::rot_to_dir(a % 4);
+            let (bx, by) = Self::rot_to_dir(b % 4);
+
+            vec2(x as f32, y as f32)
+                + vec2((ax + bx) as f32, (ay + by) as f32) / 2.0
+        };
+
+        // The wall goes through the two corners that don't touch the open
+        // sides' shared corner
+        (corner(rot, rot + 3), corner(rot + 1, rot + 2), span)
+    }
+
+    pub fn floor_at(&self, x: i32, y: i32) -> Option<Height> {
+        self.get(x, y).find_map(|tile| match tile {
+            Tile::Floor(_, height) => Some(height),
+            _ => None,
+        })
+    }
+
+    fn ceiling_at(&self, x: i32, y: i32) -> Option<Height> {
+        self.get(x, y).find_map(|tile| match tile {
+            Tile::Ceiling(_, height) => Some(height),
+            _ => None,
+        })
+    }
+
+    /// Returns the vertical span (floor and ceiling height) of given tile, if
+    /// the tile has a floor; tiles without ceiling are assumed to be of the
+    /// default height.
+    fn span_at(&self, x: i32, y: i32) -> Option<(Height, Height)> {
+        let bottom = self.floor_at(x, y)?;
+
+        let top = self
+            .ceiling_at(x, y)
+            .unwrap_or_else(|| bottom.above(Height::CEILING));
+
+        Some((bottom, top))
+    }
+
+    fn rot_to_dir(rot: u8) -> (i32, i32) {
+        match rot {
+            0 => (0, -1),
+            1 => (-1, 0),
+            2 => (0, 1),
+            3 => (1, 0),
+            _ => unreachable!(),
+        }
+    }
+
+    fn xy_to_idx(&self, x: i32, y: i32) -> Option<usize> {
+        if x < self.min_x || x > self.max_x || y < self.min_y || y > self.max_y
+        {
+            return None;
+        }
+
+        let x = (x - self.min_x) as u16;
+        let y = (y - self.min_y) as u16;
+
+        Some((y * self.width + x) as usize)
+    }
+
+    fn idx_to_xy(&self, idx: usize) -> (i32, i32) {
+        let x = (idx as u16) % self.width;
+        let y = (idx as u16) / self.width;
+
+        let x = self.min_x + (x as i32);
+        let y = self.min_y + (y as i32);
+
+        (x, y)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Tile<'a> {
+    Ceiling(&'a str, Height),
+    Floor(&'a str, Height),
+    Wall(&'a str, u8),
+    Diagonal(&'a str),
+}
+
+impl<'a> Tile<'a> {
+    pub fn resolve(
+        layer: &str,
+        height: Option<f32>,
+        tileset: &'a tileset::Tileset,
+        id: u8,
+    ) -> Vec<Self> {
+        let texture = tileset.tile(id - 1).texture();
+
+        match layer {
+            "ceilings" => vec![Self::Ceiling(
+                texture,
+                height.map(Height::new).unwrap_or(Height::CEILING),
+            )],
+
+            "floors" => vec![Self::Floor(
+                texture,
+                height.map(Height::new).unwrap_or(Height::FLOOR),
+            )],
+
+            "walls" => (0..4).map(|rot| Self::Wall(texture, rot)).collect(),
+            "diagonals" => vec![Self::Diagonal(texture)],
+            layer => panic!("Unrecognized layer: {}", layer),
+        }
+    }
+
+    pub fn is_wall(self) -> bool {
+        matches!(self, Tile::Wall(..))
+    }
+}
+
+/// Height of a floor or a ceiling, stored in centimeters so that tiles remain
+/// comparable.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Height(i32);
+
+impl Height {
+    pub const FLOOR: Self = Self(0);
+    pub const CEILING: Self = Self(250);
+
+    pub fn new(units: f32) -> Self {
+        Self((units * 100.0).round() as i32)
+    }
+
+    pub fn units(self) -> f32 {
+        (self.0 as f32) / 100.0
+    }
+
+    fn above(self, other: Self) -> Self {
+        Self(self.0 + other.0)
+    }
+}
+
+/// Object placed on the map, e.g. `door:...` or `tag:...`; coordinates are in
+/// tiles.
+#[derive(Clone, Debug)]
+pub struct Object {
+    pub name: String,
+    pub x: i32,
+    pub y: i32,
+    pub w: i32,
+    pub h: i32,
+    pub props: HashMap<String, serde_json::Value>,
+    pub shape: Shape,
+}
+
+/// Object's shape; most objects are just rectangles (or points, with zero
+/// size), but Tiled allows to draw ellipses and polygons, too.
+///
+/// Coordinates are expressed in tiles.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Shape {
+    Rect,
+    Ellipse { center: Vec2, radius: Vec2 },
+    Polygon(Vec<Vec2>),
+}
+
+impl Object {
+    pub fn position(&self) -> Vec2 {
+        vec2(self.x as f32, self.y as f32)
+    }
+
+    pub fn prop_str(&self, name: &str) -> Option<&str> {
+        self.props.get(name).map(|value| {
+            value.as_str().unwrap_or_else(|| {
+                panic!("Map contains object with non-string `{}`", name)
+            })
+        })
+    }
+
+    pub fn prop_f32(&self, name: &str) -> Option<f32> {
+        self.props.get(name).map(|value| {
+            value.as_f64().unwrap_or_else(|| {
+                panic!("Map contains object with non-numeric `{}`", name)
+            }) as f32
+        })
+    }
+
+    pub fn prop_bool(&self, name: &str) -> Option<bool> {
+        self.props.get(name).map(|value| {
+            value.as_bool().unwrap_or_else(|| {
+                panic!("Map contains object with non-boolean `{}`", name)
+            })
+        })
+    }
+}
+
+/// Free-form wall, e.g. drawn as a polyline in Tiled; coordinates are in
+/// world units.
+#[derive(Clone, Copy, Debug)]
+pub struct Segment<'a> {
+    pub start: Vec2,
+    pub end: Vec2,
+    pub bottom: f32,
+    pub top: f32,
+    pub tex: &'a str,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Step<'a> {
+    rot: u8,
+    bottom: Height,
+    top: Height,
+    tex: &'a str,
+    is_solid: bool,
+}
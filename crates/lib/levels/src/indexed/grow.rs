@@ -0,0 +1,344 @@
+use itertools::Itertools;
+
+use super::*;
+
+type Rect = (i32, i32, i32, i32);
+
+/// Splits given points into as few rectangles as we can find.
+///
+/// Finding the optimal split is a bit of an ordeal, so instead we try a few
+/// strategies - sweeping by columns, sweeping by rows and picking the largest
+/// rectangle first - and go with whichever yields the fewest rectangles.
+pub fn rects(points: BTreeSet<(i32, i32)>) -> Vec<Rect> {
+    let transposed = || points.iter().map(|&(x, y)| (y, x)).collect();
+
+    let by_columns = sweep(points.clone());
+
+    let by_rows = sweep(transposed())
+        .into_iter()
+        .map(|(y1, x1, y2, x2)| (x1, y1, x2, y2))
+        .collect();
+
+    let by_area = largest_first(points.clone());
+
+    [by_columns, by_rows, by_area]
+        .into_iter()
+        .map(merge)
+        .min_by_key(|rects| rects.len())
+        .unwrap()
+}
+
+/// Goes through the points in order, growing each rectangle downwards first
+/// and then to the right.
+fn sweep(mut points: BTreeSet<(i32, i32)>) -> Vec<Rect> {
+    let mut rects = Vec::new();
+
+    while let Some((x1, y1)) = points.pop_first() {
+        let mut x2 = x1;
+        let mut y2 = y1;
+
+        while points.remove(&(x1, y2 + 1)) {
+            y2 += 1;
+        }
+
+        while (y1..=y2).all(|y| points.contains(&(x2 + 1, y))) {
+            x2 += 1;
+
+            for y in y1..=y2 {
+                points.remove(&(x2, y));
+            }
+        }
+
+        rects.push((x1, y1, x2, y2));
+    }
+
+    rects
+}
+
+fn largest_first(mut points: BTreeSet<(i32, i32)>) -> Vec<Rect> {
+    let mut rects = Vec::new();
+
+    while let Some(rect) = largest_rect(&points) {
+        let (x1, y1, x2, y2) = rect;
+
+        for x in x1..=x2 {
+            for y in y1..=y2 {
+                assert!(points.remove(&(x, y)));
+            }
+        }
+
+        rects.push(rect);
+    }
+
+    rects
+}
+
+/// Finds the largest rectangle fully covered by given points.
+///
+/// Goes row by row, treating the points above each row as a histogram and
+/// looking for the largest rectangle within that histogram.
+fn largest_rect(points: &BTreeSet<(i32, i32)>) -> Option<Rect> {
+    let (min_x, max_x) =
+        points.iter().map(|(x, _)| *x).minmax().into_option()?;
+
+    let (min_y, max_y) =
+        points.iter().map(|(_, y)| *y).minmax().into_option()?;
+
+    let mut heights = vec![0; (max_x - min_x + 1) as usize];
+    let mut stack = Vec::new();
+    let mut best = None;
+    let mut best_area = 0;
+
+    for y in min_y..=max_y {
+        for (x, height) in (min_x..).zip(&mut heights) {
+            if points.contains(&(x, y)) {
+                *height += 1;
+            } else {
+                *height = 0;
+            }
+        }
+
+        stack.clear();
+
+        for idx in 0..=heights.len() {
+            let height = heights.get(idx).copied().unwrap_or(0);
+
+            while let Some(&top) = stack.last() {
+                if heights[top] < height {
+                    break;
+                }
+
+                stack.pop();
+
+                let left = stack.last().map_or(0, |&left| left + 1);
+                let area = heights[top] * (idx - left);
+
+                if area > best_area {
+                    best = Some((
+                        min_x + left as i32,
+                        y - heights[top] as i32 + 1,
+                        min_x + idx as i32 - 1,
+                        y,
+                    ));
+
+                    best_area = area;
+                }
+            }
+
+            stack.push(idx);
+        }
+    }
+
+    best
+}
+
+/// Joins rectangles that share an entire edge, until there's nothing left to
+/// join.
+fn merge(mut rects: Vec<Rect>) -> Vec<Rect> {
+    'merge: loop {
+        for i in 0..rects.len() {
+            for j in (i + 1)..rects.len() {
+                if let Some(rect) = try_merge(rects[i], rects[j]) {
+                    rects[i] = rect;
+                    rects.swap_remove(j);
+                    continue 'merge;
+                }
+            }
+        }
+
+        return rects;
+    }
+}
+
+fn try_merge(
+    (ax1, ay1, ax2, ay2): Rect,
+    (bx1, by1, bx2, by2): Rect,
+) -> Option<Rect> {
+    if ax1 == bx1 && ax2 == bx2 && (ay2 + 1 == by1 || by2 + 1 == ay1) {
+        return Some((ax1, ay1.min(by1), ax2, ay2.max(by2)));
+    }
+
+    if ay1 == by1 && ay2 == by2 && (ax2 + 1 == bx1 || bx2 + 1 == ax1) {
+        return Some((ax1.min(bx1), ay1, ax2.max(bx2), ay2));
+    }
+
+    None
+}
+
+pub fn line<T>(
+    pending_points: &mut BTreeSet<(i32, i32, T)>,
+    x: i32,
+    y: i32,
+    tile: T,
+    matches: impl Fn(i32, i32) -> bool,
+) -> (i32, i32, i32, i32)
+where
+    T: Copy + Ord,
+{
+    let mut x1 = x;
+    let mut y1 = y;
+    let mut x2 = x;
+    let mut y2 = y;
+
+    // -----
+
+    let mut grow_dir = None;
+
+    for dir in Direction::all() {
+        let (x, y) = match dir {
+            Direction::Up => (x, y - 1),
+            Direction::Down => (x, y + 1),
+            Direction::Left => (x - 1, y),
+            Direction::Right => (x + 1, y),
+        };
+
+        if pending_points.contains(&(x, y, tile)) && matches(x, y) {
+            grow_dir = Some(dir);
+            break;
+        }
+    }
+
+    // -----
+
+    while let Some(dir) = grow_dir {
+        match dir {
+            Direction::Up => y1 -= 1,
+            Direction::Down => y2 += 1,
+            Direction::Left => x1 -= 1,
+            Direction::Right => x2 += 1,
+        }
+
+        let (x, y) = match dir {
+            Direction::Up => (x, y1 - 1),
+            Direction::Down => (x, y2 + 1),
+            Direction::Left => (x1 - 1, y),
+            Direction::Right => (x2 + 1, y),
+        };
+
+        if !pending_points.contains(&(x, y, tile)) || !matches(x, y) {
+            let dir = match dir {
+                Direction::Up => Direction::Down,
+                Direction::Down => Direction::Up,
+                Direction::Left => Direction::Right,
+                Direction::Right => Direction::Left,
+            };
+
+            let (x, y) = match dir {
+                Direction::Up => (x, y1 - 1),
+                Direction::Down => (x, y2 + 1),
+                Direction::Left => (x1 - 1, y),
+                Direction::Right => (x2 + 1, y),
+            };
+
+            if pending_points.contains(&(x, y, tile)) && matches(x, y) {
+                grow_dir = Some(dir);
+            } else {
+                break;
+            }
+        }
+    }
+
+    // -----
+
+    for px in x1..=x2 {
+        for py in y1..=y2 {
+            if px == x && py == y {
+                continue;
+            }
+
+            assert!(pending_points.remove(&(px, py, tile)));
+        }
+    }
+
+    (x1, y1, x2, y2)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    fn all() -> impl Iterator<Item = Self> {
+        [Self::Up, Self::Down, Self::Left, Self::Right].into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn points(rows: &[&str]) -> BTreeSet<(i32, i32)> {
+        rows.iter()
+            .enumerate()
+            .flat_map(|(y, row)| {
+                row.chars()
+                    .enumerate()
+                    .filter(|(_, tile)| *tile == '#')
+                    .map(move |(x, _)| (x as i32, y as i32))
+            })
+            .collect()
+    }
+
+    fn area(rects: &[Rect]) -> i32 {
+        rects
+            .iter()
+            .map(|(x1, y1, x2, y2)| (x2 - x1 + 1) * (y2 - y1 + 1))
+            .sum()
+    }
+
+    #[test]
+    fn rects_covers_all_points() {
+        let points = points(&[
+            "###..", //
+            "#.###", //
+            "#####", //
+            "..#..", //
+        ]);
+
+        let rects = rects(points.clone());
+
+        assert_eq!(points.len() as i32, area(&rects));
+
+        for (x, y) in points {
+            assert!(rects.iter().any(|&(x1, y1, x2, y2)| {
+                (x1..=x2).contains(&x) && (y1..=y2).contains(&y)
+            }));
+        }
+    }
+
+    #[test]
+    fn rects_picks_best_strategy() {
+        let points = points(&[
+            ".#", //
+            "##", //
+            ".#", //
+        ]);
+
+        assert_eq!(3, sweep(points.clone()).len());
+        assert_eq!(vec![(1, 0, 1, 2), (0, 1, 0, 1)], rects(points));
+    }
+
+    #[test]
+    fn largest_first_picks_largest_rect() {
+        let rects = largest_first(points(&[
+            "#...", //
+            "####", //
+            "####", //
+            "#...", //
+        ]));
+
+        assert_eq!(vec![(0, 1, 3, 2), (0, 0, 0, 0), (0, 3, 0, 3)], rects);
+    }
+
+    #[test]
+    fn merge_joins_rects_sharing_edge() {
+        let rects =
+            merge(vec![(0, 0, 1, 0), (5, 5, 5, 5), (0, 1, 1, 3), (2, 0, 2, 3)]);
+
+        assert_eq!(vec![(0, 0, 2, 3), (5, 5, 5, 5)], rects);
+    }
+}
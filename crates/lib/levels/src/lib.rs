@@ -0,0 +1,17 @@
+//! Loading of maps created in Tiled (and generating new ones) - this crate
+//! doesn't depend on Bevy, so that maps can be inspected by tools such as
+//! `level-stats`.
+
+#![feature(map_first_last)]
+
+pub mod generator;
+pub mod geometrized;
+pub mod indexed;
+pub mod map;
+mod placement;
+pub mod tileset;
+
+pub use self::placement::Placement;
+
+/// Height of regular rooms.
+pub const CEILING_HEIGHT: f32 = 2.5;
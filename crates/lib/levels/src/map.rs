@@ -0,0 +1,707 @@
+use std::cmp;
+use std::collections::BTreeMap;
+
+use glam::{vec2, Vec2};
+use itertools::Itertools;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::{indexed, tileset, CEILING_HEIGHT};
+
+/// Size of tiles (in pixels) for maps created through [`Map::new()`].
+const TILE_SIZE: i32 = 32;
+
+/// Size of chunks (in tiles) for maps created through [`Map::new()`].
+const CHUNK_SIZE: i32 = 16;
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Map {
+    layers: Vec<Layer>,
+    #[serde(rename = "tilewidth")]
+    tile_width: i32,
+    #[serde(rename = "tileheight")]
+    tile_height: i32,
+}
+
+impl Map {
+    /// Creates an empty map, e.g. to be filled by the generator.
+    pub fn new() -> Self {
+        Self {
+            layers: Default::default(),
+            tile_width: TILE_SIZE,
+            tile_height: TILE_SIZE,
+        }
+    }
+
+    pub fn from_tmj(data: &str) -> Self {
+        serde_json::from_str::<Map>(data).expect("Couldn't deserialize map")
+    }
+
+    pub fn index(self, tileset: &tileset::Tileset) -> indexed::Map<'_> {
+        let chunks = self.layers.iter().flat_map(|layer| &layer.chunks);
+        let (min_x, min_y, max_x, max_y) = Chunk::bounds(chunks);
+
+        // ----
+
+        let mut map = indexed::Map::new(min_x, min_y, max_x, max_y);
+
+        for layer in self.layers {
+            // Layers can be called e.g. `floors` or `floors:platform`, so that
+            // maps can contain many layers of the same kind (say, each with a
+            // different height)
+            let kind = layer
+                .name
+                .split_once(':')
+                .map_or(layer.name.as_str(), |(kind, _)| kind);
+
+            let height = layer.properties.iter().find_map(|prop| {
+                if prop.name == "height" {
+                    Some(prop.value.as_f64().unwrap_or_else(|| {
+                        panic!(
+                            "Map contains layer with invalid height: {}",
+                            layer.name
+                        )
+                    }) as f32)
+                } else {
+                    None
+                }
+            });
+
+            for chunk in layer.chunks {
+                let mut x = chunk.x;
+                let mut y = chunk.y;
+
+                for tile in chunk.data {
+                    assert!(x >= chunk.x && x <= chunk.x + chunk.width);
+                    assert!(y >= chunk.y && y <= chunk.y + chunk.height);
+
+                    if tile > 0 {
+                        map.add(
+                            x,
+                            y,
+                            indexed::Tile::resolve(kind, height, tileset, tile),
+                        );
+                    }
+
+                    x += 1;
+
+                    if x >= chunk.x + chunk.width {
+                        x = chunk.x;
+                        y += 1;
+                    }
+                }
+            }
+
+            for object in layer.objects {
+                if !object.polyline.is_empty() {
+                    Self::index_polyline(
+                        tileset,
+                        &mut map,
+                        vec2(self.tile_width as f32, self.tile_height as f32),
+                        object,
+                    );
+                    continue;
+                }
+
+                let tile_size =
+                    vec2(self.tile_width as f32, self.tile_height as f32);
+
+                // Similarly to polylines, shapes' points are offset so that
+                // they match tiles (which are centered at their coordinates)
+                let shape = if !object.polygon.is_empty() {
+                    indexed::Shape::Polygon(
+                        object
+                            .polygon
+                            .iter()
+                            .map(|point| {
+                                vec2(object.x + point.x, object.y + point.y)
+                                    / tile_size
+                                    - 0.5
+                            })
+                            .collect(),
+                    )
+                } else if object.ellipse {
+                    let radius = vec2(object.width, object.height) / 2.0;
+
+                    indexed::Shape::Ellipse {
+                        center: (vec2(object.x, object.y) + radius) / tile_size
+                            - 0.5,
+                        radius: radius / tile_size,
+                    }
+                } else {
+                    indexed::Shape::Rect
+                };
+
+                map.add_object(indexed::Object {
+                    name: object.name,
+                    x: (object.x / (self.tile_width as f32)).floor() as i32,
+                    y: (object.y / (self.tile_height as f32)).floor() as i32,
+                    w: (object.width as i32) / self.tile_width,
+                    h: (object.height as i32) / self.tile_height,
+                    props: object
+                        .properties
+                        .into_iter()
+                        .map(|prop| (prop.name, prop.value))
+                        .collect(),
+                    shape,
+                });
+            }
+        }
+
+        map
+    }
+
+    /// Adds a tile layer (e.g. `floors`); `tiles` contain ids from the
+    /// tileset.
+    pub fn add_tiles(&mut self, name: &str, tiles: &BTreeMap<(i32, i32), u8>) {
+        let mut chunks = BTreeMap::new();
+
+        for (&(x, y), &tile) in tiles {
+            let chunk_x = x.div_euclid(CHUNK_SIZE) * CHUNK_SIZE;
+            let chunk_y = y.div_euclid(CHUNK_SIZE) * CHUNK_SIZE;
+
+            let chunk =
+                chunks.entry((chunk_x, chunk_y)).or_insert_with(|| Chunk {
+                    x: chunk_x,
+                    y: chunk_y,
+                    width: CHUNK_SIZE,
+                    height: CHUNK_SIZE,
+                    data: vec![0; (CHUNK_SIZE * CHUNK_SIZE) as usize],
+                });
+
+            // Tiled's ids start at one, with zero meaning "no tile"
+            chunk.data[((y - chunk_y) * CHUNK_SIZE + x - chunk_x) as usize] =
+                tile + 1;
+        }
+
+        self.layers.push(Layer {
+            name: name.to_owned(),
+            chunks: chunks.into_values().collect(),
+            objects: Default::default(),
+            properties: Default::default(),
+        });
+    }
+
+    /// Adds an object (e.g. `tag:start`) to the `objects` layer; objects with
+    /// zero width and height are created as points.
+    pub fn add_object(
+        &mut self,
+        name: impl Into<String>,
+        x: i32,
+        y: i32,
+        w: i32,
+        h: i32,
+    ) {
+        let layer = if let Some(idx) =
+            self.layers.iter().position(|layer| layer.name == "objects")
+        {
+            &mut self.layers[idx]
+        } else {
+            self.layers.push(Layer {
+                name: "objects".into(),
+                chunks: Default::default(),
+                objects: Default::default(),
+                properties: Default::default(),
+            });
+
+            self.layers.last_mut().unwrap()
+        };
+
+        // Points are placed in the middle of their tile, so that rounding
+        // doesn't move them to a neighbouring one
+        let offset = if w == 0 && h == 0 {
+            (self.tile_width / 2, self.tile_height / 2)
+        } else {
+            (0, 0)
+        };
+
+        layer.objects.push(Object {
+            x: (x * self.tile_width + offset.0) as f32,
+            y: (y * self.tile_height + offset.1) as f32,
+            width: (w * self.tile_width) as f32,
+            height: (h * self.tile_height) as f32,
+            name: name.into(),
+            properties: Default::default(),
+            polyline: Default::default(),
+            polygon: Default::default(),
+            ellipse: false,
+        });
+    }
+
+    /// Places tile at given coordinates into the layer of given kind (e.g.
+    /// `floors`), creating the layer if needed; `None` removes the tile from
+    /// all layers of this kind (e.g. both `floors` and `floors:platform`).
+    pub fn set_tile(&mut self, kind: &str, x: i32, y: i32, tile: Option<u8>) {
+        let is_kind = |layer: &Layer| {
+            layer.name == kind
+                || layer.name.split_once(':').map_or(false, |(k, _)| k == kind)
+        };
+
+        let Some(tile) = tile else {
+            for layer in self.layers.iter_mut().filter(|layer| is_kind(layer)) {
+                if let Some(slot) = Self::tile_mut(layer, x, y) {
+                    *slot = 0;
+                }
+            }
+
+            return;
+        };
+
+        let layer = if let Some(idx) = self
+            .layers
+            .iter()
+            .position(|layer| layer.name == kind)
+            .or_else(|| self.layers.iter().position(is_kind))
+        {
+            &mut self.layers[idx]
+        } else {
+            self.layers.push(Layer {
+                name: kind.to_owned(),
+                chunks: Default::default(),
+                objects: Default::default(),
+                properties: Default::default(),
+            });
+
+            self.layers.last_mut().unwrap()
+        };
+
+        if Self::tile_mut(layer, x, y).is_none() {
+            let chunk_x = x.div_euclid(CHUNK_SIZE) * CHUNK_SIZE;
+            let chunk_y = y.div_euclid(CHUNK_SIZE) * CHUNK_SIZE;
+
+            layer.chunks.push(Chunk {
+                x: chunk_x,
+                y: chunk_y,
+                width: CHUNK_SIZE,
+                height: CHUNK_SIZE,
+                data: vec![0; (CHUNK_SIZE * CHUNK_SIZE) as usize],
+            });
+        }
+
+        *Self::tile_mut(layer, x, y).unwrap() = tile + 1;
+    }
+
+    /// Removes objects (except polylines) covering given tile, returning how
+    /// many of them have been removed.
+    pub fn remove_objects_at(&mut self, x: i32, y: i32) -> usize {
+        let (tile_w, tile_h) = (self.tile_width, self.tile_height);
+        let mut removed = 0;
+
+        for layer in &mut self.layers {
+            layer.objects.retain(|object| {
+                if !object.polyline.is_empty() {
+                    return true;
+                }
+
+                let x1 = (object.x / (tile_w as f32)).floor() as i32;
+                let y1 = (object.y / (tile_h as f32)).floor() as i32;
+                let x2 = x1 + cmp::max(1, object.width as i32 / tile_w);
+                let y2 = y1 + cmp::max(1, object.height as i32 / tile_h);
+
+                if (x1..x2).contains(&x) && (y1..y2).contains(&y) {
+                    removed += 1;
+                    false
+                } else {
+                    true
+                }
+            });
+        }
+
+        removed
+    }
+
+    fn tile_mut(layer: &mut Layer, x: i32, y: i32) -> Option<&mut u8> {
+        let chunk = layer.chunks.iter_mut().find(|chunk| {
+            (chunk.x..chunk.x + chunk.width).contains(&x)
+                && (chunk.y..chunk.y + chunk.height).contains(&y)
+        })?;
+
+        chunk
+            .data
+            .get_mut(((y - chunk.y) * chunk.width + x - chunk.x) as usize)
+    }
+
+    /// Returns names of all the objects, e.g. `tag:start`.
+    pub fn object_names(&self) -> impl Iterator<Item = &str> {
+        self.layers
+            .iter()
+            .flat_map(|layer| &layer.objects)
+            .map(|object| object.name.as_str())
+    }
+
+    /// Serializes map into Tiled's format, so that it can be opened in the
+    /// editor.
+    pub fn to_tmj(&self) -> String {
+        let mut next_object_id = 1;
+
+        let layers: Vec<_> = self
+            .layers
+            .iter()
+            .enumerate()
+            .map(|(idx, layer)| layer.to_json(idx + 1, &mut next_object_id))
+            .collect();
+
+        let chunks = self.layers.iter().flat_map(|layer| &layer.chunks);
+        let (min_x, min_y, max_x, max_y) = Chunk::bounds(chunks);
+
+        let map = json!({
+            "compressionlevel": -1,
+            "width": max_x - min_x,
+            "height": max_y - min_y,
+            "infinite": true,
+            "layers": layers,
+            "nextlayerid": self.layers.len() + 1,
+            "nextobjectid": next_object_id,
+            "orientation": "orthogonal",
+            "renderorder": "right-down",
+            "tiledversion": "1.8.4",
+            "tilewidth": self.tile_width,
+            "tileheight": self.tile_height,
+            "tilesets": [{ "firstgid": 1, "source": "tileset.tsx" }],
+            "type": "map",
+            "version": "1.8",
+        });
+
+        serde_json::to_string_pretty(&map).unwrap()
+    }
+
+    /// Converts polyline called `wall` or `wall:<texture>` into wall segments;
+    /// `bottom` and `top` properties can be used to override wall's height.
+    fn index_polyline<'a>(
+        tileset: &'a tileset::Tileset,
+        map: &mut indexed::Map<'a>,
+        tile_size: Vec2,
+        object: Object,
+    ) {
+        let texture = match object.name.as_str() {
+            "wall" => "wall.basic",
+            name => name.strip_prefix("wall:").unwrap_or_else(|| {
+                panic!("Map contains unrecognized polyline: {}", name)
+            }),
+        };
+
+        let tex = tileset.texture(texture).unwrap_or_else(|| {
+            panic!("Map contains polyline with unknown texture: {}", texture)
+        });
+
+        let prop = |name: &str, default: f32| {
+            object
+                .properties
+                .iter()
+                .find(|prop| prop.name == name)
+                .map_or(default, |prop| {
+                    prop.value.as_f64().unwrap_or_else(|| {
+                        panic!(
+                            "Map contains polyline with invalid {}: {}",
+                            name, object.name
+                        )
+                    }) as f32
+                })
+        };
+
+        let bottom = prop("bottom", 0.0);
+        let top = prop("top", CEILING_HEIGHT);
+
+        // Tiles are centered at their coordinates, hence the offset
+        let points = object.polyline.iter().map(|point| {
+            vec2(object.x + point.x, object.y + point.y) / tile_size - 0.5
+        });
+
+        for (start, end) in points.tuple_windows() {
+            map.add_segment(indexed::Segment {
+                start,
+                end,
+                bottom,
+                top,
+                tex,
+            });
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct Layer {
+    name: String,
+    #[serde(default)]
+    chunks: Vec<Chunk>,
+    #[serde(default)]
+    objects: Vec<Object>,
+    #[serde(default)]
+    properties: Vec<Property>,
+}
+
+impl Default for Map {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Layer {
+    fn to_json(&self, id: usize, next_object_id: &mut usize) -> Value {
+        let mut json = if self.chunks.is_empty() {
+            let objects: Vec<_> = self
+                .objects
+                .iter()
+                .map(|object| {
+                    *next_object_id += 1;
+                    object.to_json(*next_object_id - 1)
+                })
+                .collect();
+
+            json!({
+                "type": "objectgroup",
+                "draworder": "topdown",
+                "objects": objects,
+            })
+        } else {
+            let (min_x, min_y, max_x, max_y) = Chunk::bounds(&self.chunks);
+
+            let chunks: Vec<_> = self
+                .chunks
+                .iter()
+                .map(|chunk| {
+                    json!({
+                        "x": chunk.x,
+                        "y": chunk.y,
+                        "width": chunk.width,
+                        "height": chunk.height,
+                        "data": chunk.data,
+                    })
+                })
+                .collect();
+
+            json!({
+                "type": "tilelayer",
+                "chunks": chunks,
+                "startx": min_x,
+                "starty": min_y,
+                "width": max_x - min_x,
+                "height": max_y - min_y,
+            })
+        };
+
+        json["id"] = id.into();
+        json["name"] = self.name.as_str().into();
+        json["opacity"] = 1.into();
+        json["visible"] = true.into();
+        json["x"] = 0.into();
+        json["y"] = 0.into();
+
+        if !self.properties.is_empty() {
+            json["properties"] = Property::to_json(&self.properties);
+        }
+
+        json
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct Chunk {
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+    data: Vec<u8>,
+}
+
+impl Chunk {
+    fn bounds<'a>(
+        chunks: impl IntoIterator<Item = &'a Self>,
+    ) -> (i32, i32, i32, i32) {
+        chunks
+            .into_iter()
+            .fold((0, 0, 0, 0), |(x1, y1, x2, y2), chunk| {
+                (
+                    cmp::min(x1, chunk.x),
+                    cmp::min(y1, chunk.y),
+                    cmp::max(x2, chunk.x + chunk.width),
+                    cmp::max(y2, chunk.y + chunk.height),
+                )
+            })
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct Object {
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+    name: String,
+    #[serde(default)]
+    properties: Vec<Property>,
+    #[serde(default)]
+    polyline: Vec<Point>,
+    #[serde(default)]
+    polygon: Vec<Point>,
+    #[serde(default)]
+    ellipse: bool,
+}
+
+impl Object {
+    fn to_json(&self, id: usize) -> Value {
+        let mut json = json!({
+            "id": id,
+            "name": self.name,
+            "type": "",
+            "x": self.x,
+            "y": self.y,
+            "width": self.width,
+            "height": self.height,
+            "rotation": 0,
+            "visible": true,
+        });
+
+        if !self.polyline.is_empty() {
+            json["polyline"] = self
+                .polyline
+                .iter()
+                .map(|point| json!({ "x": point.x, "y": point.y }))
+                .collect();
+        } else if !self.polygon.is_empty() {
+            json["polygon"] = self
+                .polygon
+                .iter()
+                .map(|point| json!({ "x": point.x, "y": point.y }))
+                .collect();
+        } else if self.ellipse {
+            json["ellipse"] = true.into();
+        } else if self.width == 0.0 && self.height == 0.0 {
+            json["point"] = true.into();
+        }
+
+        if !self.properties.is_empty() {
+            json["properties"] = Property::to_json(&self.properties);
+        }
+
+        json
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+struct Point {
+    x: f32,
+    y: f32,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct Property {
+    name: String,
+    value: Value,
+}
+
+impl Property {
+    fn to_json(props: &[Self]) -> Value {
+        props
+            .iter()
+            .map(|prop| {
+                let ty = match &prop.value {
+                    Value::Bool(_) => "bool",
+                    Value::Number(num) if num.is_i64() => "int",
+                    Value::Number(_) => "float",
+                    _ => "string",
+                };
+
+                json!({ "name": prop.name, "type": ty, "value": prop.value })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edited_maps_survive_saving() {
+        let tileset = tileset::Tileset::from_tsx(include_str!(
+            "../../../../assets/levels/tileset.tsx"
+        ));
+        let floor = tileset.id("floor.basic").unwrap();
+        let wall = tileset.id("wall.basic").unwrap();
+
+        let mut map = Map::new();
+
+        map.set_tile("floors", 0, 0, Some(floor));
+        map.set_tile("floors", -20, 3, Some(floor));
+        map.set_tile("walls", 1, 0, Some(wall));
+        map.set_tile("walls", 1, 0, None);
+        map.add_object("tag:start", 0, 0, 0, 0);
+        map.add_object("zone:room", -20, 3, 2, 2);
+        map.add_object("heart", 5, 5, 0, 0);
+
+        assert_eq!(1, map.remove_objects_at(-19, 4));
+        assert_eq!(1, map.remove_objects_at(5, 5));
+        assert_eq!(0, map.remove_objects_at(5, 5));
+
+        let map = Map::from_tmj(&map.to_tmj());
+        let map = map.index(&tileset);
+
+        let mut tiles: Vec<_> = map
+            .tiles()
+            .map(|(x, y, tile)| (x, y, tile.is_wall()))
+            .collect();
+
+        tiles.sort();
+
+        assert_eq!(vec![(-20, 3, false), (0, 0, false)], tiles);
+
+        let objects: Vec<_> =
+            map.objects().iter().map(|object| &object.name).collect();
+
+        assert_eq!(vec!["tag:start"], objects);
+    }
+
+    #[test]
+    fn shapes_are_converted_into_tiles() {
+        let tileset = tileset::Tileset::from_tsx(include_str!(
+            "../../../../assets/levels/tileset.tsx"
+        ));
+
+        let map = Map::from_tmj(
+            r#"{
+                "tilewidth": 32,
+                "tileheight": 32,
+                "layers": [{
+                    "name": "objects",
+                    "objects": [
+                        {
+                            "name": "zone:circle",
+                            "x": 64, "y": 32, "width": 64, "height": 64,
+                            "ellipse": true
+                        },
+                        {
+                            "name": "zone:triangle",
+                            "x": 16, "y": 16, "width": 0, "height": 0,
+                            "polygon": [
+                                { "x": 0, "y": 0 },
+                                { "x": 64, "y": 0 },
+                                { "x": 0, "y": 32 }
+                            ]
+                        }
+                    ]
+                }]
+            }"#,
+        );
+
+        // Shapes should survive saving, too
+        let map = Map::from_tmj(&map.to_tmj());
+        let map = map.index(&tileset);
+        let shapes: Vec<_> = map.objects().iter().map(|o| &o.shape).collect();
+
+        assert_eq!(
+            vec![
+                &indexed::Shape::Ellipse {
+                    center: vec2(2.5, 1.5),
+                    radius: vec2(1.0, 1.0),
+                },
+                &indexed::Shape::Polygon(vec![
+                    vec2(0.0, 0.0),
+                    vec2(2.0, 0.0),
+                    vec2(0.0, 1.0),
+                ]),
+            ],
+            shapes
+        );
+    }
+}
@@ -0,0 +1,142 @@
+use std::f32::consts::PI;
+
+use glam::{vec3, Mat4, Quat, Vec2, Vec3};
+
+use crate::CEILING_HEIGHT;
+
+/// Transform of a model (floor, ceiling or wall) making up the map's
+/// geometry.
+#[derive(Clone, Copy, Debug)]
+pub struct Placement {
+    pub translation: Vec3,
+    pub rotation: Quat,
+    pub scale: Vec3,
+
+    /// How many times the texture repeats along each axis
+    pub uv_divisor: (u8, u8),
+}
+
+impl Placement {
+    pub fn floor(x1: i32, z1: i32, x2: i32, z2: i32, y: f32) -> Self {
+        log::debug!("floor({}, {}, {}, {}, {})", x1, z1, x2, z2, y);
+
+        Self::flat("Floor", x1, z1, x2, z2, y)
+    }
+
+    pub fn ceiling(x1: i32, z1: i32, x2: i32, z2: i32, y: f32) -> Self {
+        log::debug!("ceiling({}, {}, {}, {}, {})", x1, z1, x2, z2, y);
+
+        Self::flat("Ceiling", x1, z1, x2, z2, y)
+    }
+
+    /// Returns placement of an axis-aligned wall spanning from `bottom` to
+    /// `top` (in world units).
+    pub fn wall(
+        x1: i32,
+        z1: i32,
+        x2: i32,
+        z2: i32,
+        rot: u8,
+        bottom: f32,
+        top: f32,
+    ) -> Self {
+        let (x1, x2) = (x1.min(x2), x1.max(x2));
+        let (z1, z2) = (z1.min(z2), z1.max(z2));
+        let dx = x2 - x1 + 1;
+        let dz = z2 - z1 + 1;
+
+        log::debug!(
+            "wall({}, {}, {}, {}, {}, {}, {}); dx={}, dz={}",
+            x1,
+            z1,
+            x2,
+            z2,
+            rot,
+            bottom,
+            top,
+            dx,
+            dz
+        );
+
+        assert!(dx == 1 || dz == 1, "Wall is not axis-aligned");
+        assert!(bottom < top, "Wall has no height");
+
+        let extrude = match rot {
+            0 => vec3(0.0, 0.0, -0.5),
+            1 => vec3(-0.5, 0.0, 0.0),
+            2 => vec3(0.0, 0.0, 0.5),
+            3 => vec3(0.5, 0.0, 0.0),
+            _ => panic!("Invalid wall rotation: {}", rot),
+        };
+
+        let scale = if dx == 1 { dz } else { dx };
+        let height = top - bottom;
+
+        Self {
+            translation: vec3(
+                (x1 as f32 + x2 as f32) / 2.0,
+                bottom,
+                (z1 as f32 + z2 as f32) / 2.0,
+            ) + extrude,
+            rotation: Quat::from_rotation_y(PI / 2.0 * (rot as f32)),
+            scale: vec3((scale as f32) / 2.0, height / 2.0, 1.0),
+            uv_divisor: (scale as _, Self::v_divisor(height)),
+        }
+    }
+
+    /// Returns placement of a wall going from `start` to `end` (in world
+    /// units, on the XZ plane); contrary to [`Self::wall()`], it doesn't have
+    /// to be axis-aligned.
+    pub fn segment(start: Vec2, end: Vec2, bottom: f32, top: f32) -> Self {
+        log::debug!("segment({}, {}, {}, {})", start, end, bottom, top);
+
+        let dir = end - start;
+        let length = dir.length();
+        let height = top - bottom;
+
+        assert!(length > 0.0, "Wall has no length");
+        assert!(bottom < top, "Wall has no height");
+
+        let center = (start + end) / 2.0;
+
+        Self {
+            translation: vec3(center.x, bottom, center.y),
+            rotation: Quat::from_rotation_y((-dir.y).atan2(dir.x)),
+            scale: vec3(length / 2.0, height / 2.0, 1.0),
+            uv_divisor: (length.round().max(1.0) as _, Self::v_divisor(height)),
+        }
+    }
+
+    pub fn matrix(&self) -> Mat4 {
+        Mat4::from_scale_rotation_translation(
+            self.scale,
+            self.rotation,
+            self.translation,
+        )
+    }
+
+    fn flat(kind: &str, x1: i32, z1: i32, x2: i32, z2: i32, y: f32) -> Self {
+        let (x1, x2) = (x1.min(x2), x1.max(x2));
+        let (z1, z2) = (z1.min(z2), z1.max(z2));
+        let dx = x2 - x1 + 1;
+        let dz = z2 - z1 + 1;
+
+        assert!(dx > 0 && dz > 0, "{} has no area", kind);
+
+        Self {
+            translation: vec3(
+                (x1 + x2) as f32 / 2.0,
+                y,
+                (z1 + z2) as f32 / 2.0,
+            ),
+            rotation: Quat::IDENTITY,
+            scale: vec3((dx as f32) / 2.0, 1.0, (dz as f32) / 2.0),
+            uv_divisor: (dx as _, dz as _),
+        }
+    }
+
+    /// Keeps the texture's aspect ratio the same as for regular walls.
+    fn v_divisor(height: f32) -> u8 {
+        (height / CEILING_HEIGHT).round().max(1.0) as _
+    }
+}
@@ -0,0 +1,91 @@
+use serde::Deserialize;
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Tileset {
+    #[serde(rename = "$value")]
+    items: Vec<Item>,
+}
+
+impl Tileset {
+    pub fn from_tsx(data: &str) -> Self {
+        quick_xml::de::from_str(data).expect("Couldn't deserialize tileset")
+    }
+
+    pub fn tile(&self, id: u8) -> &Tile {
+        let id = id.to_string();
+
+        self.items
+            .iter()
+            .flat_map(|item| item.as_tile())
+            .find(|tile| tile.id == id)
+            .unwrap_or_else(|| panic!("Unknown tile: {}", id))
+    }
+
+    /// Returns textures of all the tiles, in the tileset's order.
+    pub fn textures(&self) -> impl Iterator<Item = &str> {
+        self.items
+            .iter()
+            .flat_map(|item| item.as_tile())
+            .map(|tile| tile.texture())
+    }
+
+    /// Looks for a tile using given texture (e.g. `wall.basic`).
+    pub fn texture(&self, name: &str) -> Option<&str> {
+        self.items
+            .iter()
+            .flat_map(|item| item.as_tile())
+            .map(|tile| tile.texture())
+            .find(|texture| *texture == name)
+    }
+
+    /// Returns id of the tile using given texture (e.g. `wall.basic`).
+    pub fn id(&self, texture: &str) -> Option<u8> {
+        self.items
+            .iter()
+            .flat_map(|item| item.as_tile())
+            .find(|tile| tile.texture() == texture)
+            .and_then(|tile| tile.id.parse().ok())
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Item {
+    Grid,
+    Tile(Tile),
+}
+
+impl Item {
+    pub fn as_tile(&self) -> Option<&Tile> {
+        match self {
+            Item::Tile(tile) => Some(tile),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Tile {
+    id: String,
+    #[serde(rename = "$value")]
+    image: TileImage,
+}
+
+impl Tile {
+    pub fn image(&self) -> &str {
+        &self.image.source
+    }
+
+    pub fn texture(&self) -> &str {
+        self.image()
+            .strip_prefix("../models/")
+            .unwrap()
+            .strip_suffix(".png")
+            .unwrap()
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct TileImage {
+    source: String,
+}
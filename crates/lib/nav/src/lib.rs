@@ -1,4 +1,4 @@
-use doome_geo::{diag, Polygon};
+use doome_geo::{diag, Aabb, Polygon};
 use glam::{vec2, Vec2};
 use image::RgbaImage;
 
@@ -105,6 +105,35 @@ impl NavData {
         )
     }
 
+    /// Returns impassable cells lying (at least partially) within given
+    /// distance from `center`; useful for debugging.
+    pub fn impassable_cells_around(
+        &self,
+        center: Vec2,
+        radius: f32,
+    ) -> impl Iterator<Item = Aabb> + '_ {
+        let min = ((center - radius - self.area_start) / self.raster_unit)
+            .floor()
+            .max(Vec2::ZERO);
+
+        let max = ((center + radius - self.area_start) / self.raster_unit)
+            .ceil()
+            .min(vec2(self.width as f32, self.height as f32));
+
+        let (x1, y1) = (min.x as isize, min.y as isize);
+        let (x2, y2) = (max.x as isize, max.y as isize);
+
+        (x1..x2)
+            .flat_map(move |x| (y1..y2).map(move |y| (x, y)))
+            .filter(|pos| !self.is_passable(*pos))
+            .map(|(x, y)| {
+                let min = self.area_start
+                    + vec2(x as f32, y as f32) * self.raster_unit;
+
+                Aabb::new(min, min + self.raster_unit)
+            })
+    }
+
     pub fn rasterize(&self) -> RgbaImage {
         let mut img = RgbaImage::new(self.width as u32, self.height as u32);
 
@@ -7,7 +7,7 @@ mod serializer;
 use std::fmt;
 use std::ops::{Index, IndexMut};
 
-use glam::{vec4, Vec3};
+use glam::{vec4, Vec3, Vec4};
 use instant::{Duration, Instant};
 
 use self::axis::*;
@@ -55,6 +55,16 @@ impl GeometryIndexer {
         Some(Box::new(index))
     }
 
+    /// Returns how many items the index built for given geometry takes; the
+    /// index has to fit within [`crate::STATIC_GEOMETRY_INDEX_SIZE`].
+    pub fn index_len(geometry: &StaticGeometry) -> usize {
+        if geometry.iter().next().is_none() {
+            return 0;
+        }
+
+        serializer::encode(RopedBvh::build(Bvh::build(geometry))).len()
+    }
+
     fn measure<T>(f: impl FnOnce() -> T) -> (T, Duration) {
         let tt = Instant::now();
         let val = f();
@@ -3,6 +3,24 @@ use doome_shader_common::STATIC_GEOMETRY_INDEX_SIZE;
 use super::*;
 
 pub fn serialize(fbvh: RopedBvh) -> (StaticGeometryIndex, usize) {
+    let mut out = encode(fbvh);
+    let out_len = out.len();
+
+    while out.len() < STATIC_GEOMETRY_INDEX_SIZE {
+        out.push(vec4(0.0, 0.0, 0.0, 0.0));
+    }
+
+    let out = out.try_into().unwrap_or_else(|out: Vec<_>| {
+        panic!(
+            "ayy ayy the geometry index is too large -- produced {} items",
+            out.len()
+        );
+    });
+
+    (StaticGeometryIndex::new(out), out_len)
+}
+
+pub fn encode(fbvh: RopedBvh) -> Vec<Vec4> {
     let mut out = Vec::new();
 
     for node in fbvh {
@@ -45,20 +63,5 @@ pub fn serialize(fbvh: RopedBvh) -> (StaticGeometryIndex, usize) {
         out.push(v2);
     }
 
-    let out_len = out.len();
-
-    // ----
-
-    while out.len() < STATIC_GEOMETRY_INDEX_SIZE {
-        out.push(vec4(0.0, 0.0, 0.0, 0.0));
-    }
-
-    let out = out.try_into().unwrap_or_else(|out: Vec<_>| {
-        panic!(
-            "ayy ayy the geometry index is too large -- produced {} items",
-            out.len()
-        );
-    });
-
-    (StaticGeometryIndex::new(out), out_len)
+    out
 }
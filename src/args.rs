@@ -1,12 +1,14 @@
 use std::fmt;
 
+use bevy::prelude::Resource;
 use bevy::window::WindowMode;
 use clap::{Parser, ValueEnum};
 use doome_engine::{HEIGHT, WIDTH};
 
+use crate::levels::LevelRef;
 use crate::settings::WINDOW_SCALE;
 
-#[derive(Debug, Parser)]
+#[derive(Debug, Parser, Resource)]
 pub struct Args {
     #[arg(long)]
     width: Option<f32>,
@@ -16,6 +18,10 @@ pub struct Args {
 
     #[arg(long, default_value_t = ArgsWindowMode::Windowed)]
     mode: ArgsWindowMode,
+
+    /// Level to start the game at, either its id or its name (e.g. `level3`)
+    #[arg(long)]
+    pub level: Option<LevelRef>,
 }
 
 impl Args {
@@ -25,6 +31,7 @@ impl Args {
             width: None,
             height: None,
             mode: ArgsWindowMode::Windowed,
+            level: None,
         }
     }
 
@@ -1,15 +1,22 @@
+use std::collections::HashSet;
 use std::time::Duration;
 
 use bevy::prelude::*;
 use doome_bevy::audio::Audio;
+use doome_bevy::convert::graphical_to_physical;
 use doome_bevy::health::Health;
+use doome_bevy::physics::components::{layer, Body};
 use doome_bevy::physics::events::Collision;
+use doome_bevy::physics::{Interpolated, PhysicsQuery, QueryFilter};
 use doome_bevy::prelude::Assets;
 
 use crate::explosions::spawn_explosion;
 use crate::player::AddScreenShake;
 use crate::weapons::BulletType;
 
+/// Impulse received by bodies at the very center of an explosion
+const EXPLOSION_IMPULSE: f32 = 30.0;
+
 pub struct BulletsPlugin;
 
 impl Plugin for BulletsPlugin {
@@ -46,46 +53,153 @@ fn collide_and_apply_damage(
     mut commands: Commands,
     assets: Res<Assets>,
     mut audio: ResMut<Audio>,
+    physics: PhysicsQuery,
     mut collisions: EventReader<Collision>,
     mut health: Query<&mut Health>,
-    bullets: Query<(&Bullet, &Transform)>,
+    mut bodies: Query<&mut Body>,
+    bullets: Query<&Bullet>,
+    transforms: Query<(&Transform, Option<&Interpolated>)>,
     mut dmg_events: EventWriter<DamageDealt>,
     mut screen_shakes: EventWriter<AddScreenShake>,
 ) {
+    // Physics can run a few times per frame, so a bullet might report more
+    // than one collision before it gets despawned
+    let mut spent_bullets = HashSet::new();
+
     for collision in collisions.iter() {
-        if let Ok((bullet, transform)) = bullets.get(collision.entity_a) {
-            if is_a_bullet(&bullets, collision.entity_b) {
-                continue;
-            }
+        let Ok(bullet) = bullets.get(collision.entity_a) else { continue };
 
-            if let BulletType::Rocket { explosion_radius } = bullet.bullet_type
-            {
-                spawn_explosion(
-                    &mut commands,
-                    &assets,
-                    &mut audio,
-                    transform.clone().with_scale(Vec3::ONE * explosion_radius),
-                );
-                screen_shakes.send(AddScreenShake(0.5));
-            }
+        if !spent_bullets.insert(collision.entity_a) {
+            continue;
+        }
 
-            if let Some(mut entity) = commands.get_entity(collision.entity_a) {
-                entity.despawn();
-            }
+        // Collisions happen at the physics step's positions, which might be
+        // ahead of the interpolated ones we'd get from `Transform`
+        let (transform, interpolated) =
+            transforms.get(collision.entity_a).unwrap();
+
+        let transform = Transform {
+            translation: actual_translation(transform, interpolated),
+            ..*transform
+        };
+
+        if let BulletType::Rocket {
+            explosion_radius,
+            splash_damage,
+        } = bullet.bullet_type
+        {
+            spawn_explosion(
+                &mut commands,
+                &assets,
+                &mut audio,
+                transform.with_scale(Vec3::ONE * explosion_radius),
+            );
+            screen_shakes.send(AddScreenShake(0.5));
 
-            if let Ok(mut health) = health.get_mut(collision.entity_b) {
-                dmg_events.send(DamageDealt {
-                    amount: bullet.damage,
-                    entity: collision.entity_b,
-                });
+            let center = graphical_to_physical(transform.translation);
 
-                health.health = (health.health - bullet.damage)
-                    .clamp(0.0, health.max_health);
+            for (entity, falloff, dir) in find_entities_caught_in_explosion(
+                &physics,
+                &transforms,
+                center,
+                explosion_radius,
+                collision.entity_b,
+            ) {
+                deal_damage(
+                    &mut health,
+                    &mut dmg_events,
+                    entity,
+                    splash_damage * falloff,
+                );
+
+                if let Ok(mut body) = bodies.get_mut(entity) {
+                    body.apply_impulse(dir * EXPLOSION_IMPULSE * falloff);
+                }
             }
         }
+
+        if let Some(mut entity) = commands.get_entity(collision.entity_a) {
+            entity.despawn();
+        }
+
+        deal_damage(
+            &mut health,
+            &mut dmg_events,
+            collision.entity_b,
+            bullet.damage,
+        );
     }
 }
 
+/// Returns entities within explosion's radius which aren't hidden behind
+/// walls, together with how strongly they are hit (from `1.0` at the center
+/// down to `0.0` at the edge) and the direction they get pushed in.
+fn find_entities_caught_in_explosion(
+    physics: &PhysicsQuery,
+    transforms: &Query<(&Transform, Option<&Interpolated>)>,
+    center: Vec2,
+    radius: f32,
+    hit_entity: Entity,
+) -> Vec<(Entity, f32, Vec2)> {
+    let filter = QueryFilter::new(layer::PLAYER | layer::ENEMY | layer::WORLD)
+        .excluding(hit_entity);
+
+    physics
+        .overlap_circle(center, radius, filter)
+        .into_iter()
+        .filter_map(|entity| {
+            let (transform, interpolated) = transforms.get(entity).ok()?;
+
+            let pos = graphical_to_physical(actual_translation(
+                transform,
+                interpolated,
+            ));
+
+            let distance = center.distance(pos);
+
+            let is_visible = physics
+                .raycast(
+                    center,
+                    pos - center,
+                    distance,
+                    QueryFilter::new(layer::WORLD),
+                )
+                .map_or(true, |hit| hit.entity == entity);
+
+            if !is_visible {
+                return None;
+            }
+
+            let falloff = (1.0 - distance / radius).clamp(0.0, 1.0);
+
+            Some((entity, falloff, (pos - center).normalize_or_zero()))
+        })
+        .collect()
+}
+
+/// See: [`Interpolated::actual_translation()`].
+fn actual_translation(
+    transform: &Transform,
+    interpolated: Option<&Interpolated>,
+) -> Vec3 {
+    interpolated.map_or(transform.translation, |interpolated| {
+        interpolated.actual_translation(transform)
+    })
+}
+
+fn deal_damage(
+    health: &mut Query<&mut Health>,
+    dmg_events: &mut EventWriter<DamageDealt>,
+    entity: Entity,
+    amount: f32,
+) {
+    let Ok(mut health) = health.get_mut(entity) else { return };
+
+    dmg_events.send(DamageDealt { amount, entity });
+
+    health.health = (health.health - amount).clamp(0.0, health.max_health);
+}
+
 fn collect_garbage(
     time: Res<Time>,
     mut commands: Commands,
@@ -99,7 +213,3 @@ fn collect_garbage(
         }
     }
 }
-
-fn is_a_bullet(bullets: &Query<(&Bullet, &Transform)>, entity: Entity) -> bool {
-    bullets.get(entity).is_ok()
-}
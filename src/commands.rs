@@ -1,16 +1,22 @@
 mod cmd;
+mod physics_dump;
 
 use std::sync::Arc;
 
+use anyhow::Context;
 use bevy::app::AppExit;
 use bevy::ecs::system::SystemParam;
 use doome_bevy::physics::PhysicsEnabled;
 use doome_bevy::rendering_options::RenderingOptions;
 
 pub use self::cmd::*;
+use self::physics_dump::DumpedCollider;
+pub use self::physics_dump::PhysicsDumpFormat;
+use crate::editor::{SaveEditedMap, ToggleEditor};
 use crate::inventory::Inventory;
 use crate::music::SwitchTrack;
 use crate::prelude::*;
+use crate::save::{LoadGame, SaveGame};
 use crate::ui;
 use crate::weapons::{PrefabWeapons, Weapon, WeaponDefinition, WeaponSprites};
 
@@ -34,11 +40,15 @@ pub struct EventWriters<'w, 's> {
     sync_nav_data_tx: EventWriter<'w, 's, SyncNavData>,
     goto_level_tx: EventWriter<'w, 's, GotoLevel>,
     switch_track_tx: EventWriter<'w, 's, SwitchTrack>,
+    save_game_tx: EventWriter<'w, 's, SaveGame>,
+    load_game_tx: EventWriter<'w, 's, LoadGame>,
+    toggle_editor_tx: EventWriter<'w, 's, ToggleEditor>,
+    save_edited_map_tx: EventWriter<'w, 's, SaveEditedMap>,
 }
 
 #[derive(SystemParam)]
 struct Queries<'w, 's> {
-    colliders: Query<'w, 's, &'static Collider>,
+    colliders: Query<'w, 's, (Entity, &'static Collider)>,
     all_entities: Query<'w, 's, Entity>,
     player: Query<'w, 's, Entity, With<Player>>,
     enemies: Query<'w, 's, Entity, With<Enemy>>,
@@ -53,12 +63,15 @@ fn handle_commands(
     mut commands: Commands,
     assets: Res<Assets>,
     prefab_weapons: Res<PrefabWeapons>,
+    levels: Res<LevelRegistry>,
+    campaign: Res<Campaign>,
     // Mutable resources
     mut rendering_options: ResMut<RenderingOptions>,
     mut physics_enabled: ResMut<PhysicsEnabled>,
     mut input_lock: ResMut<InputLock>,
     mut weapon_sprites: ResMut<ui::gun::State>,
     mut enemy_ai_enabled: ResMut<EnemyAiEnabled>,
+    scripts: Res<ScriptEngine>,
     // Queries
     mut queries: Queries,
     // Event writers
@@ -88,6 +101,59 @@ fn handle_commands(
                 }
             }
 
+            Command::ListLevels => {
+                for (level, def) in levels.iter() {
+                    let kind = if def.map.is_some() { "map" } else { "code" };
+
+                    let reached = if campaign
+                        .furthest_level()
+                        .map_or(false, |furthest| level <= furthest)
+                    {
+                        ", reached"
+                    } else {
+                        ""
+                    };
+
+                    event_writers.output_tx.send(CommandOutput(format!(
+                        "{}: {} ({}{})",
+                        level.id(),
+                        def.name,
+                        kind,
+                        reached
+                    )));
+                }
+            }
+
+            Command::Respawn => {
+                let Some(checkpoint) = campaign.checkpoint() else {
+                    event_writers.output_tx.send(CommandOutput(
+                        "No checkpoint has been reached yet".into(),
+                    ));
+
+                    continue;
+                };
+
+                let player = queries.player.single();
+
+                *queries.transforms.get_mut(player).unwrap() =
+                    checkpoint.transform;
+
+                checkpoint.loadout.apply(
+                    &prefab_weapons,
+                    &mut queries.weapons.get_mut(player).unwrap(),
+                    &mut queries.inventory.single_mut(),
+                    &mut weapon_sprites,
+                );
+            }
+
+            Command::SaveGame { name } => {
+                event_writers.save_game_tx.send(SaveGame(name));
+            }
+
+            Command::LoadGame { name } => {
+                event_writers.load_game_tx.send(LoadGame(name));
+            }
+
             Command::Position { entity } => {
                 let entity = resolve_entity(entity, &queries.player);
                 let transform = queries.transforms.get(entity).unwrap();
@@ -110,6 +176,10 @@ fn handle_commands(
                     queries.healths.get_mut(entity).unwrap();
 
                 health_component.health = health;
+
+                if health > 0.0 {
+                    health_component.is_dead = false;
+                }
             }
 
             Command::Heal { entity, amount } => {
@@ -124,23 +194,7 @@ fn handle_commands(
                 spawnable,
                 position,
             } => {
-                let entity = match spawnable {
-                    Spawnable::MothMonster => {
-                        MothMonster::spawn(&assets, &mut commands, position)
-                    }
-                    Spawnable::Doome => {
-                        Doome::spawn(&assets, &mut commands, position)
-                    }
-                    Spawnable::Heart => Picker::heart()
-                        .with_position(position.xz())
-                        .spawn(&assets, &mut commands),
-                    Spawnable::RiflePickup => Picker::rifle()
-                        .with_position(position.xz())
-                        .spawn(&assets, &mut commands),
-                    Spawnable::RpgPickup => Picker::rpg()
-                        .with_position(position.xz())
-                        .spawn(&assets, &mut commands),
-                };
+                let entity = spawnable.spawn(&assets, &mut commands, position);
 
                 event_writers.output_tx.send(CommandOutput(format!(
                     "Spawned {spawnable:?}: {}",
@@ -182,43 +236,35 @@ fn handle_commands(
                 }
             }
 
-            Command::DumpPhysics => {
-                use std::fmt::Write;
-
-                let mut n = 0;
-                let mut lines = String::new();
-                for entity in queries.all_entities.iter() {
-                    let mut points = "{polygon}(".to_string();
-
-                    let Ok(transform) = queries.transforms.get(entity) else { continue };
-                    let Ok(collider) = queries.colliders.get(entity) else { continue };
-
-                    let polygon = collider.to_polygon(transform);
-
-                    let mut is_first = true;
-                    for point in polygon.points() {
-                        writeln!(
-                            lines,
-                            "a_{{{n}}} = ({}, {})",
-                            point.x, point.y
-                        )
-                        .unwrap();
-
-                        if is_first {
-                            is_first = false;
-                        } else {
-                            points.push_str(", ");
-                        }
-
-                        write!(points, "a_{{{n}}}").unwrap();
-
-                        n += 1;
+            Command::DumpPhysics { format, path } => {
+                let colliders: Vec<_> = queries
+                    .colliders
+                    .iter()
+                    .filter_map(|(entity, collider)| {
+                        let transform = queries.transforms.get(entity).ok()?;
+
+                        Some(DumpedCollider {
+                            entity,
+                            layers: collider.layers(),
+                            is_detector: collider.is_detector(),
+                            polygon: collider.to_polygon(transform),
+                        })
+                    })
+                    .collect();
+
+                let path =
+                    path.unwrap_or_else(|| format.default_path().to_owned());
+
+                let dump = physics_dump::dump(format, &colliders);
+
+                let msg = match std::fs::write(&path, dump) {
+                    Ok(()) => format!("Physics dumped to {path}"),
+                    Err(err) => {
+                        format!("Couldn't dump physics to {path}: {err}")
                     }
+                };
 
-                    writeln!(lines, "{points})").unwrap();
-                }
-
-                std::fs::write("physics_dump", lines).unwrap();
+                event_writers.output_tx.send(CommandOutput(msg));
             }
 
             Command::GotoLevel { level } => {
@@ -263,11 +309,20 @@ fn handle_commands(
                 event_writers.switch_track_tx.send(SwitchTrack(track));
             }
 
-            Command::ToggleDebug => {
+            Command::ToggleDebug { overlay: None } => {
                 rendering_options.debug_pass_enabled =
                     !rendering_options.debug_pass_enabled;
             }
 
+            Command::ToggleDebug {
+                overlay: Some(overlay),
+            } => {
+                if !rendering_options.debug_overlays.remove(&overlay) {
+                    rendering_options.debug_overlays.insert(overlay);
+                    rendering_options.debug_pass_enabled = true;
+                }
+            }
+
             Command::ToggleSSE => {
                 rendering_options.sse_enabled = !rendering_options.sse_enabled;
             }
@@ -275,10 +330,75 @@ fn handle_commands(
             Command::ToggleAi => {
                 enemy_ai_enabled.0 = !enemy_ai_enabled.0;
             }
+
+            Command::Editor => {
+                event_writers.toggle_editor_tx.send(ToggleEditor);
+            }
+
+            Command::EditorSave { path } => {
+                event_writers.save_edited_map_tx.send(SaveEditedMap(path));
+            }
+
+            Command::Exec { path } => {
+                let result = std::fs::read_to_string(&path)
+                    .with_context(|| format!("Couldn't read {}", path))
+                    .and_then(|code| scripts.eval(&code));
+
+                report_script_result(&mut event_writers.output_tx, result);
+            }
+
+            Command::Eval { code } => {
+                let result = scripts.eval(&code);
+
+                report_script_result(&mut event_writers.output_tx, result);
+            }
         }
     }
 }
 
+impl Spawnable {
+    pub fn spawn(
+        self,
+        assets: &Assets,
+        commands: &mut Commands,
+        position: Vec3,
+    ) -> Entity {
+        match self {
+            Spawnable::MothMonster => {
+                MothMonster::spawn(assets, commands, position)
+            }
+            Spawnable::Doome => Doome::spawn(assets, commands, position),
+            Spawnable::Heart => Picker::heart()
+                .with_position(position.xz())
+                .spawn(assets, commands),
+            Spawnable::RiflePickup => Picker::rifle()
+                .with_position(position.xz())
+                .spawn(assets, commands),
+            Spawnable::RpgPickup => Picker::rpg()
+                .with_position(position.xz())
+                .spawn(assets, commands),
+            Spawnable::FlashlightPickup => Picker::flashlight()
+                .with_position(position.xz())
+                .spawn(assets, commands),
+        }
+    }
+}
+
+fn report_script_result(
+    output_tx: &mut EventWriter<CommandOutput>,
+    result: anyhow::Result<String>,
+) {
+    let output = match result {
+        Ok(output) if output.is_empty() => return,
+        Ok(output) => output,
+        Err(err) => format!("Error: {:#}", err),
+    };
+
+    for line in output.lines() {
+        output_tx.send(CommandOutput(line.to_owned()));
+    }
+}
+
 fn give_gun_to_player(
     player: &Query<Entity, With<Player>>,
     weapons: &mut Query<&mut Weapon>,
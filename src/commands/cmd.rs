@@ -3,10 +3,13 @@ use std::str::FromStr;
 
 use anyhow::{anyhow, Context};
 use bevy::prelude::Entity;
+use doome_bevy::debug::DebugOverlay;
 use glam::Vec3;
 
+use crate::commands::physics_dump::PhysicsDumpFormat;
 use crate::music::MusicTrack;
 use crate::prelude::*;
+use crate::save::QUICK_SAVE;
 
 #[derive(Debug, Clone)]
 pub enum Command {
@@ -22,6 +25,22 @@ pub enum Command {
     // list-entities
     ListEntities,
 
+    // list-levels
+    ListLevels,
+
+    /// Moves player back to the last checkpoint they've reached
+    Respawn,
+
+    // Example: save, save my-save
+    SaveGame {
+        name: String,
+    },
+
+    // Example: load, load my-save
+    LoadGame {
+        name: String,
+    },
+
     // Displays the position of a given entity
     //  Example: position player
     //  Example: pos player
@@ -67,11 +86,17 @@ pub enum Command {
 
     SyncNavData,
     NoClip,
-    DumpPhysics,
 
-    // Example: goto-level 2
+    // Writes colliders into a file, for offline inspection
+    // Example: dump-physics, dump-physics svg, dump-physics json dump.json
+    DumpPhysics {
+        format: PhysicsDumpFormat,
+        path: Option<String>,
+    },
+
+    // Example: goto-level 2, goto-level level2
     GotoLevel {
-        level: Level,
+        level: LevelRef,
     },
 
     Give {
@@ -80,14 +105,39 @@ pub enum Command {
     SwitchTrack {
         track: MusicTrack,
     },
-    /// Toggles the debug mode
-    ToggleDebug,
+    /// Toggles the debug mode or one of its overlays
+    // Example: toggle-debug, toggle-debug contacts
+    ToggleDebug {
+        overlay: Option<DebugOverlay>,
+    },
 
     /// Toggles screen space effects
     ToggleSSE,
 
     /// Toggles enemy AI on/off
     ToggleAi,
+
+    /// Toggles the level editor
+    Editor,
+
+    // Saves map edited in the level editor
+    // Example: editor-save, editor-save assets/levels/my-level.tmj
+    EditorSave {
+        path: Option<String>,
+    },
+
+    // Runs a script from given file
+    // Example: exec scripts/arena.rhai
+    Exec {
+        path: String,
+    },
+
+    // Runs a script; everything after `eval` (including new lines) is
+    // treated as the code
+    // Example: eval say("hi!")
+    Eval {
+        code: String,
+    },
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -97,6 +147,7 @@ pub enum Spawnable {
     Heart,
     RpgPickup,
     RiflePickup,
+    FlashlightPickup,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -130,6 +181,16 @@ impl FromStr for Command {
             "lock-input" => Ok(Command::LockInput),
             "unlock-input" => Ok(Command::UnlockInput),
             "list-entities" => Ok(Command::ListEntities),
+            "list-levels" => Ok(Command::ListLevels),
+            "respawn" => Ok(Command::Respawn),
+
+            "save" => Ok(Command::SaveGame {
+                name: parts.next().unwrap_or(QUICK_SAVE).to_owned(),
+            }),
+
+            "load" => Ok(Command::LoadGame {
+                name: parts.next().unwrap_or(QUICK_SAVE).to_owned(),
+            }),
 
             "pos" | "position" => {
                 let entity = parts.next().context("No entity")?;
@@ -197,15 +258,24 @@ impl FromStr for Command {
 
             "sync-nav-data" => Ok(Command::SyncNavData),
             "noclip" => Ok(Command::NoClip),
-            "dump-physics" => Ok(Command::DumpPhysics),
+            "dump-physics" => {
+                let format = parts
+                    .next()
+                    .map(|format| format.parse())
+                    .transpose()?
+                    .unwrap_or(PhysicsDumpFormat::Desmos);
+
+                Ok(Command::DumpPhysics {
+                    format,
+                    path: parts.next().map(ToOwned::to_owned),
+                })
+            }
 
             "goto-level" => {
                 let level = parts
                     .next()
-                    .context("Missing level-id")?
-                    .parse()
-                    .map_err(|err| anyhow!("{}", err))
-                    .context("Invalid level-id")?;
+                    .context("Missing level-id or level-name")?
+                    .parse()?;
 
                 Ok(Command::GotoLevel { level })
             }
@@ -224,12 +294,43 @@ impl FromStr for Command {
                 Ok(Command::SwitchTrack { track })
             }
 
-            "toggle-debug" => Ok(Command::ToggleDebug),
+            "toggle-debug" => {
+                let overlay =
+                    parts.next().map(|overlay| overlay.parse()).transpose()?;
+
+                Ok(Command::ToggleDebug { overlay })
+            }
 
             "toggle-sse" => Ok(Command::ToggleSSE),
 
             "toggle-ai" => Ok(Command::ToggleAi),
 
+            "editor" => Ok(Command::Editor),
+
+            "editor-save" => Ok(Command::EditorSave {
+                path: parts.next().map(ToOwned::to_owned),
+            }),
+
+            "exec" => {
+                let path = parts.next().context("Missing path")?;
+
+                Ok(Command::Exec {
+                    path: path.to_owned(),
+                })
+            }
+
+            "eval" => {
+                let code = s.trim_start()[cmd.len()..].trim();
+
+                if code.is_empty() {
+                    return Err(anyhow!("Missing code"));
+                }
+
+                Ok(Command::Eval {
+                    code: code.to_owned(),
+                })
+            }
+
             _ => Err(anyhow!("Failed to parse command: {s}")),
         }
     }
@@ -269,6 +370,7 @@ impl FromStr for Spawnable {
             "heart" => Ok(Spawnable::Heart),
             "rpg-pickup" => Ok(Spawnable::RpgPickup),
             "rifle-pickup" => Ok(Spawnable::RiflePickup),
+            "flashlight-pickup" => Ok(Spawnable::FlashlightPickup),
             _ => Err(anyhow!("Invalid spawnable: {s}")),
         }
     }
@@ -315,6 +417,19 @@ impl FromStr for Item {
     }
 }
 
+impl FromStr for PhysicsDumpFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "desmos" => Ok(Self::Desmos),
+            "svg" => Ok(Self::Svg),
+            "json" => Ok(Self::Json),
+            _ => Err(anyhow!("Invalid physics dump format: {s}")),
+        }
+    }
+}
+
 impl FromStr for MusicTrack {
     type Err = anyhow::Error;
 
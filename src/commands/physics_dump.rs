@@ -0,0 +1,146 @@
+use std::fmt::Write;
+
+use doome_geo::{Aabb, Polygon};
+use serde_json::json;
+
+use crate::prelude::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PhysicsDumpFormat {
+    /// Text file that can be pasted into Desmos
+    Desmos,
+    Svg,
+    Json,
+}
+
+impl PhysicsDumpFormat {
+    pub fn default_path(&self) -> &'static str {
+        match self {
+            Self::Desmos => "physics_dump",
+            Self::Svg => "physics_dump.svg",
+            Self::Json => "physics_dump.json",
+        }
+    }
+}
+
+/// Collider as seen by the physics dump
+pub struct DumpedCollider {
+    pub entity: Entity,
+    pub layers: CollisionLayers,
+    pub is_detector: bool,
+    pub polygon: Polygon,
+}
+
+pub fn dump(format: PhysicsDumpFormat, colliders: &[DumpedCollider]) -> String {
+    match format {
+        PhysicsDumpFormat::Desmos => dump_desmos(colliders),
+        PhysicsDumpFormat::Svg => dump_svg(colliders),
+        PhysicsDumpFormat::Json => dump_json(colliders),
+    }
+}
+
+fn dump_desmos(colliders: &[DumpedCollider]) -> String {
+    let mut n = 0;
+    let mut lines = String::new();
+
+    for collider in colliders {
+        let mut points = "{polygon}(".to_string();
+        let mut is_first = true;
+
+        for point in collider.polygon.points() {
+            writeln!(lines, "a_{{{n}}} = ({}, {})", point.x, point.y).unwrap();
+
+            if is_first {
+                is_first = false;
+            } else {
+                points.push_str(", ");
+            }
+
+            write!(points, "a_{{{n}}}").unwrap();
+
+            n += 1;
+        }
+
+        writeln!(lines, "{points})").unwrap();
+    }
+
+    lines
+}
+
+fn dump_svg(colliders: &[DumpedCollider]) -> String {
+    const MARGIN: f32 = 1.0;
+
+    let aabb = colliders
+        .iter()
+        .map(|collider| collider.polygon.aabb())
+        .reduce(Aabb::union)
+        .unwrap_or_else(|| Aabb::new(Vec2::ZERO, Vec2::ZERO));
+
+    let min = aabb.min - MARGIN;
+    let size = aabb.max - aabb.min + 2.0 * MARGIN;
+
+    let mut svg = String::new();
+
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}">"#,
+        min.x, min.y, size.x, size.y,
+    )
+    .unwrap();
+
+    for collider in colliders {
+        let points: Vec<_> = collider
+            .polygon
+            .points()
+            .iter()
+            .map(|point| format!("{},{}", point.x, point.y))
+            .collect();
+
+        let (color, dash) = if collider.is_detector {
+            ("orange", r#" stroke-dasharray="0.2""#)
+        } else {
+            ("red", "")
+        };
+
+        writeln!(
+            svg,
+            r#"  <polygon points="{}" fill="none" stroke="{}" stroke-width="0.05"{}><title>{:?}</title></polygon>"#,
+            points.join(" "),
+            color,
+            dash,
+            collider.entity,
+        )
+        .unwrap();
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn dump_json(colliders: &[DumpedCollider]) -> String {
+    let colliders: Vec<_> = colliders
+        .iter()
+        .map(|collider| {
+            let aabb = collider.polygon.aabb();
+
+            json!({
+                "entity": collider.entity.to_bits(),
+                "layer": collider.layers.layer,
+                "mask": collider.layers.mask,
+                "detector": collider.is_detector,
+                "points": collider
+                    .polygon
+                    .points()
+                    .iter()
+                    .map(|point| [point.x, point.y])
+                    .collect::<Vec<_>>(),
+                "aabb": {
+                    "min": [aabb.min.x, aabb.min.y],
+                    "max": [aabb.max.x, aabb.max.y],
+                },
+            })
+        })
+        .collect();
+
+    serde_json::to_string_pretty(&colliders).unwrap()
+}
@@ -0,0 +1,570 @@
+use std::f32::consts::PI;
+
+use bevy::input::mouse::MouseMotion;
+use doome_bevy::physics::PhysicsEnabled;
+use doome_levels::generator::KEY_COLORS;
+use doome_levels::{map, tileset};
+
+use crate::prelude::*;
+
+const MOVEMENT_SPEED: f32 = 6.0;
+
+/// Level editor, toggled through the `editor` command.
+///
+/// It allows to fly around the current level and place (left mouse button) or
+/// remove (right mouse button) stuff at the tile pointed at by the camera;
+/// every change rebuilds the level, so it can be seen in the raytracer right
+/// away.
+///
+/// Controls:
+///
+/// - `WASD` + mouse - flying around, `Space` / `LShift` - going up or down,
+/// - `1`..`9` - picking the tool (floors, walls, ceilings, doors, keys,
+///   torches, tags, zones, lights),
+/// - `Q` / `E` - picking tile's texture or door's and key's color.
+///
+/// The map can be saved through `editor-save`.
+pub struct EditorPlugin;
+
+impl Plugin for EditorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ToggleEditor>()
+            .add_event::<SaveEditedMap>()
+            .init_resource::<Editor>()
+            .add_system(toggle)
+            .add_system(save)
+            .add_system(process_input)
+            .add_system(rebuild.after(process_input))
+            .add_system(
+                sync_camera
+                    .after(process_input)
+                    .after(crate::player::sync_camera),
+            );
+    }
+}
+
+pub struct ToggleEditor;
+
+/// Saves the edited map; when path is missing, map is saved back into
+/// `assets/levels`.
+pub struct SaveEditedMap(pub Option<String>);
+
+#[derive(Resource, Default)]
+pub struct Editor {
+    state: Option<EditorState>,
+}
+
+struct EditorState {
+    level: &'static str,
+    map: map::Map,
+    tileset: tileset::Tileset,
+    tool: Tool,
+    texture_idx: usize,
+    color_idx: Option<usize>,
+    zone_start: Option<(i32, i32)>,
+    last_door: Option<String>,
+    next_id: usize,
+    is_dirty: bool,
+    camera: Vec3,
+    yaw: f32,
+    pitch: f32,
+    status: Entity,
+    enemy_ai_was_enabled: bool,
+    physics_was_enabled: bool,
+}
+
+impl EditorState {
+    fn look_dir(&self) -> Vec3 {
+        vec3(
+            self.yaw.sin() * self.pitch.cos(),
+            self.pitch.sin(),
+            -self.yaw.cos() * self.pitch.cos(),
+        )
+    }
+
+    /// Returns tile the camera is looking at, i.e. where the camera's ray hits
+    /// the ground.
+    fn target(&self) -> Option<(i32, i32)> {
+        let dir = self.look_dir();
+
+        if dir.y >= -0.01 {
+            return None;
+        }
+
+        let hit = self.camera + dir * (-self.camera.y / dir.y);
+
+        Some((hit.x.round() as i32, hit.z.round() as i32))
+    }
+
+    fn textures(&self) -> Vec<&str> {
+        let Some(prefix) = self.tool.texture_prefix() else {
+            return Default::default();
+        };
+
+        self.tileset
+            .textures()
+            .filter(|texture| texture.starts_with(prefix))
+            .collect()
+    }
+
+    fn texture(&self) -> Option<&str> {
+        let textures = self.textures();
+
+        if textures.is_empty() {
+            None
+        } else {
+            Some(textures[self.texture_idx % textures.len()])
+        }
+    }
+
+    fn color(&self) -> Option<&'static str> {
+        self.color_idx.map(|idx| KEY_COLORS[idx])
+    }
+
+    /// Returns name that's not used by any object yet, e.g. `door-3`.
+    fn unique_name(&mut self, prefix: &str) -> String {
+        loop {
+            self.next_id += 1;
+
+            let name = format!("{}-{}", prefix, self.next_id);
+
+            let is_taken = self.map.object_names().any(|object| {
+                object
+                    .split(|ch| ch == ':' || ch == ',')
+                    .any(|part| part == name)
+            });
+
+            if !is_taken {
+                return name;
+            }
+        }
+    }
+
+    fn place(&mut self, x: i32, y: i32) -> Result<(), String> {
+        let object = match self.tool {
+            Tool::Floor | Tool::Wall | Tool::Ceiling => {
+                let texture = self.texture().ok_or("No textures available")?;
+                let tile = self.tileset.id(texture).unwrap();
+
+                self.map.set_tile(self.tool.layer(), x, y, Some(tile));
+
+                return Ok(());
+            }
+
+            Tool::Door => {
+                let name = self.unique_name("door");
+
+                let object = match self.color() {
+                    Some(color) => format!("door:{},{}", name, color),
+                    None => format!("door:{}", name),
+                };
+
+                self.last_door = Some(name);
+
+                object
+            }
+
+            Tool::Key => {
+                let color =
+                    self.color().ok_or("Pick key's color first (Q / E)")?;
+
+                let door = self
+                    .last_door
+                    .as_ref()
+                    .ok_or("Place a door for this key first")?;
+
+                format!("key:{},{}", door, color)
+            }
+
+            Tool::Torch => "torch".into(),
+            Tool::Tag => format!("tag:{}", self.unique_name("tag")),
+
+            Tool::Zone => {
+                let Some((x1, y1)) = self.zone_start.take() else {
+                    self.zone_start = Some((x, y));
+                    return Ok(());
+                };
+
+                let name = format!("zone:{}", self.unique_name("zone"));
+
+                self.map.add_object(
+                    name,
+                    x1.min(x),
+                    y1.min(y),
+                    (x1 - x).abs() + 1,
+                    (y1 - y).abs() + 1,
+                );
+
+                return Ok(());
+            }
+
+            Tool::Light => "light:point".into(),
+        };
+
+        self.map.add_object(object, x, y, 0, 0);
+
+        Ok(())
+    }
+
+    fn remove(&mut self, x: i32, y: i32) {
+        match self.tool {
+            Tool::Floor | Tool::Wall | Tool::Ceiling => {
+                self.map.set_tile(self.tool.layer(), x, y, None);
+            }
+
+            _ => {
+                self.zone_start = None;
+                self.map.remove_objects_at(x, y);
+            }
+        }
+    }
+
+    fn status(&self) -> String {
+        let target = match self.target() {
+            Some((x, y)) => format!("{},{}", x, y),
+            None => "-".into(),
+        };
+
+        let mut status = format!("editor: {:?}", self.tool);
+
+        if let Some(texture) = self.texture() {
+            status.push_str(&format!(" ({})", texture));
+        }
+
+        if matches!(self.tool, Tool::Door | Tool::Key) {
+            status.push_str(&format!(" ({})", self.color().unwrap_or("-")));
+        }
+
+        if let Some((x, y)) = self.zone_start {
+            status.push_str(&format!(" from {},{}", x, y));
+        }
+
+        format!("{} at {}", status, target)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Tool {
+    Floor,
+    Wall,
+    Ceiling,
+    Door,
+    Key,
+    Torch,
+    Tag,
+    Zone,
+    Light,
+}
+
+impl Tool {
+    const ALL: [(KeyCode, Self); 9] = [
+        (KeyCode::Key1, Self::Floor),
+        (KeyCode::Key2, Self::Wall),
+        (KeyCode::Key3, Self::Ceiling),
+        (KeyCode::Key4, Self::Door),
+        (KeyCode::Key5, Self::Key),
+        (KeyCode::Key6, Self::Torch),
+        (KeyCode::Key7, Self::Tag),
+        (KeyCode::Key8, Self::Zone),
+        (KeyCode::Key9, Self::Light),
+    ];
+
+    fn layer(self) -> &'static str {
+        match self {
+            Tool::Floor => "floors",
+            Tool::Wall => "walls",
+            Tool::Ceiling => "ceilings",
+            _ => unreachable!(),
+        }
+    }
+
+    fn texture_prefix(self) -> Option<&'static str> {
+        match self {
+            Tool::Floor => Some("floor."),
+            Tool::Wall => Some("wall."),
+            Tool::Ceiling => Some("ceiling."),
+            _ => None,
+        }
+    }
+}
+
+fn toggle(
+    mut commands: Commands,
+    mut editor: ResMut<Editor>,
+    mut toggle_rx: EventReader<ToggleEditor>,
+    mut output_tx: EventWriter<CommandOutput>,
+    mut change_hud_visibility_tx: EventWriter<ChangeHudVisibility>,
+    mut enemy_ai_enabled: ResMut<EnemyAiEnabled>,
+    mut physics_enabled: ResMut<PhysicsEnabled>,
+    coordinator: Res<LevelsCoordinator>,
+    registry: Res<LevelRegistry>,
+    mut player: Query<(&mut Player, &mut Transform)>,
+) {
+    if toggle_rx.iter().last().is_none() {
+        return;
+    }
+
+    let Ok((mut player, mut player_xform)) = player.get_single_mut() else {
+        return;
+    };
+
+    if let Some(state) = editor.state.take() {
+        commands.entity(state.status).despawn();
+
+        // Leave the player where the camera is, so that the changes can be
+        // tried out right away
+        player.can_move = true;
+        player_xform.translation = state.camera * vec3(1.0, 0.0, 1.0);
+        enemy_ai_enabled.0 = state.enemy_ai_was_enabled;
+        physics_enabled.0 = state.physics_was_enabled;
+        change_hud_visibility_tx.send(ChangeHudVisibility::show());
+
+        output_tx.send(CommandOutput(
+            "Editor disabled; use `goto-level` to restart the level".into(),
+        ));
+
+        return;
+    }
+
+    let level = registry.get(coordinator.current_level);
+    let tileset = LevelLoader::tileset();
+
+    let map = if let Some(map) = level.map {
+        map::Map::from_tmj(map)
+    } else {
+        output_tx.send(CommandOutput(format!(
+            "Level `{}` is built in code, starting with an empty map",
+            level.name
+        )));
+
+        // Raytracer doesn't like maps without any geometry, so let's start
+        // with a single tile
+        let mut map = map::Map::new();
+
+        map.set_tile(
+            "floors",
+            player_xform.translation.x.round() as i32,
+            player_xform.translation.z.round() as i32,
+            tileset.id("floor.basic"),
+        );
+
+        map
+    };
+
+    let forward = player_xform.forward();
+
+    let status = commands
+        .spawn((
+            Text::new(""),
+            Transform::from_translation(vec3(0.01, 0.02, 0.0)),
+        ))
+        .id();
+
+    player.can_move = false;
+    change_hud_visibility_tx.send(ChangeHudVisibility::hide());
+
+    editor.state = Some(EditorState {
+        level: level.name,
+        map,
+        tileset,
+        tool: Tool::Floor,
+        texture_idx: 0,
+        color_idx: None,
+        zone_start: None,
+        last_door: None,
+        next_id: 0,
+        is_dirty: true,
+        camera: player_xform.translation + vec3(0.0, 4.0, 0.0),
+        yaw: forward.x.atan2(-forward.z),
+        pitch: -PI / 4.0,
+        status,
+        enemy_ai_was_enabled: enemy_ai_enabled.0,
+        physics_was_enabled: physics_enabled.0,
+    });
+
+    enemy_ai_enabled.0 = false;
+
+    // Otherwise the player would keep falling down whenever the edited map
+    // doesn't have any floor underneath them
+    physics_enabled.0 = false;
+}
+
+fn process_input(
+    time: Res<Time>,
+    keys: Res<Input<KeyCode>>,
+    mouse: Res<Input<MouseButton>>,
+    settings: Res<Settings>,
+    input_lock: Res<InputLock>,
+    mut editor: ResMut<Editor>,
+    mut mouse_motion_rx: EventReader<MouseMotion>,
+    mut output_tx: EventWriter<CommandOutput>,
+) {
+    let Some(state) = &mut editor.state else { return };
+
+    if input_lock.is_locked {
+        return;
+    }
+
+    let dt = time.delta_seconds();
+
+    for ev in mouse_motion_rx.iter() {
+        state.yaw += settings.mouse_sensitivity * ev.delta.x * dt;
+        state.pitch -= settings.mouse_sensitivity * ev.delta.y * dt;
+        state.pitch = state.pitch.clamp(-PI / 2.0 + 0.01, PI / 2.0 - 0.01);
+    }
+
+    // -----
+
+    let forward = state.look_dir();
+    let right = forward.cross(Vec3::Y).normalize();
+    let mut movement = Vec3::ZERO;
+
+    for (key, dir) in [
+        (KeyCode::W, forward),
+        (KeyCode::S, -forward),
+        (KeyCode::A, -right),
+        (KeyCode::D, right),
+        (KeyCode::Space, Vec3::Y),
+        (KeyCode::LShift, -Vec3::Y),
+    ] {
+        if keys.pressed(key) {
+            movement += dir;
+        }
+    }
+
+    state.camera += movement.normalize_or_zero() * MOVEMENT_SPEED * dt;
+
+    // -----
+
+    for (key, tool) in Tool::ALL {
+        if keys.just_pressed(key) && state.tool != tool {
+            state.tool = tool;
+            state.texture_idx = 0;
+            state.zone_start = None;
+        }
+    }
+
+    if keys.just_pressed(KeyCode::Q) || keys.just_pressed(KeyCode::E) {
+        let next = keys.just_pressed(KeyCode::E);
+
+        if matches!(state.tool, Tool::Door | Tool::Key) {
+            // Cycles through `None`, `Some(0)`, ..., `Some(len - 1)`
+            let colors = KEY_COLORS.len() + 1;
+            let idx = state.color_idx.map_or(0, |idx| idx + 1);
+
+            let idx = if next {
+                (idx + 1) % colors
+            } else {
+                (idx + colors - 1) % colors
+            };
+
+            state.color_idx = idx.checked_sub(1);
+        } else {
+            let textures = state.textures().len().max(1);
+
+            state.texture_idx = if next {
+                (state.texture_idx + 1) % textures
+            } else {
+                (state.texture_idx + textures - 1) % textures
+            };
+        }
+    }
+
+    // -----
+
+    let Some((x, y)) = state.target() else { return };
+
+    if mouse.just_pressed(MouseButton::Left) {
+        match state.place(x, y) {
+            Ok(()) => {
+                state.is_dirty = true;
+            }
+            Err(err) => {
+                output_tx.send(CommandOutput(err));
+            }
+        }
+    }
+
+    if mouse.just_pressed(MouseButton::Right) {
+        state.remove(x, y);
+        state.is_dirty = true;
+    }
+}
+
+/// Respawns the level from the edited map.
+///
+/// Level's state (see [`LevelBuilder::complete()`]) is kept as-is, so the
+/// level's logic keeps referring to entities from before the rebuild - the
+/// level has to be restarted through `goto-level` to play it again.
+fn rebuild(
+    mut commands: Commands,
+    assets: Res<Assets>,
+    mut editor: ResMut<Editor>,
+    mut sync_nav_data_tx: EventWriter<SyncNavData>,
+    mut texts: Query<&mut Text>,
+    entities: Query<
+        Entity,
+        (
+            Or<(
+                With<AssetHandle<Model>>,
+                With<Light>,
+                With<LevelZone>,
+                (With<GcAfterLevelUnloaded>, With<Transform>),
+            )>,
+            Without<Flashlight>,
+        ),
+    >,
+) {
+    let Some(state) = &mut editor.state else { return };
+
+    if let Ok(mut text) = texts.get_mut(state.status) {
+        text.text = state.status();
+    }
+
+    if !state.is_dirty {
+        return;
+    }
+
+    state.is_dirty = false;
+
+    for entity in entities.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let mut lvl = LevelBuilder::new(&mut commands, &assets);
+
+    LevelLoader::from_map(state.map.clone()).spawn(&mut lvl);
+    sync_nav_data_tx.send(SyncNavData::default());
+}
+
+fn sync_camera(editor: Res<Editor>, mut camera: Query<&mut Camera>) {
+    let Some(state) = &editor.state else { return };
+    let Ok(mut camera) = camera.get_single_mut() else { return };
+
+    camera.origin = state.camera;
+    camera.look_at = state.camera + state.look_dir();
+}
+
+fn save(
+    editor: Res<Editor>,
+    mut save_rx: EventReader<SaveEditedMap>,
+    mut output_tx: EventWriter<CommandOutput>,
+) {
+    for SaveEditedMap(path) in save_rx.iter() {
+        let Some(state) = &editor.state else {
+            output_tx.send(CommandOutput("Editor is not enabled".into()));
+            continue;
+        };
+
+        let path = path
+            .clone()
+            .unwrap_or_else(|| format!("assets/levels/{}.tmj", state.level));
+
+        let msg = match std::fs::write(&path, state.map.to_tmj()) {
+            Ok(()) => format!("Map saved to {}", path),
+            Err(err) => format!("Couldn't save map to {}: {}", path, err),
+        };
+
+        output_tx.send(CommandOutput(msg));
+    }
+}
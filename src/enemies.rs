@@ -1,9 +1,12 @@
 use bevy::prelude::*;
 use doome_bevy::convert::{graphical_to_physical, physical_to_graphical};
+use doome_bevy::debug::{DebugLines, DebugOverlay};
 use doome_bevy::nav::NavObstacle;
-use doome_bevy::physics::components::{Collider, RayCast};
+use doome_bevy::physics::components::{layer, Collider, CollisionLayers};
+use doome_bevy::physics::{PhysicsQuery, QueryFilter};
 use doome_bevy::player::Player;
 use doome_bevy::prelude::Body;
+use doome_bevy::rendering_options::RenderingOptions;
 use doome_nav::{NavData, NavDataBuilder};
 use instant::Instant;
 
@@ -49,6 +52,7 @@ impl Plugin for EnemiesPlugin {
         app.add_system(update_shooting);
         app.add_system(assign_paths_to_enemies);
         app.add_system(enemy_movement);
+        app.add_system(draw_debug_overlays);
     }
 }
 
@@ -88,8 +92,9 @@ fn update_hivemind(
 fn update_shooting(
     ai_enabled: Res<EnemyAiEnabled>,
     mut commands: Commands,
+    physics: PhysicsQuery,
     hivemind: Query<&Hivemind>,
-    mut enemies: Query<(&mut Enemy, &mut Weapon, &Transform, &RayCast)>,
+    mut enemies: Query<(Entity, &mut Weapon, &Transform), With<Enemy>>,
 ) {
     if !ai_enabled.0 {
         return;
@@ -105,19 +110,20 @@ fn update_shooting(
     let player_pos = hivemind.known_player_position;
     let player_vel = hivemind.known_player_velocity;
 
-    for (_, mut weapon, transform, raycast) in enemies.iter_mut() {
+    for (entity, mut weapon, transform) in enemies.iter_mut() {
         let pos = graphical_to_physical(transform.translation);
         let distance_to_player = (player_pos - pos).length();
         let time_to_hit = distance_to_player / weapon.definition.bullet_speed;
         let predicted_player_pos = player_pos + player_vel * time_to_hit;
         let to_predicted_pos = (predicted_player_pos - pos).normalize();
 
-        if let Some(_) = player_entity_raycast(raycast, player_entity) {
+        if sees_player(&physics, entity, pos, player_entity, player_pos) {
             if weapon.can_shoot() {
                 weapon.shoot(
                     &mut commands,
                     transform,
                     physical_to_graphical(to_predicted_pos),
+                    CollisionLayers::ENEMY_PROJECTILE,
                 );
             }
         }
@@ -167,12 +173,15 @@ fn assign_paths_to_enemies(
 }
 
 const FOLLOW_SPEED: f32 = 4.0;
+const STEERING_SPEED: f32 = 10.0;
+const SIGHT_DISTANCE: f32 = 20.0;
 const NEXT_PATH_NODE_PICK_DISTANCE: f32 = 0.5;
 
 fn enemy_movement(
     ai_enabled: Res<EnemyAiEnabled>,
+    physics: PhysicsQuery,
     hivemind: Query<&Hivemind>,
-    mut enemies: Query<(&mut Enemy, &mut Body, &mut Transform, &RayCast)>,
+    mut enemies: Query<(Entity, &mut Enemy, &mut Body, &Transform)>,
 ) {
     if !ai_enabled.0 {
         return;
@@ -185,35 +194,36 @@ fn enemy_movement(
         return;
     };
 
-    for (mut enemy, mut body, mut transform, raycast) in enemies.iter_mut() {
-        body.velocity = Vec2::ZERO;
+    let player_pos = hivemind.known_player_position;
 
-        if !enemy.follows_player {
-            continue;
-        }
+    for (entity, mut enemy, mut body, transform) in enemies.iter_mut() {
+        let mut desired_velocity = Vec2::ZERO;
 
-        if let Some(_player_pos) = player_entity_raycast(raycast, player_entity)
-        {
-            // TODO: Side strafing
-            // do nothing
-        } else {
-            follow_path_to_player(&mut enemy, &mut transform, &mut body);
+        if enemy.follows_player {
+            let pos = graphical_to_physical(transform.translation);
+
+            if sees_player(&physics, entity, pos, player_entity, player_pos) {
+                // TODO: Side strafing
+                // do nothing
+            } else {
+                desired_velocity = follow_path_to_player(&mut enemy, transform);
+            }
         }
+
+        // Steering (instead of setting the velocity directly) allows for the
+        // enemies to get knocked back
+        body.acceleration = (desired_velocity - body.velocity) * STEERING_SPEED;
     }
 }
 
-fn follow_path_to_player(
-    enemy: &mut Enemy,
-    transform: &Transform,
-    body: &mut Body,
-) {
-    let Some(path) = &mut enemy.path else { return };
+fn follow_path_to_player(enemy: &mut Enemy, transform: &Transform) -> Vec2 {
+    let Some(path) = &mut enemy.path else { return Vec2::ZERO };
 
     let pos = graphical_to_physical(transform.translation);
 
     let mut path_items = path.drain(..).peekable();
     loop {
-        let Some(next) = path_items.peek() else { return }; // return if path is empty
+        let Some(next) = path_items.peek() else { return Vec2::ZERO }; // return if path is empty
 
         if pos.distance(*next) >= NEXT_PATH_NODE_PICK_DISTANCE {
             break;
@@ -225,28 +235,29 @@ fn follow_path_to_player(
     let remaining_path_items = path_items.collect();
     *path = remaining_path_items;
 
-    let Some(next) = path.first() else { return }; // return if path is empty
+    let Some(next) = path.first() else { return Vec2::ZERO }; // return if path is empty
 
     let dir = *next - pos;
-    let dir = dir.normalize_or_zero() * FOLLOW_SPEED;
 
-    body.velocity = dir;
+    dir.normalize_or_zero() * FOLLOW_SPEED
 }
 
-fn player_entity_raycast(
-    raycast: &RayCast,
+fn sees_player(
+    physics: &PhysicsQuery,
+    enemy_entity: Entity,
+    enemy_pos: Vec2,
     player_entity: Entity,
-) -> Option<Vec2> {
-    let hit = raycast.hit.as_ref()?;
-
-    if hit.entity == player_entity {
-        Some(hit.position)
-    } else {
-        None
-    }
+    player_pos: Vec2,
+) -> bool {
+    let filter =
+        QueryFilter::new(layer::WORLD | layer::PLAYER).excluding(enemy_entity);
+
+    physics
+        .raycast(enemy_pos, player_pos - enemy_pos, SIGHT_DISTANCE, filter)
+        .map_or(false, |hit| hit.entity == player_entity)
 }
 
-fn sync_nav_data(
+pub fn sync_nav_data(
     mut event_rx: EventReader<SyncNavData>,
     mut hivemind: Query<&mut Hivemind>,
     walls: Query<(&Transform, &Collider), With<NavObstacle>>,
@@ -268,7 +279,9 @@ fn sync_nav_data(
     let mut nav_data_builder = NavDataBuilder::new(0.75);
 
     for (transform, collider) in walls.iter() {
-        if collider.is_detector() {
+        if collider.is_detector()
+            || (collider.layers().layer & layer::WORLD) == 0
+        {
             continue;
         }
 
@@ -289,3 +302,64 @@ fn sync_nav_data(
         build_time
     );
 }
+
+/// How far from the player the debug pass draws the navigation grid
+const DEBUG_NAV_GRID_RADIUS: f32 = 8.0;
+
+const DEBUG_NAV_GRID_COLOR: Vec3 = Vec3::splat(0.5);
+const DEBUG_PATH_COLOR: Vec3 = Vec3::new(1.0, 0.5, 0.0);
+const DEBUG_SIGHT_COLOR: Vec3 = Vec3::new(0.0, 1.0, 1.0);
+
+fn draw_debug_overlays(
+    rendering_options: Res<RenderingOptions>,
+    physics: PhysicsQuery,
+    mut debug_lines: ResMut<DebugLines>,
+    hivemind: Query<&Hivemind>,
+    enemies: Query<(Entity, &Enemy, &Transform)>,
+) {
+    let hivemind = hivemind.single();
+    let player_pos = hivemind.known_player_position;
+
+    if rendering_options.shows_overlay(DebugOverlay::NavGrid) {
+        if let Some(nav_data) = &hivemind.nav_data {
+            for cell in nav_data
+                .impassable_cells_around(player_pos, DEBUG_NAV_GRID_RADIUS)
+            {
+                debug_lines.rect_2d(cell.min, cell.max, DEBUG_NAV_GRID_COLOR);
+            }
+        }
+    }
+
+    for (entity, enemy, transform) in enemies.iter() {
+        let pos = graphical_to_physical(transform.translation);
+
+        if rendering_options.shows_overlay(DebugOverlay::Paths) {
+            if let Some(path) = &enemy.path {
+                let mut prev = pos;
+
+                for &next in path {
+                    debug_lines.line_2d(prev, next, DEBUG_PATH_COLOR);
+                    prev = next;
+                }
+            }
+        }
+
+        if rendering_options.shows_overlay(DebugOverlay::Raycasts) {
+            let filter = QueryFilter::new(layer::WORLD | layer::PLAYER)
+                .excluding(entity);
+
+            let end = physics
+                .raycast(pos, player_pos - pos, SIGHT_DISTANCE, filter)
+                .map_or_else(
+                    || {
+                        pos + (player_pos - pos).normalize_or_zero()
+                            * SIGHT_DISTANCE
+                    },
+                    |hit| hit.position,
+                );
+
+            debug_lines.line_2d(pos, end, DEBUG_SIGHT_COLOR);
+            debug_lines.point_2d(end, DEBUG_SIGHT_COLOR);
+        }
+    }
+}
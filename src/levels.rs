@@ -1,8 +1,12 @@
 use bevy::prelude::*;
 
 pub use self::builder::*;
+pub use self::campaign::*;
 pub use self::coordinator::*;
 pub use self::loader::*;
+pub use self::registry::*;
+pub use self::save_point::*;
+pub use self::script::*;
 pub use self::zone::*;
 
 pub mod level0;
@@ -12,10 +16,15 @@ pub mod level3;
 pub mod level4;
 pub mod level5;
 pub mod level6;
+pub mod survival;
 
 mod builder;
+mod campaign;
 mod coordinator;
 mod loader;
+mod registry;
+mod save_point;
+mod script;
 mod zone;
 
 pub struct LevelsPlugin;
@@ -24,29 +33,63 @@ impl Plugin for LevelsPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<GotoLevel>()
             .add_event::<LevelGameplayEvent>()
+            .add_event::<ZoneEvent>()
+            .init_resource::<LevelRegistry>()
+            .init_resource::<Campaign>()
+            .init_resource::<SavePoint>()
+            .init_resource::<survival::Survival>()
             .add_startup_system(LevelsCoordinator::init)
             .add_system_to_stage(
                 CoreStage::PreUpdate,
-                ordered_systems! {
-                    LevelsCoordinator::unload
-                    => level0::init
-                    => level1::init
-                    => level2::init
-                    => level3::init
-                    => level4::init
-                    => level5::init
-                    => level6::init
-                },
-            )
-            .add_system(LevelsCoordinator::handle_game_state)
-            .add_system(level0::process)
-            .add_system(level1::process)
-            .add_system(level2::process)
-            .add_system(level3::process)
-            .add_system(level4::process)
-            .add_system(level5::process)
-            .add_system(level6::process)
-            .add_system(LevelsCoordinator::process_zones);
+                LevelsCoordinator::unload,
+            )
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                Campaign::enter_level.after(LevelsCoordinator::unload),
+            )
+            .add_level(
+                LevelRegistry::GAME_OVER,
+                None,
+                level0::init,
+                level0::process,
+            )
+            .add_level("level1", None, level1::init, level1::process)
+            .add_level(
+                "level2",
+                Some(level2::MAP),
+                level2::init,
+                level2::process,
+            )
+            .add_level(
+                "level3",
+                Some(level3::MAP),
+                level3::init,
+                level3::process,
+            )
+            .add_level(
+                "level4",
+                Some(level4::MAP),
+                level4::init,
+                level4::process,
+            )
+            .add_level(
+                "level5",
+                Some(level5::MAP),
+                level5::init,
+                level5::process,
+            )
+            .add_level(
+                "level6",
+                Some(level6::MAP),
+                level6::init,
+                level6::process,
+            )
+            .add_level("survival", None, survival::init, survival::process)
+            .add_system(LevelScriptState::process)
+            .add_system(LevelZone::track::<Player>)
+            .add_system(LevelZone::track::<Enemy>)
+            .add_system(LevelZone::track::<Bullet>)
+            .add_system(Campaign::track_checkpoints);
     }
 }
 
@@ -1,14 +1,14 @@
-use std::f32::consts::PI;
-
 use bevy::ecs::system::EntityCommands;
 use bevy::prelude::*;
 use doome_bevy::assets::{AssetHandle, Assets, Model};
 use doome_bevy::components::*;
 use doome_bevy::nav::NavObstacle;
 use doome_bevy::physics::components::Collider;
-use glam::{vec2, vec3};
+use doome_bevy::physics::Floor;
+use doome_levels::{Placement, CEILING_HEIGHT};
+use glam::vec2;
 
-use super::{GcAfterLevelUnloaded, LevelZone};
+use super::{GcAfterLevelUnloaded, LevelZone, ZoneShape};
 
 pub struct LevelBuilder<'p, 'w, 's> {
     commands: &'p mut Commands<'w, 's>,
@@ -36,36 +36,30 @@ impl<'p, 'w, 's> LevelBuilder<'p, 'w, 's> {
         x2: i32,
         z2: i32,
     ) -> LevelModelBuilder<'w, 's, 'a> {
-        let (x1, x2) = (x1.min(x2), x1.max(x2));
-        let (z1, z2) = (z1.min(z2), z1.max(z2));
-        let dx = x2 - x1 + 1;
-        let dz = z2 - z1 + 1;
-
-        log::debug!(
-            "floor({}, {}, {}, {}); dx={}, dz={}",
-            x1,
-            z1,
-            x2,
-            z2,
-            dx,
-            dz
-        );
-
-        assert!(dx > 0 && dz > 0, "Floor has no area");
+        self.floor_at(x1, z1, x2, z2, 0.0)
+    }
+
+    #[must_use]
+    pub fn floor_at<'a>(
+        &'a mut self,
+        x1: i32,
+        z1: i32,
+        x2: i32,
+        z2: i32,
+        y: f32,
+    ) -> LevelModelBuilder<'w, 's, 'a> {
+        let placement = Placement::floor(x1, z1, x2, z2, y);
+        let (u_divisor, v_divisor) = placement.uv_divisor;
 
         self.model("floor")
-            .with_translation(vec3(
-                (x1 + x2) as f32 / 2.0,
-                0.0,
-                (z1 + z2) as f32 / 2.0,
-            ))
-            .with_scale(vec3((dx as f32) / 2.0, 1.0, (dz as f32) / 2.0))
+            .with_placement(placement)
             .with_material(
                 Material::default()
                     .with_color(Color::hex(0xffffff))
-                    .with_uv_divisor(dx as _, dz as _)
+                    .with_uv_divisor(u_divisor, v_divisor)
                     .without_casting_shadows(),
             )
+            .with_floor(Floor::rect(1.0, 1.0))
     }
 
     #[must_use]
@@ -76,34 +70,27 @@ impl<'p, 'w, 's> LevelBuilder<'p, 'w, 's> {
         x2: i32,
         z2: i32,
     ) -> LevelModelBuilder<'w, 's, 'a> {
-        let (x1, x2) = (x1.min(x2), x1.max(x2));
-        let (z1, z2) = (z1.min(z2), z1.max(z2));
-        let dx = x2 - x1 + 1;
-        let dz = z2 - z1 + 1;
-
-        log::debug!(
-            "ceiling({}, {}, {}, {}); dx={}, dz={}",
-            x1,
-            z1,
-            x2,
-            z2,
-            dx,
-            dz
-        );
-
-        assert!(dx > 0 && dz > 0, "Ceiling has no area");
+        self.ceiling_at(x1, z1, x2, z2, CEILING_HEIGHT)
+    }
+
+    #[must_use]
+    pub fn ceiling_at<'a>(
+        &'a mut self,
+        x1: i32,
+        z1: i32,
+        x2: i32,
+        z2: i32,
+        y: f32,
+    ) -> LevelModelBuilder<'w, 's, 'a> {
+        let placement = Placement::ceiling(x1, z1, x2, z2, y);
+        let (u_divisor, v_divisor) = placement.uv_divisor;
 
         self.model("ceiling")
-            .with_translation(vec3(
-                (x1 + x2) as f32 / 2.0,
-                2.5,
-                (z1 + z2) as f32 / 2.0,
-            ))
-            .with_scale(vec3((dx as f32) / 2.0, 1.0, (dz as f32) / 2.0))
+            .with_placement(placement)
             .with_material(
                 Material::default()
                     .with_color(Color::hex(0xffffff))
-                    .with_uv_divisor(dx as _, dz as _),
+                    .with_uv_divisor(u_divisor, v_divisor),
             )
     }
 
@@ -116,51 +103,102 @@ impl<'p, 'w, 's> LevelBuilder<'p, 'w, 's> {
         z2: i32,
         rot: u8,
     ) -> LevelModelBuilder<'w, 's, 'a> {
-        let (x1, x2) = (x1.min(x2), x1.max(x2));
-        let (z1, z2) = (z1.min(z2), z1.max(z2));
-        let dx = x2 - x1 + 1;
-        let dz = z2 - z1 + 1;
-
-        log::debug!(
-            "wall({}, {}, {}, {}, {}); dx={}, dz={}",
-            x1,
-            z1,
-            x2,
-            z2,
-            rot,
-            dx,
-            dz
-        );
-
-        assert!(dx == 1 || dz == 1, "Wall is not axis-aligned");
-
-        let extrude = match rot {
-            0 => vec3(0.0, 0.0, -0.5),
-            1 => vec3(-0.5, 0.0, 0.0),
-            2 => vec3(0.0, 0.0, 0.5),
-            3 => vec3(0.5, 0.0, 0.0),
-            _ => panic!("Invalid wall rotation: {}", rot),
-        };
+        self.wall_at(x1, z1, x2, z2, rot, 0.0, CEILING_HEIGHT)
+    }
 
-        let scale = if dx == 1 { dz } else { dx };
+    /// Spawns a wall spanning from `bottom` to `top` (in world units).
+    #[must_use]
+    pub fn wall_at<'a>(
+        &'a mut self,
+        x1: i32,
+        z1: i32,
+        x2: i32,
+        z2: i32,
+        rot: u8,
+        bottom: f32,
+        top: f32,
+    ) -> LevelModelBuilder<'w, 's, 'a> {
+        let placement = Placement::wall(x1, z1, x2, z2, rot, bottom, top);
+
+        self.wall_model(placement)
+    }
+
+    /// Spawns a wall going from `start` to `end` (in world units, on the XZ
+    /// plane); contrary to [`Self::wall_at()`], it doesn't have to be
+    /// axis-aligned.
+    ///
+    /// Segments are double-sided, so it doesn't matter which way they go.
+    #[must_use]
+    pub fn wall_segment<'a>(
+        &'a mut self,
+        start: Vec2,
+        end: Vec2,
+        bottom: f32,
+        top: f32,
+    ) -> LevelModelBuilder<'w, 's, 'a> {
+        let placement = Placement::segment(start, end, bottom, top);
+
+        self.wall_model(placement)
+            .alter_material(|mat| mat.double_sided())
+    }
+
+    /// Spawns a free-standing block of walls spanning given tiles, with a
+    /// ceiling at its bottom and a floor at its top - e.g. to build a sliding
+    /// or a destructible wall; `f` gets to adjust each of the block's models.
+    pub fn block(
+        &mut self,
+        x1: i32,
+        z1: i32,
+        x2: i32,
+        z2: i32,
+        bottom: f32,
+        top: f32,
+        mut f: impl for<'a> FnMut(
+            LevelModelBuilder<'w, 's, 'a>,
+        ) -> LevelModelBuilder<'w, 's, 'a>,
+    ) -> Vec<Entity> {
+        let sides = [
+            (x1, z1, x2, z1, 0),
+            (x1, z1, x1, z2, 1),
+            (x1, z2, x2, z2, 2),
+            (x2, z1, x2, z2, 3),
+        ];
+
+        let mut entities: Vec<_> = sides
+            .into_iter()
+            .map(|(x1, z1, x2, z2, rot)| {
+                let wall = self
+                    .wall_at(x1, z1, x2, z2, rot, bottom, top)
+                    .alter_material(|mat| mat.double_sided());
+
+                f(wall).spawn().id()
+            })
+            .collect();
+
+        entities.push(f(self.ceiling_at(x1, z1, x2, z2, bottom)).spawn().id());
+        entities.push(f(self.floor_at(x1, z1, x2, z2, top)).spawn().id());
+        entities
+    }
+
+    fn wall_model<'a>(
+        &'a mut self,
+        placement: Placement,
+    ) -> LevelModelBuilder<'w, 's, 'a> {
+        let (u_divisor, v_divisor) = placement.uv_divisor;
+        let height = placement.scale.y * 2.0;
 
         self.model("wall")
             .obstacle()
-            .with_translation(
-                vec3(
-                    (x1 as f32 + x2 as f32) / 2.0,
-                    0.0,
-                    (z1 as f32 + z2 as f32) / 2.0,
-                ) + extrude,
-            )
-            .with_rotation(Quat::from_rotation_y(PI / 2.0 * (rot as f32)))
-            .with_scale(vec3((scale as f32) / 2.0, 1.25, 1.0))
+            .with_placement(placement)
             .with_material(
                 Material::default()
                     .with_color(Color::hex(0xffffff))
-                    .with_uv_divisor(scale as _, 1),
+                    .with_uv_divisor(u_divisor, v_divisor),
+            )
+            .with_collider(
+                Collider::line(vec2(-1.0, 0.0), vec2(1.0, 0.0))
+                    .with_height(height),
             )
-            .with_collider(Collider::line(vec2(-1.0, 0.0), vec2(1.0, 0.0)))
     }
 
     pub fn point_light<'a>(
@@ -215,14 +253,14 @@ impl<'p, 'w, 's> LevelBuilder<'p, 'w, 's> {
         x2: f32,
         z2: f32,
     ) {
-        self.commands.spawn(LevelZone {
-            name: name.to_string(),
-            x1,
-            z1,
-            x2,
-            z2,
-            contains_player: false,
-        });
+        self.custom_zone(LevelZone::new(
+            name,
+            ZoneShape::Rect { x1, z1, x2, z2 },
+        ));
+    }
+
+    pub fn custom_zone(&mut self, zone: LevelZone) {
+        self.commands.spawn(zone);
     }
 
     pub fn complete<T>(self, level: T)
@@ -240,6 +278,7 @@ pub struct LevelModelBuilder<'w, 's, 'a> {
     transform: Transform,
     material: Option<Material>,
     collider: Option<Collider>,
+    floor: Option<Floor>,
     is_obstacle: bool,
 }
 
@@ -256,6 +295,7 @@ impl<'w, 's, 'a> LevelModelBuilder<'w, 's, 'a> {
             transform: Default::default(),
             material: Default::default(),
             collider: Default::default(),
+            floor: Default::default(),
             is_obstacle: false,
         }
     }
@@ -265,6 +305,13 @@ impl<'w, 's, 'a> LevelModelBuilder<'w, 's, 'a> {
         self
     }
 
+    /// Keeps the collider, but makes the model passable for the enemies'
+    /// navigation, e.g. because the model is going to move.
+    pub fn without_obstacle(mut self) -> Self {
+        self.is_obstacle = false;
+        self
+    }
+
     pub fn dynamic(mut self) -> Self {
         self.geo_type = GeometryType::Dynamic;
         self
@@ -290,6 +337,15 @@ impl<'w, 's, 'a> LevelModelBuilder<'w, 's, 'a> {
         self
     }
 
+    pub fn with_placement(mut self, val: Placement) -> Self {
+        self.transform = Transform {
+            translation: val.translation,
+            rotation: val.rotation,
+            scale: val.scale,
+        };
+        self
+    }
+
     pub fn with_material(mut self, val: Material) -> Self {
         self.material = Some(val);
         self
@@ -308,6 +364,27 @@ impl<'w, 's, 'a> LevelModelBuilder<'w, 's, 'a> {
         self
     }
 
+    /// Removes collider, making the model passable both for the player and
+    /// for the enemies' navigation.
+    pub fn without_collider(mut self) -> Self {
+        self.collider = None;
+        self.is_obstacle = false;
+        self
+    }
+
+    /// Makes the model walkable for bodies moving vertically (see
+    /// [`Floor`]).
+    pub fn with_floor(mut self, val: Floor) -> Self {
+        self.floor = Some(val);
+        self
+    }
+
+    /// Removes floor, making the model's surface non-walkable.
+    pub fn without_floor(mut self) -> Self {
+        self.floor = None;
+        self
+    }
+
     pub fn spawn(self) -> EntityCommands<'w, 's, 'a> {
         let mut entity =
             self.commands
@@ -321,6 +398,10 @@ impl<'w, 's, 'a> LevelModelBuilder<'w, 's, 'a> {
             entity.insert(collider);
         }
 
+        if let Some(floor) = self.floor {
+            entity.insert(floor);
+        }
+
         if self.is_obstacle {
             entity.insert(NavObstacle);
         }
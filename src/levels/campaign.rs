@@ -0,0 +1,154 @@
+use crate::prelude::*;
+use crate::ui;
+use crate::weapons::{PrefabWeapons, Weapon};
+
+/// Tracks player's progress through the levels.
+///
+/// Weapon and flashlight are carried from one level to another - when a level
+/// gets restarted (e.g. after the game over screen), player gets back whatever
+/// they had when they first entered it.
+///
+/// Levels can also contain checkpoints (`checkpoint:name` objects) - once the
+/// player walks through one, dying respawns them there instead of restarting
+/// the entire level.
+#[derive(Resource, Default)]
+pub struct Campaign {
+    level: Option<Level>,
+    furthest_level: Option<Level>,
+    loadout: Option<Loadout>,
+    checkpoint: Option<Checkpoint>,
+}
+
+/// Stuff the player carries between levels.
+#[derive(Clone, Debug)]
+pub struct Loadout {
+    pub weapon: &'static str,
+    pub ammo: Option<usize>,
+    pub has_flashlight: bool,
+}
+
+#[derive(Clone, Debug)]
+pub struct Checkpoint {
+    pub name: String,
+    pub transform: Transform,
+    pub loadout: Loadout,
+}
+
+impl Campaign {
+    pub fn furthest_level(&self) -> Option<Level> {
+        self.furthest_level
+    }
+
+    pub fn checkpoint(&self) -> Option<&Checkpoint> {
+        self.checkpoint.as_ref()
+    }
+
+    /// Overrides what the player gets when the current level is restarted.
+    pub fn set_loadout(&mut self, loadout: Loadout) {
+        self.loadout = Some(loadout);
+    }
+
+    pub(super) fn enter_level(
+        mut this: ResMut<Self>,
+        coordinator: Res<LevelsCoordinator>,
+        registry: Res<LevelRegistry>,
+        prefab_weapons: Res<PrefabWeapons>,
+        mut weapon_sprites: ResMut<ui::gun::State>,
+        mut weapon: Query<&mut Weapon, With<Player>>,
+        mut inventory: Query<&mut Inventory>,
+    ) {
+        let Some(level) = coordinator.loading_level else { return };
+
+        if level == registry.game_over() {
+            return;
+        }
+
+        let Ok(mut weapon) = weapon.get_single_mut() else { return };
+        let Ok(mut inventory) = inventory.get_single_mut() else { return };
+
+        this.checkpoint = None;
+
+        if this.level != Some(level) || this.loadout.is_none() {
+            log::info!("Entering level: {}", registry.name(level));
+
+            this.level = Some(level);
+            this.loadout = Some(Loadout::capture(&weapon, &inventory));
+
+            if this
+                .furthest_level
+                .map_or(true, |furthest| furthest < level)
+            {
+                this.furthest_level = Some(level);
+            }
+        }
+
+        *inventory = Default::default();
+
+        if let Some(loadout) = &this.loadout {
+            loadout.apply(
+                &prefab_weapons,
+                &mut weapon,
+                &mut inventory,
+                &mut weapon_sprites,
+            );
+        }
+    }
+
+    pub(super) fn track_checkpoints(
+        mut this: ResMut<Self>,
+        mut level_rx: EventReader<LevelGameplayEvent>,
+        player: Query<(&Transform, &Weapon), With<Player>>,
+        inventory: Query<&Inventory>,
+    ) {
+        for event in level_rx.iter() {
+            let LevelGameplayEvent::ZoneEntered(zone) = event else { continue };
+            let Some(name) = zone.strip_prefix("checkpoint:") else {
+                continue;
+            };
+
+            if this.checkpoint.as_ref().map_or(false, |cp| cp.name == name) {
+                continue;
+            }
+
+            let Ok((transform, weapon)) = player.get_single() else { continue };
+            let Ok(inventory) = inventory.get_single() else { continue };
+
+            log::info!("Checkpoint reached: {}", name);
+
+            this.checkpoint = Some(Checkpoint {
+                name: name.to_owned(),
+                transform: *transform,
+                loadout: Loadout::capture(weapon, inventory),
+            });
+        }
+    }
+}
+
+impl Loadout {
+    pub fn capture(weapon: &Weapon, inventory: &Inventory) -> Self {
+        Self {
+            weapon: weapon.definition.name,
+            ammo: weapon.ammo,
+            has_flashlight: inventory.has_flashlight,
+        }
+    }
+
+    pub fn apply(
+        &self,
+        prefab_weapons: &PrefabWeapons,
+        weapon: &mut Weapon,
+        inventory: &mut Inventory,
+        weapon_sprites: &mut ui::gun::State,
+    ) {
+        inventory.has_flashlight = self.has_flashlight;
+
+        let Some((definition, sprites)) = prefab_weapons.get(self.weapon) else {
+            log::warn!("Loadout refers to unknown weapon: {}", self.weapon);
+            return;
+        };
+
+        weapon.update_def(definition.clone());
+        weapon.ammo = self.ammo;
+        weapon_sprites.current_weapon = sprites.clone();
+    }
+}
@@ -1,11 +1,18 @@
-use std::ops;
+use std::convert::Infallible;
 use std::str::FromStr;
+use std::{fmt, ops};
 
+use crate::args::Args;
 use crate::prelude::*;
 
 #[derive(Resource)]
 pub struct LevelsCoordinator {
     pub current_level: Level,
+
+    /// Level that's being loaded during this frame, if any; set by
+    /// [`Self::unload()`], used to decide which level's init should run.
+    pub loading_level: Option<Level>,
+
     pub is_game_over: bool,
     pub time_in_game_over: f32,
 }
@@ -13,12 +20,21 @@ pub struct LevelsCoordinator {
 impl LevelsCoordinator {
     pub fn init(
         mut commands: Commands,
+        args: Res<Args>,
+        registry: Res<LevelRegistry>,
         mut goto_level_tx: EventWriter<GotoLevel>,
     ) {
-        let starting_level = Level::l1();
+        let starting_level =
+            args.level.clone().unwrap_or_else(|| "level1".into());
+
+        let current_level =
+            registry.resolve(&starting_level).unwrap_or_else(|| {
+                panic!("Unknown starting level: {}", starting_level)
+            });
 
         let levels_coordinator = LevelsCoordinator {
-            current_level: starting_level,
+            current_level,
+            loading_level: None,
             is_game_over: false,
             time_in_game_over: 0.0,
         };
@@ -28,54 +44,11 @@ impl LevelsCoordinator {
         goto_level_tx.send(GotoLevel::new(starting_level));
     }
 
-    pub fn handle_game_state(
-        mut this: ResMut<LevelsCoordinator>,
-        mut goto_level_tx: EventReader<GotoLevel>,
-    ) {
-        let last_level = goto_level_tx.iter().last().copied();
-
-        if let Some(level) = last_level {
-            if *level == Level::l0() {
-                this.is_game_over = true;
-                this.time_in_game_over = 0.0;
-            } else {
-                this.is_game_over = false;
-                this.current_level = *level;
-            }
-        }
-    }
-
-    pub fn process_zones(
-        player: Query<&Transform, With<Player>>,
-        mut zones: Query<&mut LevelZone>,
-        mut level_tx: EventWriter<LevelGameplayEvent>,
-    ) {
-        let Ok(player_xform) = player.get_single() else { return; };
-
-        for mut zone in zones.iter_mut() {
-            let contains_player = zone.contains(player_xform);
-
-            if zone.contains_player != contains_player {
-                if contains_player {
-                    log::trace!("Zone entered: {}", zone.name);
-
-                    level_tx.send(LevelGameplayEvent::ZoneEntered(
-                        zone.name.clone(),
-                    ));
-                } else {
-                    log::trace!("Zone left: {}", zone.name);
-
-                    level_tx
-                        .send(LevelGameplayEvent::ZoneLeft(zone.name.clone()));
-                }
-
-                zone.contains_player = contains_player;
-            }
-        }
-    }
-
     pub fn unload(
         mut commands: Commands,
+        mut this: ResMut<LevelsCoordinator>,
+        registry: Res<LevelRegistry>,
+        mut save_point: ResMut<SavePoint>,
         mut goto_level_rx: EventReader<GotoLevel>,
         entities: Query<
             Entity,
@@ -86,11 +59,28 @@ impl LevelsCoordinator {
                 With<GcAfterLevelUnloaded>,
             )>,
         >,
-        mut inventory: Query<&mut Inventory>,
         mut change_hud_visibility_tx: EventWriter<ChangeHudVisibility>,
     ) {
-        if goto_level_rx.iter().count() == 0 {
+        this.loading_level = None;
+
+        let Some(goto) = goto_level_rx.iter().last() else { return };
+
+        let Some(level) = registry.resolve(goto) else {
+            log::error!("Unknown level: {}", **goto);
             return;
+        };
+
+        log::info!("Loading level: {}", registry.name(level));
+
+        this.loading_level = Some(level);
+        save_point.begin_loading(registry.name(level));
+
+        if level == registry.game_over() {
+            this.is_game_over = true;
+            this.time_in_game_over = 0.0;
+        } else {
+            this.is_game_over = false;
+            this.current_level = level;
         }
 
         if !entities.is_empty() {
@@ -101,66 +91,69 @@ impl LevelsCoordinator {
             commands.entity(entity).despawn_recursive();
         }
 
-        if let Ok(mut inventory) = inventory.get_single_mut() {
-            *inventory = Default::default();
-        }
-
         change_hud_visibility_tx.send(ChangeHudVisibility::show());
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub struct Level(usize);
+/// Identifies level within the [`LevelRegistry`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Level(pub(super) usize);
 
 impl Level {
-    pub fn l0() -> Self {
-        Self(0)
-    }
-
-    pub fn l1() -> Self {
-        Self(1)
+    pub fn id(self) -> usize {
+        self.0
     }
+}
 
-    pub fn l2() -> Self {
-        Self(2)
-    }
+/// Refers to a level either by its id or by its name, e.g. `2` or `level2`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LevelRef {
+    Id(usize),
+    Name(String),
+}
 
-    pub fn l3() -> Self {
-        Self(3)
+impl From<Level> for LevelRef {
+    fn from(level: Level) -> Self {
+        Self::Id(level.0)
     }
+}
 
-    pub fn l4() -> Self {
-        Self(4)
+impl From<&str> for LevelRef {
+    fn from(name: &str) -> Self {
+        Self::Name(name.to_owned())
     }
+}
 
-    pub fn l5() -> Self {
-        Self(5)
-    }
+impl FromStr for LevelRef {
+    type Err = Infallible;
 
-    pub fn l6() -> Self {
-        Self(6)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(s.parse()
+            .map(Self::Id)
+            .unwrap_or_else(|_| Self::Name(s.to_owned())))
     }
 }
 
-impl FromStr for Level {
-    type Err = &'static str;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        s.parse().map(Self).map_err(|_| "Not a valid number")
+impl fmt::Display for LevelRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LevelRef::Id(id) => write!(f, "{}", id),
+            LevelRef::Name(name) => write!(f, "{}", name),
+        }
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub struct GotoLevel(Level);
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GotoLevel(LevelRef);
 
 impl GotoLevel {
-    pub fn new(level: Level) -> Self {
-        Self(level)
+    pub fn new(level: impl Into<LevelRef>) -> Self {
+        Self(level.into())
     }
 }
 
 impl ops::Deref for GotoLevel {
-    type Target = Level;
+    type Target = LevelRef;
 
     fn deref(&self) -> &Self::Target {
         &self.0
@@ -173,4 +166,5 @@ pub enum LevelGameplayEvent {
     KeyPicked(String),
     ZoneEntered(String),
     ZoneLeft(String),
+    WallRemoved(String),
 }
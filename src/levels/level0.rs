@@ -10,11 +10,16 @@ pub fn init(
     mut commands: Commands,
     mut game_commands: EventWriter<Command>,
     assets: Res<Assets>,
-    mut goto_level_rx: EventReader<GotoLevel>,
+    mut player: Query<(&mut Transform, &mut Vertical), With<Player>>,
 ) {
-    if !goto_level_rx.iter().any(|level| **level == Level::l0()) {
-        return;
-    }
+    // The player might've died by falling into a pit, in which case they'd
+    // keep falling through the game over screen
+    let (mut player_xform, mut player_vertical) = player.single_mut();
+
+    *player_xform = Transform::default();
+    player_vertical.velocity = 0.0;
+
+    // -----
 
     let mut lvl = LevelBuilder::new(&mut commands, &assets);
 
@@ -32,11 +37,10 @@ pub fn process(
     time: Res<Time>,
     mut coordinator: ResMut<LevelsCoordinator>,
     mut game_commands: EventWriter<Command>,
-    mut goto_level_rx: EventReader<GotoLevel>,
     mut keyboard_rx: EventReader<KeyboardInput>,
     mut mouse_button_rx: EventReader<MouseButtonInput>,
 ) {
-    if goto_level_rx.iter().any(|level| **level != Level::l0()) {
+    if coordinator.loading_level.is_some() && !coordinator.is_game_over {
         // Unlock input for the next level
         game_commands.send(Command::UnlockInput);
     }
@@ -55,7 +59,7 @@ pub fn process(
 
         if total != 0 {
             game_commands.send(Command::GotoLevel {
-                level: coordinator.current_level,
+                level: coordinator.current_level.into(),
             })
         }
     }
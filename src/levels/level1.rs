@@ -77,15 +77,10 @@ const ELEPHANT_Z: f32 = ROOM_CENTER_Z + 3.0;
 pub fn init(
     mut commands: Commands,
     assets: Res<Assets>,
-    mut goto_level_rx: EventReader<GotoLevel>,
+    mut save_point: ResMut<SavePoint>,
     mut player: Query<(&mut Player, &mut Transform)>,
+    mut change_hud_visibility_tx: EventWriter<ChangeHudVisibility>,
 ) {
-    if !goto_level_rx.iter().any(|level| **level == Level::l1()) {
-        return;
-    }
-
-    // -----
-
     let (mut player, mut player_xform) = player.single_mut();
 
     player.can_move = false;
@@ -108,50 +103,6 @@ pub fn init(
     lvl.wall(-2, -1, 2, -1, 2).spawn();
     lvl.wall(-2, -1, -2, 20, 3).spawn();
 
-    let corr_sl0 = lvl
-        .spot_light(
-            vec3(0.0, 4.0, 0.0),
-            vec3(0.0, 0.0, 0.0),
-            PI / 4.0,
-            Color::hex(0xffffff),
-            0.0,
-        )
-        .insert(Fade::fade_in_delayed(0.5, 0.1))
-        .id();
-
-    let corr_sl1 = lvl
-        .spot_light(
-            vec3(0.0, 4.0, 6.0),
-            vec3(0.0, 0.0, 6.0),
-            PI / 4.0,
-            Color::hex(0xffffff),
-            0.0,
-        )
-        .insert(Fade::fade_in_delayed(1.5, 0.1))
-        .id();
-
-    let corr_sl2 = lvl
-        .spot_light(
-            vec3(0.0, 4.0, 12.0),
-            vec3(0.0, 0.0, 12.0),
-            PI / 4.0,
-            Color::hex(0xffffff),
-            0.0,
-        )
-        .insert(Fade::fade_in_delayed(2.5, 0.1))
-        .id();
-
-    let corr_sl3 = lvl
-        .spot_light(
-            vec3(0.0, 4.0, 18.0),
-            vec3(0.0, 0.0, 18.0),
-            PI / 4.0,
-            Color::hex(0xffffff),
-            0.0,
-        )
-        .insert(Fade::fade_in_delayed(3.5, 0.1))
-        .id();
-
     // -----
 
     lvl.floor(-8, 21, 8, 37)
@@ -184,30 +135,87 @@ pub fn init(
                 .with_reflectivity(0.8)
                 .with_reflection_color(Color::hex(0xffffff)),
         )
-        .with_collider(Collider::circle(4.0, 16))
+        .with_collider(Collider::circle(4.0))
         .spawn();
 
-    let room_sl0 = lvl
-        .spot_light(
-            vec3(0.0, 1.0, ELEPHANT_Z - 4.0),
-            vec3(0.0, 0.0, ELEPHANT_Z),
-            PI / 2.0,
-            Color::hex(0xff0000),
-            0.0,
-        )
-        .insert(Fade::fade_in_delayed(4.5, 0.1))
-        .id();
+    let stage = if save_point.take_resume().as_deref() == Some("room") {
+        player.can_move = true;
+        change_hud_visibility_tx.send(ChangeHudVisibility::hide());
 
-    lvl.complete(LevelState {
-        stage: LevelStage::Intro {
+        let (txt_elephant, room_sl0, room_sl1) = spawn_room_lights(&mut lvl);
+
+        LevelStage::InsideRoom {
+            txt_elephant,
+            room_sl0,
+            room_sl1,
+        }
+    } else {
+        let corr_sl0 = lvl
+            .spot_light(
+                vec3(0.0, 4.0, 0.0),
+                vec3(0.0, 0.0, 0.0),
+                PI / 4.0,
+                Color::hex(0xffffff),
+                0.0,
+            )
+            .insert(Fade::fade_in_delayed(0.5, 0.1))
+            .id();
+
+        let corr_sl1 = lvl
+            .spot_light(
+                vec3(0.0, 4.0, 6.0),
+                vec3(0.0, 0.0, 6.0),
+                PI / 4.0,
+                Color::hex(0xffffff),
+                0.0,
+            )
+            .insert(Fade::fade_in_delayed(1.5, 0.1))
+            .id();
+
+        let corr_sl2 = lvl
+            .spot_light(
+                vec3(0.0, 4.0, 12.0),
+                vec3(0.0, 0.0, 12.0),
+                PI / 4.0,
+                Color::hex(0xffffff),
+                0.0,
+            )
+            .insert(Fade::fade_in_delayed(2.5, 0.1))
+            .id();
+
+        let corr_sl3 = lvl
+            .spot_light(
+                vec3(0.0, 4.0, 18.0),
+                vec3(0.0, 0.0, 18.0),
+                PI / 4.0,
+                Color::hex(0xffffff),
+                0.0,
+            )
+            .insert(Fade::fade_in_delayed(3.5, 0.1))
+            .id();
+
+        let room_sl0 = lvl
+            .spot_light(
+                vec3(0.0, 1.0, ELEPHANT_Z - 4.0),
+                vec3(0.0, 0.0, ELEPHANT_Z),
+                PI / 2.0,
+                Color::hex(0xff0000),
+                0.0,
+            )
+            .insert(Fade::fade_in_delayed(4.5, 0.1))
+            .id();
+
+        LevelStage::Intro {
             tt: 0.0,
             corr_sl0,
             corr_sl1,
             corr_sl2,
             corr_sl3,
             room_sl0,
-        },
-    });
+        }
+    };
+
+    lvl.complete(LevelState { stage });
 }
 
 #[derive(Component)]
@@ -249,11 +257,22 @@ enum LevelStage {
     Outro,
 }
 
+impl LevelStage {
+    /// See: [`SavePoint`].
+    fn save_point(&self) -> Option<String> {
+        match self {
+            Self::InsideRoom { .. } => Some("room".into()),
+            _ => None,
+        }
+    }
+}
+
 pub fn process(
     assets: Res<Assets>,
     time: Res<Time>,
     keys: Res<Input<KeyCode>>,
     mut commands: Commands,
+    mut save_point: ResMut<SavePoint>,
     mut level: Query<&mut LevelState>,
     mut player: Query<&mut Player>,
     camera: Query<&Camera>,
@@ -265,6 +284,8 @@ pub fn process(
 ) {
     let Ok(mut level) = level.get_single_mut() else { return };
 
+    save_point.set(level.stage.save_point());
+
     match &mut level.stage {
         LevelStage::Intro {
             tt,
@@ -324,39 +345,9 @@ pub fn process(
                 commands.entity(*sl).insert(Fade::fade_out(0.25));
             }
 
-            let mut lvl = LevelBuilder::new(&mut commands, &assets);
-
-            let txt_elephant = lvl
-                .commands()
-                .spawn((
-                    Text::new("Press F to address the elephant in the room")
-                        .centered(),
-                    Transform::from_translation(vec3(0.5, 0.05, 0.0)),
-                    Visibility::invisible(),
-                ))
-                .id();
-
-            let room_sl0 = lvl
-                .spot_light(
-                    vec3(-8.4, 2.0, ELEPHANT_Z - 8.4),
-                    vec3(0.0, 0.0, ELEPHANT_Z),
-                    PI / 3.0,
-                    Color::hex(0xffffff),
-                    0.0,
-                )
-                .insert(Fade::fade_in_delayed(0.4, 0.15))
-                .id();
-
-            let room_sl1 = lvl
-                .spot_light(
-                    vec3(8.4, 2.0, ELEPHANT_Z - 8.4),
-                    vec3(0.0, 0.0, ELEPHANT_Z),
-                    PI / 3.0,
-                    Color::hex(0xffffff),
-                    0.0,
-                )
-                .insert(Fade::fade_in_delayed(0.4, 0.15))
-                .id();
+            let (txt_elephant, room_sl0, room_sl1) = spawn_room_lights(
+                &mut LevelBuilder::new(&mut commands, &assets),
+            );
 
             level.stage = if *intro_completed {
                 LevelStage::InsideRoom {
@@ -422,8 +413,45 @@ pub fn process(
             let ready = typewriter_rx.iter().any(|event| event.id == "outro");
 
             if ready {
-                goto_level_tx.send(GotoLevel::new(Level::l2()));
+                goto_level_tx.send(GotoLevel::new("level2"));
             }
         }
     }
 }
+
+/// Spawns the elephant's prompt and lights that illuminate the room once the
+/// player gets inside.
+fn spawn_room_lights(lvl: &mut LevelBuilder) -> (Entity, Entity, Entity) {
+    let txt_elephant = lvl
+        .commands()
+        .spawn((
+            Text::new("Press F to address the elephant in the room").centered(),
+            Transform::from_translation(vec3(0.5, 0.05, 0.0)),
+            Visibility::invisible(),
+        ))
+        .id();
+
+    let room_sl0 = lvl
+        .spot_light(
+            vec3(-8.4, 2.0, ELEPHANT_Z - 8.4),
+            vec3(0.0, 0.0, ELEPHANT_Z),
+            PI / 3.0,
+            Color::hex(0xffffff),
+            0.0,
+        )
+        .insert(Fade::fade_in_delayed(0.4, 0.15))
+        .id();
+
+    let room_sl1 = lvl
+        .spot_light(
+            vec3(8.4, 2.0, ELEPHANT_Z - 8.4),
+            vec3(0.0, 0.0, ELEPHANT_Z),
+            PI / 3.0,
+            Color::hex(0xffffff),
+            0.0,
+        )
+        .insert(Fade::fade_in_delayed(0.4, 0.15))
+        .id();
+
+    (txt_elephant, room_sl0, room_sl1)
+}
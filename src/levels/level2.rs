@@ -3,17 +3,36 @@ use std::time::Duration;
 
 use crate::prelude::*;
 
+pub const MAP: &str = include_str!("../../assets/levels/level2.tmj");
+
+/// How long the gate keeps rising, in seconds
+const GATE_RISE_TIME: f32 = 3.15;
+
+/// How fast the gate rises, in units per second
+const GATE_RISE_SPEED: f32 = 1.0 / 1.2;
+
 pub fn init(
     mut commands: Commands,
     assets: Res<Assets>,
-    mut goto_level_rx: EventReader<GotoLevel>,
+    mut save_point: ResMut<SavePoint>,
     mut player: Query<(&mut Player, &mut Transform)>,
 ) {
-    if !goto_level_rx.iter().any(|level| **level == Level::l2()) {
-        return;
-    }
+    let stage = match save_point.take_resume().as_deref() {
+        Some("awaiting-flashlight") => LevelStage::AwaitingFlashlightPickup,
+        Some("awaiting-zone") => LevelStage::AwaitingZoneEnter,
+        Some("awaiting-key:door-a") => {
+            LevelStage::AwaitingKeyPickup { door: "door-a" }
+        }
+        Some("awaiting-key:door-b") => {
+            LevelStage::AwaitingKeyPickup { door: "door-b" }
+        }
+        _ => LevelStage::Intro,
+    };
 
-    // -----
+    let is_gate_risen = matches!(
+        stage,
+        LevelStage::AwaitingZoneEnter | LevelStage::AwaitingKeyPickup { .. }
+    );
 
     let (mut player, mut player_xform) = player.single_mut();
 
@@ -52,6 +71,7 @@ pub fn init(
                 .without_casting_shadows()
                 .with_uv_divisor(8, 8),
         )
+        .with_floor(Floor::rect(1.0, 1.0))
         .spawn();
 
     lvl.model("table")
@@ -148,10 +168,16 @@ pub fn init(
         }
     }
 
+    let gate_y = if is_gate_risen {
+        GATE_RISE_TIME * GATE_RISE_SPEED
+    } else {
+        0.0
+    };
+
     let ent_gate = lvl
         .model("gate")
         .dynamic()
-        .with_translation(vec3(-8.52, 0.0, 0.0))
+        .with_translation(vec3(-8.52, gate_y, 0.0))
         .with_scale(vec3(1.0, 1.5, 4.0))
         .with_material(
             Material::default()
@@ -184,22 +210,28 @@ pub fn init(
             Color::hex(0xffffff) * 0.75,
             0.0,
         )
-        .insert(Fade::fade_in(1.5))
         .id();
 
     let ent_l0 = lvl
         .point_light(vec3(-15.0, 2.0, 0.0), Color::hex(0xff0000), 0.0)
         .id();
 
-    Picker::flashlight()
-        .with_position(vec2(-6.8, 0.5))
-        .spawn(lvl.assets(), lvl.commands());
+    if is_gate_risen {
+        // Fast-forward to where the gate has already risen (see the
+        // `AwaitingGateRise` stage), with the flashlight already picked up
+        lvl.commands().entity(ent_gate).remove::<Collider>();
+        lvl.commands().entity(ent_lamp).despawn();
+    } else {
+        lvl.commands().entity(ent_sl0).insert(Fade::fade_in(1.5));
+
+        Picker::flashlight()
+            .with_position(vec2(-6.8, 0.5))
+            .spawn(lvl.assets(), lvl.commands());
+    }
 
     // -----
 
-    let locator =
-        LevelLoader::load(include_str!("../../assets/levels/level2.tmj"))
-            .spawn(&mut lvl);
+    let locator = LevelLoader::load(MAP).spawn(&mut lvl);
 
     lvl.model("gate")
         .dynamic()
@@ -215,6 +247,12 @@ pub fn init(
         .with_collider(Collider::line(vec2(0.0, -1.0), vec2(0.0, 1.0)))
         .spawn();
 
+    if let LevelStage::AwaitingKeyPickup { door } = stage {
+        let other_door = if door == "door-a" { "door-b" } else { "door-a" };
+
+        lvl.commands().entity(locator.door(other_door)).despawn();
+    }
+
     // -----
 
     lvl.complete(LevelState {
@@ -223,7 +261,7 @@ pub fn init(
         ent_lamp,
         ent_sl0,
         ent_l0,
-        stage: LevelStage::Intro,
+        stage,
     });
 }
 
@@ -243,14 +281,31 @@ enum LevelStage {
     AwaitingGateRise { timer: Timer },
     AwaitingMothsDeath { moths: Vec<Entity> },
     AwaitingZoneEnter,
-    AwaitingKeyPickup,
+    AwaitingKeyPickup { door: &'static str },
     AwaitingLeaving,
 }
 
+impl LevelStage {
+    /// See: [`SavePoint`].
+    fn save_point(&self) -> Option<String> {
+        match self {
+            Self::AwaitingFlashlightPickup => {
+                Some("awaiting-flashlight".into())
+            }
+            Self::AwaitingZoneEnter => Some("awaiting-zone".into()),
+            Self::AwaitingKeyPickup { door } => {
+                Some(format!("awaiting-key:{}", door))
+            }
+            _ => None,
+        }
+    }
+}
+
 pub fn process(
     time: Res<Time>,
     assets: Res<Assets>,
     mut commands: Commands,
+    mut save_point: ResMut<SavePoint>,
     mut level: Query<&mut LevelState>,
     mut transforms: Query<&mut Transform>,
     mut lights: Query<&mut Light>,
@@ -265,6 +320,8 @@ pub fn process(
     let dt = time.delta();
     let level = &mut *level;
 
+    save_point.set(level.stage.save_point());
+
     // -----
 
     if let Ok(mut light) = lights.get_mut(level.ent_sl0) {
@@ -296,7 +353,7 @@ pub fn process(
             if inventory.has_flashlight {
                 level.stage = LevelStage::AwaitingGateRise {
                     timer: Timer::new(
-                        Duration::from_millis(3150),
+                        Duration::from_secs_f32(GATE_RISE_TIME),
                         TimerMode::Once,
                     ),
                 };
@@ -307,7 +364,7 @@ pub fn process(
             timer.tick(dt);
 
             transforms.get_mut(level.ent_gate).unwrap().translation.y +=
-                time.delta_seconds() / 1.2;
+                time.delta_seconds() * GATE_RISE_SPEED;
 
             if timer.just_finished() {
                 commands.entity(level.ent_gate).remove::<Collider>();
@@ -361,12 +418,12 @@ pub fn process(
                             "gotcha this time !! DON'T watch your back !!!",
                         ));
 
-                        let (moth_spawn_point, other_door) = if name == "door-a"
-                        {
-                            ("monster-door-a", "door-b")
-                        } else {
-                            ("monster-door-b", "door-a")
-                        };
+                        let (door, moth_spawn_point, other_door) =
+                            if name == "door-a" {
+                                ("door-a", "monster-door-a", "door-b")
+                            } else {
+                                ("door-b", "monster-door-b", "door-a")
+                            };
 
                         MothMonster::spawn(
                             &assets,
@@ -378,7 +435,7 @@ pub fn process(
                             .entity(level.locator.door(other_door))
                             .despawn();
 
-                        level.stage = LevelStage::AwaitingKeyPickup;
+                        level.stage = LevelStage::AwaitingKeyPickup { door };
                     }
 
                     _ => {
@@ -388,7 +445,7 @@ pub fn process(
             }
         }
 
-        LevelStage::AwaitingKeyPickup => {
+        LevelStage::AwaitingKeyPickup { .. } => {
             for event in level_rx.iter() {
                 match event {
                     LevelGameplayEvent::ZoneEntered(name) if name == "key" => {
@@ -426,7 +483,7 @@ pub fn process(
                     }
 
                     LevelGameplayEvent::ZoneEntered(name) if name == "end" => {
-                        goto_level_tx.send(GotoLevel::new(Level::l3()));
+                        goto_level_tx.send(GotoLevel::new("level3"));
                     }
 
                     _ => {
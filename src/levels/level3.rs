@@ -2,18 +2,14 @@ use std::time::Duration;
 
 use crate::prelude::*;
 
+pub const MAP: &str = include_str!("../../assets/levels/level3.tmj");
+
 pub fn init(
     mut commands: Commands,
     assets: Res<Assets>,
-    mut goto_level_rx: EventReader<GotoLevel>,
+    mut save_point: ResMut<SavePoint>,
     mut player: Query<(&mut Player, &mut Transform)>,
 ) {
-    if !goto_level_rx.iter().any(|level| **level == Level::l3()) {
-        return;
-    }
-
-    // -----
-
     let (mut player, mut player_xform) = player.single_mut();
 
     player.can_move = true;
@@ -23,16 +19,22 @@ pub fn init(
 
     let mut lvl = LevelBuilder::new(&mut commands, &assets);
 
-    let locator =
-        LevelLoader::load(include_str!("../../assets/levels/level3.tmj"))
-            .spawn(&mut lvl);
+    let locator = LevelLoader::load(MAP)
+        .with_script(include_str!("../../assets/levels/level3.script.json"))
+        .spawn(&mut lvl);
+
+    let stage = save_point
+        .take_resume()
+        .and_then(|stage| LevelStage::resume(&stage))
+        .unwrap_or(LevelStage::EntryIntro);
+
+    if let LevelStage::InsideCorridor { corridor } = stage {
+        activate_corridor(lvl.commands(), &locator, corridor);
+    }
 
     // -----
 
-    lvl.complete(LevelState {
-        locator,
-        stage: LevelStage::EntryIntro,
-    });
+    lvl.complete(LevelState { locator, stage });
 }
 
 #[derive(Component)]
@@ -44,25 +46,67 @@ pub struct LevelState {
 enum LevelStage {
     EntryIntro,
     EntryAwaitingKey,
-    EntryAwaitingDoor {
-        corridor: &'static str,
-    },
-    InsideCorridor {
-        dialog1_said: bool,
-        dialog2_said: bool,
-    },
-    Trapped {
-        timer: Timer,
-    },
-    Outro {
-        timer: Timer,
-    },
+    EntryAwaitingDoor { corridor: &'static str },
+    InsideCorridor { corridor: &'static str },
+    Trapped { timer: Timer },
+    Outro { timer: Timer },
+}
+
+impl LevelStage {
+    /// See: [`LevelScriptState::set_stage()`].
+    fn name(&self) -> &'static str {
+        match self {
+            Self::EntryIntro => "entry-intro",
+            Self::EntryAwaitingKey => "entry-awaiting-key",
+            Self::EntryAwaitingDoor { .. } => "entry-awaiting-door",
+            Self::InsideCorridor { .. } => "inside-corridor",
+            Self::Trapped { .. } => "trapped",
+            Self::Outro { .. } => "outro",
+        }
+    }
+
+    /// See: [`SavePoint`].
+    fn save_point(&self) -> Option<String> {
+        match self {
+            Self::EntryAwaitingKey => Some("awaiting-key".into()),
+            Self::EntryAwaitingDoor { corridor } => {
+                Some(format!("awaiting-door:{}", corridor))
+            }
+            Self::InsideCorridor { corridor } => {
+                Some(format!("inside-corridor:{}", corridor))
+            }
+            _ => None,
+        }
+    }
+
+    fn resume(save_point: &str) -> Option<Self> {
+        if save_point == "awaiting-key" {
+            return Some(Self::EntryAwaitingKey);
+        }
+
+        let (stage, corridor) = save_point.split_once(':')?;
+
+        let corridor = match corridor {
+            "a" => "a",
+            "b" => "b",
+            "c" => "c",
+            _ => return None,
+        };
+
+        match stage {
+            "awaiting-door" => Some(Self::EntryAwaitingDoor { corridor }),
+            "inside-corridor" => Some(Self::InsideCorridor { corridor }),
+            _ => None,
+        }
+    }
 }
 
 pub fn process(
     time: Res<Time>,
     mut commands: Commands,
+    mut save_point: ResMut<SavePoint>,
     mut level: Query<&mut LevelState>,
+    mut script: Query<&mut LevelScriptState>,
     mut typewriter_tx: EventWriter<TypewriterPrint>,
     mut level_rx: EventReader<LevelGameplayEvent>,
     mut player: Query<(&mut Player, &mut Transform)>,
@@ -74,6 +118,12 @@ pub fn process(
     let level = &mut *level;
     let dt = time.delta();
 
+    save_point.set(level.stage.save_point());
+
+    if let Ok(mut script) = script.get_single_mut() {
+        script.set_stage(level.stage.name());
+    }
+
     // -----
 
     match &mut level.stage {
@@ -165,66 +215,20 @@ pub fn process(
                 "you want to fight ??! -- let's fight!",
             ));
 
-            commands
-                .entity(level.locator.torch("entry-1"))
-                .remove::<TorchActive>();
-
-            commands
-                .entity(level.locator.torch("entry-2"))
-                .remove::<TorchActive>();
-
-            commands
-                .entity(level.locator.torch(format!("corridor-{}", corridor)))
-                .insert(TorchActive::now());
+            activate_corridor(&mut commands, &level.locator, *corridor);
 
             level.stage = LevelStage::InsideCorridor {
-                dialog1_said: false,
-                dialog2_said: false,
+                corridor: *corridor,
             };
         }
 
-        LevelStage::InsideCorridor {
-            dialog1_said,
-            dialog2_said,
-        } => {
+        LevelStage::InsideCorridor { .. } => {
             let mut ready = false;
 
             for event in level_rx.iter() {
-                match event {
-                    LevelGameplayEvent::ZoneEntered(zone_name) => {
-                        match zone_name.as_str() {
-                            "corridor.dialog-1" => {
-                                if !*dialog1_said {
-                                    typewriter_tx.send(TypewriterPrint::new(
-                                        "uhm hey what are you doing",
-                                    ));
-
-                                    *dialog1_said = true;
-                                }
-                            }
-
-                            "corridor.dialog-2" => {
-                                if !*dialog2_said {
-                                    typewriter_tx.send(TypewriterPrint::new(
-                                        "come on i was just joking please hey",
-                                    ));
-
-                                    *dialog2_said = true;
-                                }
-                            }
-
-                            "trap" => {
-                                ready = true;
-                            }
-
-                            _ => {
-                                //
-                            }
-                        }
-                    }
-
-                    _ => {
-                        //
+                if let LevelGameplayEvent::ZoneEntered(zone_name) = event {
+                    if zone_name == "trap" {
+                        ready = true;
                     }
                 }
             }
@@ -295,8 +299,27 @@ pub fn process(
             timer.tick(dt);
 
             if timer.just_finished() {
-                goto_level_tx.send(GotoLevel::new(Level::l4()));
+                goto_level_tx.send(GotoLevel::new("level4"));
             }
         }
     }
 }
+
+/// Lights up the corridor the player has chosen, turning off the entry.
+fn activate_corridor(
+    commands: &mut Commands,
+    locator: &LevelLocator,
+    corridor: &str,
+) {
+    commands
+        .entity(locator.torch("entry-1"))
+        .remove::<TorchActive>();
+
+    commands
+        .entity(locator.torch("entry-2"))
+        .remove::<TorchActive>();
+
+    commands
+        .entity(locator.torch(format!("corridor-{}", corridor)))
+        .insert(TorchActive::now());
+}
@@ -4,19 +4,14 @@ use std::time::Duration;
 use crate::music::MusicTrack;
 use crate::prelude::*;
 
+pub const MAP: &str = include_str!("../../assets/levels/level4.tmj");
+
 pub fn init(
     mut commands: Commands,
     mut game_commands: EventWriter<Command>,
     assets: Res<Assets>,
-    mut goto_level_rx: EventReader<GotoLevel>,
     mut player: Query<(&mut Player, &mut Transform)>,
 ) {
-    if !goto_level_rx.iter().any(|level| **level == Level::l4()) {
-        return;
-    }
-
-    // -----
-
     let (mut player, mut player_xform) = player.single_mut();
 
     player.can_move = true;
@@ -38,9 +33,7 @@ pub fn init(
         .point_light(Default::default(), Color::hex(0xff61c6) * 0.3, 0.0)
         .id();
 
-    let locator =
-        LevelLoader::load(include_str!("../../assets/levels/level4.tmj"))
-            .spawn(&mut lvl);
+    let locator = LevelLoader::load(MAP).spawn(&mut lvl);
 
     for column in 1..=4 {
         let column_pos = locator.tag(format!("column-{}", column));
@@ -172,7 +165,7 @@ pub fn process(
                 game_commands.send(Command::SwitchTrack {
                     track: MusicTrack::Doome,
                 });
-                goto_level_tx.send(GotoLevel::new(Level::l5()));
+                goto_level_tx.send(GotoLevel::new("level5"));
             }
         }
     }
@@ -2,18 +2,14 @@ use std::time::Duration;
 
 use crate::prelude::*;
 
+pub const MAP: &str = include_str!("../../assets/levels/level5.tmj");
+
 pub fn init(
     mut commands: Commands,
     assets: Res<Assets>,
-    mut goto_level_rx: EventReader<GotoLevel>,
+    mut save_point: ResMut<SavePoint>,
     mut player: Query<(&mut Player, &mut Transform)>,
 ) {
-    if !goto_level_rx.iter().any(|level| **level == Level::l5()) {
-        return;
-    }
-
-    // -----
-
     let (mut player, mut player_xform) = player.single_mut();
 
     player.can_move = true;
@@ -23,21 +19,51 @@ pub fn init(
 
     let mut lvl = LevelBuilder::new(&mut commands, &assets);
 
-    let locator =
-        LevelLoader::load(include_str!("../../assets/levels/level5.tmj"))
-            .spawn(&mut lvl);
+    let locator = LevelLoader::load(MAP)
+        .with_rhai_script(include_str!("../../assets/levels/level5.rhai"))
+        .spawn(&mut lvl);
+
+    let mut loot = None;
+
+    let stage = match save_point.take_resume().as_deref() {
+        Some("awaiting-door") => LevelStage::AwaitingGoingThroughDoor,
+
+        Some("awaiting-door:rifle") => {
+            loot = Some(spawn_loot(lvl.assets(), lvl.commands(), &locator));
+
+            LevelStage::AwaitingGoingThroughDoor
+        }
+
+        Some("awaiting-leaving") => LevelStage::AwaitingLeaving {
+            light: spawn_exit_light(lvl.assets(), lvl.commands(), &locator),
+        },
+
+        _ => LevelStage::Intro,
+    };
+
+    if !matches!(stage, LevelStage::Intro) {
+        // All the waves are over, and so are their torches
+        for (_, torch_entity) in locator.torches() {
+            lvl.commands().entity(torch_entity).remove::<TorchActive>();
+        }
+    }
 
     // -----
 
     lvl.complete(LevelState {
         locator,
-        stage: LevelStage::Intro,
+        loot,
+        stage,
     });
 }
 
 #[derive(Component)]
 pub struct LevelState {
     locator: LevelLocator,
+
+    /// Rifle dropped after the first wave, if it's been dropped already
+    loot: Option<Entity>,
+
     stage: LevelStage,
 }
 
@@ -52,11 +78,41 @@ enum LevelStage {
     AwaitingOutro { timer: Timer },
 }
 
+impl LevelStage {
+    /// See: [`LevelRhaiScript::set_stage()`].
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Intro => "intro",
+            Self::SpawningWave { .. } => "spawning-wave",
+            Self::SpawningWaveEnemies { .. } => "spawning-wave-enemies",
+            Self::AwaitingWaveCompletion { .. } => "awaiting-wave-completion",
+            Self::PostWaveCooldown { .. } => "post-wave-cooldown",
+            Self::AwaitingGoingThroughDoor => "awaiting-door",
+            Self::AwaitingLeaving { .. } => "awaiting-leaving",
+            Self::AwaitingOutro { .. } => "awaiting-outro",
+        }
+    }
+
+    /// See: [`SavePoint`].
+    fn save_point(&self, is_loot_lying: bool) -> Option<String> {
+        match self {
+            Self::AwaitingGoingThroughDoor if is_loot_lying => {
+                Some("awaiting-door:rifle".into())
+            }
+            Self::AwaitingGoingThroughDoor => Some("awaiting-door".into()),
+            Self::AwaitingLeaving { .. } => Some("awaiting-leaving".into()),
+            _ => None,
+        }
+    }
+}
+
 pub fn process(
     mut commands: Commands,
     time: Res<Time>,
     assets: Res<Assets>,
+    mut save_point: ResMut<SavePoint>,
     mut level: Query<&mut LevelState>,
+    mut script: Query<&mut LevelRhaiScript>,
     mut typewriter_tx: EventWriter<TypewriterPrint>,
     mut sync_nav_data_tx: EventWriter<SyncNavData>,
     mut command_tx: EventWriter<Command>,
@@ -69,6 +125,16 @@ pub fn process(
     let level = &mut *level;
     let dt = time.delta();
 
+    let is_loot_lying = level
+        .loot
+        .map_or(false, |loot| commands.get_entity(loot).is_some());
+
+    save_point.set(level.stage.save_point(is_loot_lying));
+
+    if let Ok(mut script) = script.get_single_mut() {
+        script.set_stage(level.stage.name());
+    }
+
     match &mut level.stage {
         LevelStage::Intro => {
             typewriter_tx.send(TypewriterPrint::new(
@@ -141,9 +207,11 @@ pub fn process(
                          just give me a second",
                     ));
 
-                    Picker::rifle()
-                        .with_position(level.locator.tag("wave1.loot").xz())
-                        .spawn(&assets, &mut commands);
+                    level.loot = Some(spawn_loot(
+                        &assets,
+                        &mut commands,
+                        &level.locator,
+                    ));
 
                     level.stage = LevelStage::PostWaveCooldown {
                         cooldown: Timer::new(
@@ -216,15 +284,11 @@ pub fn process(
 
                                 inventory.single_mut().has_flashlight = false;
 
-                                let light =
-                                    LevelBuilder::new(&mut commands, &assets)
-                                        .point_light(
-                                            level.locator.tag("exit-light")
-                                                + vec3(0.0, 1.5, 0.0),
-                                            Color::hex(0xffffff),
-                                            1.0,
-                                        )
-                                        .id();
+                                let light = spawn_exit_light(
+                                    &assets,
+                                    &mut commands,
+                                    &level.locator,
+                                );
 
                                 level.stage =
                                     LevelStage::AwaitingLeaving { light };
@@ -236,40 +300,6 @@ pub fn process(
                         }
                     }
 
-                    LevelGameplayEvent::KeyPicked(name) => {
-                        let inventory = inventory.single();
-
-                        if name == "2" || name == "3" {
-                            match inventory.keys.len() {
-                                2 => {
-                                    typewriter_tx.send(TypewriterPrint::new(
-                                        "gotcha!! yes... YES......",
-                                    ));
-                                }
-
-                                3 => {
-                                    typewriter_tx.send(TypewriterPrint::new(
-                                        "oh well, come see me....",
-                                    ));
-                                }
-
-                                _ => {
-                                    //
-                                }
-                            }
-
-                            MothMonster::spawn(
-                                &assets,
-                                &mut commands,
-                                level.locator.tag(if name == "2" {
-                                    "room-a.spawn"
-                                } else {
-                                    "room-b.spawn"
-                                }),
-                            );
-                        }
-                    }
-
                     _ => {
                         //
                     }
@@ -313,8 +343,32 @@ pub fn process(
             timer.tick(dt);
 
             if timer.just_finished() {
-                goto_level_tx.send(GotoLevel::new(Level::l6()));
+                goto_level_tx.send(GotoLevel::new("level6"));
             }
         }
     }
 }
+
+fn spawn_loot(
+    assets: &Assets,
+    commands: &mut Commands,
+    locator: &LevelLocator,
+) -> Entity {
+    Picker::rifle()
+        .with_position(locator.tag("wave1.loot").xz())
+        .spawn(assets, commands)
+}
+
+fn spawn_exit_light(
+    assets: &Assets,
+    commands: &mut Commands,
+    locator: &LevelLocator,
+) -> Entity {
+    LevelBuilder::new(commands, assets)
+        .point_light(
+            locator.tag("exit-light") + vec3(0.0, 1.5, 0.0),
+            Color::hex(0xffffff),
+            1.0,
+        )
+        .id()
+}
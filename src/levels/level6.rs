@@ -5,18 +5,14 @@ use doome_bevy::convert::{graphical_to_physical, physical_to_graphical};
 
 use crate::prelude::*;
 
+pub const MAP: &str = include_str!("../../assets/levels/level6.tmj");
+
 pub fn init(
     mut commands: Commands,
     assets: Res<Assets>,
-    mut goto_level_rx: EventReader<GotoLevel>,
+    mut save_point: ResMut<SavePoint>,
     mut player: Query<(&mut Player, &mut Transform)>,
 ) {
-    if !goto_level_rx.iter().any(|level| **level == Level::l6()) {
-        return;
-    }
-
-    // -----
-
     let (mut player, mut player_xform) = player.single_mut();
 
     player.can_move = true;
@@ -26,9 +22,7 @@ pub fn init(
 
     let mut lvl = LevelBuilder::new(&mut commands, &assets);
 
-    let locator =
-        LevelLoader::load(include_str!("../../assets/levels/level6.tmj"))
-            .spawn(&mut lvl);
+    let locator = LevelLoader::load(MAP).spawn(&mut lvl);
 
     lvl.point_light(
         locator.tag("light-1") + vec3(0.0, 1.8, 0.0),
@@ -37,12 +31,14 @@ pub fn init(
     )
     .insert(Fade::fade_in(6.0));
 
+    let stage = match save_point.take_resume().as_deref() {
+        Some("awaiting-outside") => LevelStage::AwaitingPlayerToGoOutside,
+        _ => LevelStage::Intro,
+    };
+
     // -----
 
-    lvl.complete(LevelState {
-        locator,
-        stage: LevelStage::Intro,
-    });
+    lvl.complete(LevelState { locator, stage });
 }
 
 #[derive(Component)]
@@ -93,12 +89,24 @@ enum LevelStage {
     Outro {
         texts: Vec<Entity>,
     },
+    Completed,
+}
+
+impl LevelStage {
+    /// See: [`SavePoint`].
+    fn save_point(&self) -> Option<String> {
+        match self {
+            Self::AwaitingPlayerToGoOutside => Some("awaiting-outside".into()),
+            _ => None,
+        }
+    }
 }
 
 pub fn process(
     mut commands: Commands,
     time: Res<Time>,
     assets: Res<Assets>,
+    mut save_point: ResMut<SavePoint>,
     mut level: Query<&mut LevelState>,
     mut level_rx: EventReader<LevelGameplayEvent>,
     mut typewriter_tx: EventWriter<TypewriterPrint>,
@@ -110,11 +118,14 @@ pub fn process(
     mut inventory: Query<&mut Inventory>,
     mut change_hud_visibility_tx: EventWriter<ChangeHudVisibility>,
     mut transforms: Query<&mut Transform, Without<Player>>,
+    mut goto_level_tx: EventWriter<GotoLevel>,
 ) {
     let Ok(mut level) = level.get_single_mut() else { return };
     let level = &mut *level;
     let dt = time.delta();
 
+    save_point.set(level.stage.save_point());
+
     match &mut level.stage {
         LevelStage::Intro => {
             typewriter_tx.send(TypewriterPrint::new(
@@ -552,10 +563,30 @@ pub fn process(
         }
 
         LevelStage::Outro { texts } => {
-            for text in texts {
+            for text in texts.iter() {
                 transforms.get_mut(*text).unwrap().translation.y -=
                     time.delta_seconds() / 8.5;
             }
+
+            let last_text = *texts.last().unwrap();
+
+            if transforms.get(last_text).unwrap().translation.y > -0.1 {
+                return;
+            }
+
+            // Once the credits are gone, let the player continue in the
+            // survival mode
+            for text in texts.drain(..) {
+                commands.entity(text).despawn();
+            }
+
+            goto_level_tx.send(GotoLevel::new("survival"));
+
+            level.stage = LevelStage::Completed;
+        }
+
+        LevelStage::Completed => {
+            //
         }
     }
 }
@@ -1,47 +1,86 @@
-mod geometrized;
-mod indexed;
+mod features;
 mod locator;
-mod map;
-mod tileset;
 
-use std::collections::{BTreeSet, HashMap};
-use std::{cmp, fmt};
+use std::collections::HashMap;
 
-use serde::Deserialize;
+use doome_levels::{indexed, map, tileset};
 
-pub use self::locator::LevelLocator;
+pub use self::locator::{LevelLocator, MapObject};
 use super::builder::LevelBuilder;
 use crate::prelude::*;
 
 pub struct LevelLoader {
-    tmj_data: &'static str,
+    map: map::Map,
+    script_data: Option<&'static str>,
+    rhai_script_data: Option<&'static str>,
 }
 
 impl LevelLoader {
     pub fn load(tmj_data: &'static str) -> Self {
-        Self { tmj_data }
+        log::info!("Loading map");
+
+        Self::from_map(map::Map::from_tmj(tmj_data))
     }
 
-    pub fn spawn(self, lvl: &mut LevelBuilder) -> LevelLocator {
-        log::info!("Loading tileset");
+    /// Loads map built in code, e.g. by [`doome_levels::generator`].
+    pub fn from_map(map: map::Map) -> Self {
+        Self {
+            map,
+            script_data: None,
+            rhai_script_data: None,
+        }
+    }
 
-        let tileset = tileset::Tileset::from_tsx(include_str!(
+    pub fn tileset() -> tileset::Tileset {
+        tileset::Tileset::from_tsx(include_str!(
             "../../assets/levels/tileset.tsx"
-        ));
+        ))
+    }
 
-        log::info!("Loading map");
-        let map = map::Map::from_tmj(self.tmj_data);
+    pub fn with_script(mut self, script_data: &'static str) -> Self {
+        self.script_data = Some(script_data);
+        self
+    }
+
+    pub fn with_rhai_script(mut self, rhai_script_data: &'static str) -> Self {
+        self.rhai_script_data = Some(rhai_script_data);
+        self
+    }
+
+    pub fn spawn(self, lvl: &mut LevelBuilder) -> LevelLocator {
+        log::info!("Loading tileset");
+        let tileset = Self::tileset();
 
         log::debug!("Indexing map");
-        let (imap, mut locator) = map.index(&tileset);
+        let imap = self.map.index(&tileset);
 
         log::debug!("Geometrizing map");
         let gmap = imap.geometrize();
 
         log::debug!("Spawning map");
-        gmap.spawn(lvl);
+        features::spawn(gmap, lvl);
+
+        let mut locator = LevelLocator::default();
         locator.spawn(&imap, lvl);
 
+        if let Some(script_data) = self.script_data {
+            log::debug!("Loading script");
+
+            let script = LevelScript::from_json(script_data);
+
+            lvl.commands().spawn((
+                LevelScriptState::new(script, locator.clone()),
+                GcAfterLevelUnloaded,
+            ));
+        }
+
+        if let Some(rhai_script_data) = self.rhai_script_data {
+            lvl.commands().spawn((
+                LevelRhaiScript::new(rhai_script_data, &locator),
+                GcAfterLevelUnloaded,
+            ));
+        }
+
         log::debug!("Completed");
 
         locator
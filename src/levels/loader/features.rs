@@ -0,0 +1,137 @@
+use doome_levels::geometrized::{Feature, Map};
+
+use super::*;
+
+pub(super) fn spawn(map: Map<'_>, lvl: &mut LevelBuilder) {
+    for feature in map.into_features() {
+        spawn_feature(feature, lvl);
+    }
+}
+
+fn spawn_feature(feature: Feature<'_>, lvl: &mut LevelBuilder) {
+    match feature {
+        Feature::Ceiling {
+            x1,
+            y1,
+            x2,
+            y2,
+            height,
+            tex,
+        } => {
+            let tex_handle = lvl.assets().load_texture(tex);
+
+            lvl.ceiling_at(x1, y1, x2, y2, height)
+                .alter_material(|mat| mat.with_texture(tex_handle))
+                .spawn();
+        }
+
+        Feature::Floor {
+            x1,
+            y1,
+            x2,
+            y2,
+            height,
+            tex,
+        } => {
+            assert!(x1 <= x2);
+            assert!(y1 <= y2);
+
+            let tex_handle = lvl.assets().load_texture(tex);
+
+            lvl.floor_at(x1, y1, x2, y2, height)
+                .alter_material(|mat| {
+                    let mat = mat.with_texture(tex_handle);
+
+                    match tex {
+                        "floor.stone.mossy.water" => mat
+                            .with_reflectivity(0.1)
+                            .with_reflection_color(Color::hex(0xffffff)),
+
+                        "floor.checkerboard" => mat
+                            .with_reflectivity(0.8)
+                            .with_reflection_color(Color::hex(0xffffff)),
+
+                        _ => mat,
+                    }
+                })
+                .spawn();
+        }
+
+        Feature::Wall {
+            x1,
+            y1,
+            x2,
+            y2,
+            rot,
+            bottom,
+            top,
+            tex,
+        } => {
+            assert!(x1 <= x2);
+            assert!(y1 <= y2);
+            assert!(rot <= 3);
+
+            let tex_handle = lvl.assets().load_texture(tex);
+
+            lvl.wall_at(x1, y1, x2, y2, rot, bottom, top)
+                .alter_material(|mat| {
+                    wall_material(mat.with_texture(tex_handle), tex)
+                })
+                .spawn();
+        }
+
+        Feature::Step {
+            x1,
+            y1,
+            x2,
+            y2,
+            rot,
+            bottom,
+            top,
+            tex,
+            is_solid,
+        } => {
+            assert!(x1 <= x2);
+            assert!(y1 <= y2);
+            assert!(rot <= 3);
+
+            let tex_handle = lvl.assets().load_texture(tex);
+
+            let step = lvl
+                .wall_at(x1, y1, x2, y2, rot, bottom, top)
+                .alter_material(|mat| mat.with_texture(tex_handle));
+
+            if is_solid {
+                step.spawn();
+            } else {
+                step.without_collider().spawn();
+            }
+        }
+
+        Feature::Segment {
+            start,
+            end,
+            bottom,
+            top,
+            tex,
+        } => {
+            let tex_handle = lvl.assets().load_texture(tex);
+
+            lvl.wall_segment(start, end, bottom, top)
+                .alter_material(|mat| {
+                    wall_material(mat.with_texture(tex_handle), tex)
+                })
+                .spawn();
+        }
+    }
+}
+
+fn wall_material(mat: Material, tex: &str) -> Material {
+    match tex {
+        "wall.marble" => mat
+            .with_reflectivity(0.8)
+            .with_reflection_color(Color::hex(0xffffff)),
+
+        _ => mat,
+    }
+}
@@ -1,26 +1,24 @@
 use std::f32::consts::PI;
 
+use doome_levels::indexed::{Object, Shape};
+use doome_levels::CEILING_HEIGHT;
 use itertools::Itertools;
 
 use super::*;
 
 #[derive(Clone, Debug, Default)]
 pub struct LevelLocator {
-    objects: HashMap<String, Vec<Object>>,
     doors: HashMap<String, Entity>,
+    enemies: Vec<Entity>,
+    enemy_groups: HashMap<String, Vec<Entity>>,
     keys: HashMap<String, Entity>,
+    lights: HashMap<String, Entity>,
+    movers: HashMap<String, Vec<Entity>>,
     tags: HashMap<String, Vec2>,
     torches: HashMap<String, Entity>,
 }
 
 impl LevelLocator {
-    pub(super) fn add(&mut self, name: String, x: i32, y: i32, w: i32, h: i32) {
-        self.objects
-            .entry(name)
-            .or_default()
-            .push(Object { x, y, w, h });
-    }
-
     pub(super) fn spawn(
         &mut self,
         imap: &indexed::Map<'_>,
@@ -28,22 +26,15 @@ impl LevelLocator {
     ) {
         let has_wall_at = |x, y| imap.get(x, y).any(|tile| tile.is_wall());
 
-        let objects = self
-            .objects
-            .iter()
-            .flat_map(|(k, vs)| vs.iter().map(move |v| (k, v)));
+        for obj in imap.objects() {
+            let obj_name = &obj.name;
 
-        for (obj_name, obj) in objects {
             if let Some(spec) = obj_name.strip_prefix("door:") {
                 let (name, key) = if spec.contains(",") {
                     let (name, color) =
                         spec.split(",").collect_tuple().unwrap();
 
-                    let color = color.strip_prefix("0x").unwrap();
-                    let color = u32::from_str_radix(color, 16).unwrap();
-                    let color = Color::hex(color);
-
-                    let key = Key::new(name, color);
+                    let key = Key::new(name, parse_color(color));
 
                     (name, Some(key))
                 } else {
@@ -64,10 +55,48 @@ impl LevelLocator {
                     .with_key_opt(key)
                     .spawn(lvl.assets(), lvl.commands());
 
+                lvl.commands().entity(entity).insert(MapObject::new(obj));
+
                 self.doors.insert(name.to_owned(), entity);
                 continue;
             }
 
+            if let Some(spec) = obj_name.strip_prefix("enemy:") {
+                let (kind, name) = match spec.split_once(':') {
+                    Some((kind, name)) => (kind, Some(name)),
+                    None => (spec, None),
+                };
+
+                let position = vec3(obj.x as f32, 0.0, obj.y as f32);
+
+                let entity = match kind {
+                    "moth-monster" => MothMonster::spawn(
+                        lvl.assets(),
+                        lvl.commands(),
+                        position,
+                    ),
+                    "doome" => {
+                        Doome::spawn(lvl.assets(), lvl.commands(), position)
+                    }
+                    _ => {
+                        panic!("Map contains unrecognized enemy: {}", spec);
+                    }
+                };
+
+                lvl.commands().entity(entity).insert(MapObject::new(obj));
+
+                self.enemies.push(entity);
+
+                if let Some(name) = name {
+                    self.enemy_groups
+                        .entry(name.to_owned())
+                        .or_default()
+                        .push(entity);
+                }
+
+                continue;
+            }
+
             if obj_name == "gate" {
                 let rot = if has_wall_at(obj.x - 1, obj.y) {
                     Quat::from_rotation_y(PI / 2.0)
@@ -80,10 +109,12 @@ impl LevelLocator {
             }
 
             if obj_name == "heart" {
-                Picker::heart()
+                let entity = Picker::heart()
                     .with_position(obj.position())
                     .spawn(lvl.assets(), lvl.commands());
 
+                lvl.commands().entity(entity).insert(MapObject::new(obj));
+
                 continue;
             }
 
@@ -93,18 +124,118 @@ impl LevelLocator {
                         panic!("Map contains invalid key definition: {}", spec);
                     });
 
-                let color = color.strip_prefix("0x").unwrap();
-                let color = u32::from_str_radix(color, 16).unwrap();
-                let color = Color::hex(color);
-
-                let entity = Picker::key(Key::new(name, color))
+                let entity = Picker::key(Key::new(name, parse_color(color)))
                     .with_position(obj.position())
                     .spawn(lvl.assets(), lvl.commands());
 
+                lvl.commands().entity(entity).insert(MapObject::new(obj));
+
                 self.keys.insert(name.to_owned(), entity);
                 continue;
             }
 
+            if let Some(spec) = obj_name.strip_prefix("light:") {
+                let (kind, name) = match spec.split_once(':') {
+                    Some((kind, name)) => (kind, Some(name)),
+                    None => (spec, None),
+                };
+
+                let color = obj
+                    .prop_str("color")
+                    .map(parse_color)
+                    .unwrap_or_else(|| Color::hex(0xffffff));
+
+                let intensity = obj.prop_f32("intensity").unwrap_or(1.0);
+                let height = obj.prop_f32("height").unwrap_or(1.8);
+                let position = vec3(obj.x as f32, height, obj.y as f32);
+
+                let entity = match kind {
+                    "point" => lvl.point_light(position, color, intensity).id(),
+
+                    "spot" => {
+                        let angle = obj.prop_f32("angle").unwrap_or(45.0);
+
+                        lvl.spot_light(
+                            position,
+                            position * vec3(1.0, 0.0, 1.0),
+                            angle.to_radians(),
+                            color,
+                            intensity,
+                        )
+                        .id()
+                    }
+
+                    _ => {
+                        panic!(
+                            "Map contains invalid light definition: {}",
+                            spec
+                        );
+                    }
+                };
+
+                if let Some(name) = name {
+                    if self.lights.insert(name.to_owned(), entity).is_some() {
+                        panic!(
+                            "Map contains light defined multiple times: {}",
+                            name
+                        );
+                    }
+                }
+
+                continue;
+            }
+
+            if let Some(name) = obj_name.strip_prefix("mover:") {
+                let entities = spawn_mover(obj, lvl);
+
+                if self.movers.insert(name.to_owned(), entities).is_some() {
+                    panic!(
+                        "Map contains mover defined multiple times: {}",
+                        name
+                    );
+                }
+
+                continue;
+            }
+
+            if let Some(spec) = obj_name.strip_prefix("pickup:") {
+                let picker = match spec {
+                    "flashlight" => Picker::flashlight(),
+                    "heart" => Picker::heart(),
+                    "rifle" => Picker::rifle(),
+                    "rpg" => Picker::rpg(),
+                    _ => {
+                        panic!("Map contains unrecognized pickup: {}", spec);
+                    }
+                };
+
+                let entity = picker
+                    .with_position(obj.position())
+                    .spawn(lvl.assets(), lvl.commands());
+
+                lvl.commands().entity(entity).insert(MapObject::new(obj));
+
+                continue;
+            }
+
+            if let Some(name) = obj_name.strip_prefix("secret-wall:") {
+                spawn_removable_wall(obj, name, RemovableWallKind::Secret, lvl);
+                continue;
+            }
+
+            if let Some(name) = obj_name.strip_prefix("destructible-wall:") {
+                let health = obj.prop_f32("health").unwrap_or(100.0);
+
+                spawn_removable_wall(
+                    obj,
+                    name,
+                    RemovableWallKind::Destructible { health },
+                    lvl,
+                );
+
+                continue;
+            }
+
             if let Some(name) = obj_name.strip_prefix("tag:") {
                 if self.tags.insert(name.to_owned(), obj.position()).is_some() {
                     panic!("Map contains tag defined multiple times: {}", name);
@@ -172,15 +303,77 @@ impl LevelLocator {
                 continue;
             }
 
-            if let Some(name) = obj_name.strip_prefix("zone:") {
+            if let Some(name) = obj_name.strip_prefix("checkpoint:") {
+                assert!(!name.is_empty(), "Map contains unnamed checkpoint");
+
+                // Checkpoints are just zones with a special name - see
+                // `Campaign::track_checkpoints()`
                 lvl.zone(
-                    name,
+                    obj_name,
                     obj.x as f32,
                     obj.y as f32,
-                    (obj.x + obj.w) as f32,
-                    (obj.y + obj.h) as f32,
+                    (obj.x + obj.w.max(1)) as f32,
+                    (obj.y + obj.h.max(1)) as f32,
+                );
+
+                continue;
+            }
+
+            if let Some(name) = obj_name.strip_prefix("zone:") {
+                let shape = match &obj.shape {
+                    Shape::Rect => ZoneShape::Rect {
+                        x1: obj.x as f32,
+                        z1: obj.y as f32,
+                        x2: (obj.x + obj.w) as f32,
+                        z2: (obj.y + obj.h) as f32,
+                    },
+
+                    Shape::Ellipse { center, radius } => {
+                        if (radius.x - radius.y).abs() > 0.01 {
+                            panic!(
+                                "Map contains zone shaped as ellipse (instead \
+                                 of circle): {}",
+                                name
+                            );
+                        }
+
+                        ZoneShape::Circle {
+                            center: *center,
+                            radius: radius.x,
+                        }
+                    }
+
+                    Shape::Polygon(points) => {
+                        ZoneShape::Polygon(points.clone())
+                    }
+                };
+
+                let filter = obj.prop_str("filter").map_or_else(
+                    || vec![ZoneTarget::Player],
+                    |filter| {
+                        filter
+                            .split(',')
+                            .map(|target| {
+                                target.trim().parse().unwrap_or_else(|_| {
+                                    panic!(
+                                        "Map contains zone with invalid \
+                                         filter: {}",
+                                        name
+                                    )
+                                })
+                            })
+                            .collect()
+                    },
                 );
 
+                let mut zone = LevelZone::new(name, shape).with_filter(filter);
+
+                if obj.prop_bool("stay").unwrap_or(false) {
+                    zone = zone.with_stay_events();
+                }
+
+                lvl.custom_zone(zone);
+
                 continue;
             }
 
@@ -197,6 +390,39 @@ impl LevelLocator {
             .unwrap_or_else(|| panic!("Map contains no door called `{}`", name))
     }
 
+    pub fn enemies(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.enemies.iter().copied()
+    }
+
+    /// Returns enemies spawned from objects called `enemy:<kind>:<name>`;
+    /// many objects can share the same name to form a group.
+    pub fn enemy_group(&self, name: impl AsRef<str>) -> &[Entity] {
+        let name = name.as_ref();
+
+        self.enemy_groups
+            .get(name)
+            .map(Vec::as_slice)
+            .unwrap_or_else(|| {
+                panic!("Map contains no enemy group called `{}`", name)
+            })
+    }
+
+    /// Resolves reference such as `door:name`, `enemy:name`, `key:name`,
+    /// `light:name`, `mover:name` or `torch:name` into entities it points at.
+    pub fn entities(&self, reference: &str) -> Option<Vec<Entity>> {
+        let (kind, name) = reference.split_once(':')?;
+
+        match kind {
+            "door" => self.doors.get(name).map(|entity| vec![*entity]),
+            "enemy" => self.enemy_groups.get(name).cloned(),
+            "key" => self.keys.get(name).map(|entity| vec![*entity]),
+            "light" => self.lights.get(name).map(|entity| vec![*entity]),
+            "mover" => self.movers.get(name).cloned(),
+            "torch" => self.torches.get(name).map(|entity| vec![*entity]),
+            _ => None,
+        }
+    }
+
     pub fn key(&self, name: impl AsRef<str>) -> Entity {
         let name = name.as_ref();
 
@@ -206,6 +432,24 @@ impl LevelLocator {
             .unwrap_or_else(|| panic!("Map contains no key called `{}`", name))
     }
 
+    pub fn light(&self, name: impl AsRef<str>) -> Entity {
+        let name = name.as_ref();
+
+        self.lights.get(name).cloned().unwrap_or_else(|| {
+            panic!("Map contains no light called `{}`", name)
+        })
+    }
+
+    /// Returns entities spawned from object called `mover:<name>` - they all
+    /// have the same [`Mover`], so they should be started and stopped together.
+    pub fn mover(&self, name: impl AsRef<str>) -> &[Entity] {
+        let name = name.as_ref();
+
+        self.movers.get(name).map(Vec::as_slice).unwrap_or_else(|| {
+            panic!("Map contains no mover called `{}`", name)
+        })
+    }
+
     pub fn tag(&self, name: impl AsRef<str>) -> Vec3 {
         let name = name.as_ref();
 
@@ -238,16 +482,203 @@ impl LevelLocator {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
-struct Object {
-    x: i32,
-    y: i32,
-    w: i32,
-    h: i32,
+/// Identifies an entity spawned from map's object, so that its state can be
+/// saved and later restored after the map gets reloaded.
+#[derive(Component, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MapObject(String);
+
+impl MapObject {
+    fn new(obj: &Object) -> Self {
+        Self(format!("{}@{},{}", obj.name, obj.x, obj.y))
+    }
+
+    pub fn id(&self) -> &str {
+        &self.0
+    }
+
+    pub fn is_door(&self) -> bool {
+        self.0.starts_with("door:")
+    }
+
+    pub fn is_enemy(&self) -> bool {
+        self.0.starts_with("enemy:")
+    }
+}
+
+/// Spawns object called `mover:<name>`, which is either a block (by default)
+/// that pushes whatever is on its way, or a platform that carries whoever
+/// stands on it.
+///
+/// Mover's `path` is a list of keyframes such as `0,2,0@1.5; pause@1`, where
+/// each keyframe is a translation relative to object's position (with the y
+/// axis pointing up, optionally followed by an angle in degrees) and the time
+/// it takes to get there.
+fn spawn_mover(obj: &Object, lvl: &mut LevelBuilder) -> Vec<Entity> {
+    let invalid = |what: &str| -> ! {
+        panic!("Map contains mover with invalid {}: {}", what, obj.name);
+    };
+
+    let mut mover = obj
+        .prop_str("path")
+        .and_then(parse_mover_path)
+        .unwrap_or_else(|| invalid("path"));
+
+    if let Some(mode) = obj.prop_str("mode") {
+        mover =
+            mover.with_mode(mode.parse().unwrap_or_else(|_| invalid("mode")));
+    }
+
+    if let Some(easing) = obj.prop_str("easing") {
+        mover = mover
+            .with_easing(easing.parse().unwrap_or_else(|_| invalid("easing")));
+    }
+
+    mover = mover.with_active(obj.prop_bool("active").unwrap_or(true));
+
+    let (x1, z1) = (obj.x, obj.y);
+    let (x2, z2) = (obj.x + obj.w.max(1) - 1, obj.y + obj.h.max(1) - 1);
+
+    let tex = lvl
+        .assets()
+        .load_texture(obj.prop_str("texture").unwrap_or("wall.stone"));
+
+    let center = vec2((x1 + x2) as f32, (z1 + z2) as f32) / 2.0;
+
+    match obj.prop_str("kind").unwrap_or("block") {
+        "block" => {
+            let bottom = obj.prop_f32("bottom").unwrap_or(0.0);
+            let top = obj.prop_f32("top").unwrap_or(CEILING_HEIGHT);
+            let mover = mover.with_pivot(vec3(center.x, bottom, center.y));
+
+            let entities = lvl.block(x1, z1, x2, z2, bottom, top, |model| {
+                model
+                    .without_obstacle()
+                    .dynamic()
+                    .alter_material(|mat| mat.with_texture(tex))
+            });
+
+            for entity in &entities {
+                lvl.commands().entity(*entity).insert(mover.clone());
+            }
+
+            entities
+        }
+
+        "platform" => {
+            let height = obj.prop_f32("height").unwrap_or(0.0);
+
+            let entity = lvl
+                .floor_at(x1, z1, x2, z2, height)
+                .dynamic()
+                .alter_material(|mat| mat.with_texture(tex))
+                .with_collider(
+                    Collider::rect(1.0, 1.0)
+                        .detector()
+                        .with_layers(CollisionLayers::TRIGGER),
+                )
+                .spawn()
+                .insert(mover)
+                .id();
+
+            vec![entity]
+        }
+
+        _ => invalid("kind"),
+    }
+}
+
+/// Spawns object called `secret-wall:<name>` or `destructible-wall:<name>`.
+fn spawn_removable_wall(
+    obj: &Object,
+    name: &str,
+    kind: RemovableWallKind,
+    lvl: &mut LevelBuilder,
+) {
+    assert!(!name.is_empty(), "Map contains unnamed wall");
+
+    let (x1, z1) = (obj.x, obj.y);
+    let (x2, z2) = (obj.x + obj.w.max(1) - 1, obj.y + obj.h.max(1) - 1);
+
+    let tex = lvl
+        .assets()
+        .load_texture(obj.prop_str("texture").unwrap_or("wall.stone"));
+
+    // Secret walls fade out once opened, so they have to be dynamic
+    let is_dynamic = kind == RemovableWallKind::Secret;
+
+    let parts = lvl.block(x1, z1, x2, z2, 0.0, CEILING_HEIGHT, |model| {
+        // (without the floor as well, since otherwise the wall's top would
+        // keep blocking the passage while the wall fades out)
+        let model = model
+            .without_collider()
+            .without_floor()
+            .alter_material(|mat| mat.with_texture(tex));
+
+        if is_dynamic {
+            model.dynamic()
+        } else {
+            model
+        }
+    });
+
+    let center = vec2((x1 + x2) as f32, (z1 + z2) as f32) / 2.0;
+    let half_extents = vec2((x2 - x1 + 1) as f32, (z2 - z1 + 1) as f32) / 2.0;
+
+    RemovableWall::new(name, kind).with_parts(parts).spawn(
+        lvl.commands(),
+        center,
+        half_extents,
+    );
 }
 
-impl Object {
-    fn position(self) -> Vec2 {
-        vec2(self.x as f32, self.y as f32)
+fn parse_mover_path(path: &str) -> Option<Mover> {
+    let mut mover = Mover::new();
+
+    for keyframe in path.split(';') {
+        let (pose, duration) = keyframe.trim().split_once('@')?;
+
+        let duration = duration
+            .trim()
+            .parse::<f32>()
+            .ok()
+            .filter(|duration| *duration >= 0.0)?;
+
+        if pose.trim() == "pause" {
+            mover = mover.with_pause(duration);
+            continue;
+        }
+
+        let coords = pose
+            .split(',')
+            .map(|coord| coord.trim().parse::<f32>().ok())
+            .collect::<Option<Vec<_>>>()?;
+
+        mover = match coords[..] {
+            [x, y, z] => mover.with_waypoint(vec3(x, y, z), duration),
+
+            [x, y, z, angle] => mover.with_keyframe(Keyframe {
+                translation: vec3(x, y, z),
+                angle: angle.to_radians(),
+                duration,
+            }),
+
+            _ => return None,
+        };
     }
+
+    Some(mover)
+}
+
+/// Parses color in either our `0xrrggbb` notation or Tiled's `#aarrggbb`
+/// notation (alpha is ignored).
+fn parse_color(color: &str) -> Color {
+    let hex = color
+        .strip_prefix("0x")
+        .or_else(|| color.strip_prefix('#'))
+        .unwrap_or_else(|| panic!("Map contains invalid color: {}", color));
+
+    let hex = u32::from_str_radix(hex, 16)
+        .unwrap_or_else(|_| panic!("Map contains invalid color: {}", color));
+
+    Color::hex(hex)
 }
@@ -0,0 +1,112 @@
+use crate::prelude::*;
+
+/// All levels known to the game, in the order they've been registered in.
+///
+/// Levels get registered through [`AppLevelsExt::add_level()`].
+#[derive(Resource, Default)]
+pub struct LevelRegistry {
+    levels: Vec<LevelDef>,
+}
+
+pub struct LevelDef {
+    pub name: &'static str,
+
+    /// Tiled map the level is loaded from, if the level is built by hand
+    /// instead.
+    pub map: Option<&'static str>,
+}
+
+impl LevelRegistry {
+    pub const GAME_OVER: &'static str = "game-over";
+
+    pub fn resolve(&self, level: &LevelRef) -> Option<Level> {
+        match level {
+            LevelRef::Id(id) => (*id < self.levels.len()).then_some(Level(*id)),
+
+            LevelRef::Name(name) => self
+                .levels
+                .iter()
+                .position(|level| level.name == name)
+                .map(Level),
+        }
+    }
+
+    pub fn get(&self, level: Level) -> &LevelDef {
+        &self.levels[level.0]
+    }
+
+    pub fn name(&self, level: Level) -> &'static str {
+        self.get(level).name
+    }
+
+    pub fn game_over(&self) -> Level {
+        self.resolve(&Self::GAME_OVER.into())
+            .expect("Game-over level has not been registered")
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Level, &LevelDef)> {
+        self.levels
+            .iter()
+            .enumerate()
+            .map(|(id, level)| (Level(id), level))
+    }
+
+    fn register(
+        &mut self,
+        name: &'static str,
+        map: Option<&'static str>,
+    ) -> Level {
+        assert!(
+            self.levels.iter().all(|level| level.name != name),
+            "Level `{}` has been already registered",
+            name
+        );
+
+        self.levels.push(LevelDef { name, map });
+
+        Level(self.levels.len() - 1)
+    }
+}
+
+pub trait AppLevelsExt {
+    /// Registers a level; `init` gets run only in the frame the level is
+    /// being loaded, while `process` runs always (and it's up to the system
+    /// to check whether its level is active).
+    fn add_level<InitParams, ProcessParams>(
+        &mut self,
+        name: &'static str,
+        map: Option<&'static str>,
+        init: impl IntoSystemDescriptor<InitParams>,
+        process: impl IntoSystemDescriptor<ProcessParams>,
+    ) -> &mut Self;
+}
+
+impl AppLevelsExt for App {
+    fn add_level<InitParams, ProcessParams>(
+        &mut self,
+        name: &'static str,
+        map: Option<&'static str>,
+        init: impl IntoSystemDescriptor<InitParams>,
+        process: impl IntoSystemDescriptor<ProcessParams>,
+    ) -> &mut Self {
+        let level = self
+            .world
+            .get_resource_or_insert_with(LevelRegistry::default)
+            .register(name, map);
+
+        let is_loading = move |coordinator: Res<LevelsCoordinator>| {
+            if coordinator.loading_level == Some(level) {
+                ShouldRun::Yes
+            } else {
+                ShouldRun::No
+            }
+        };
+
+        self.add_system_to_stage(
+            CoreStage::PreUpdate,
+            init.with_run_criteria(is_loading)
+                .after(LevelsCoordinator::unload),
+        )
+        .add_system(process)
+    }
+}
@@ -0,0 +1,46 @@
+use crate::prelude::*;
+
+/// Stage of the current level that the game can be saved at.
+///
+/// Levels' stages are full of entities and timers, so instead of serializing
+/// them, each level names stages that it knows how to rebuild from scratch
+/// (e.g. `awaiting-key`, or `inside-corridor:b` when there's more to
+/// remember) and reports the current one every frame - the game can be saved
+/// only while the level is at one of them, and loading such save makes the
+/// level's init resume from there instead of starting over.
+#[derive(Resource, Default)]
+pub struct SavePoint {
+    current: Option<String>,
+    resume: Option<(String, String)>,
+}
+
+impl SavePoint {
+    /// Reports stage the current level is at, `None` meaning that the level
+    /// can't be saved now.
+    pub fn set(&mut self, stage: Option<String>) {
+        self.current = stage;
+    }
+
+    pub fn current(&self) -> Option<&str> {
+        self.current.as_deref()
+    }
+
+    /// Makes given level resume from given stage the next time it's loaded.
+    pub fn resume(&mut self, level: impl ToString, stage: impl ToString) {
+        self.resume = Some((level.to_string(), stage.to_string()));
+    }
+
+    /// Returns stage the level being loaded should resume from, if it's being
+    /// loaded from a save.
+    pub fn take_resume(&mut self) -> Option<String> {
+        self.resume.take().map(|(_, stage)| stage)
+    }
+
+    pub(super) fn begin_loading(&mut self, level: &str) {
+        self.current = None;
+
+        if self.resume.as_ref().map_or(false, |(lvl, _)| lvl != level) {
+            self.resume = None;
+        }
+    }
+}
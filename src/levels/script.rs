@@ -0,0 +1,449 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Display;
+use std::str::FromStr;
+use std::time::Duration;
+
+use serde::{Deserialize, Deserializer};
+
+use crate::music::MusicTrack;
+use crate::prelude::*;
+
+/// Declarative set of rules loaded alongside a map - each rule waits for a
+/// trigger and then executes its actions, in order.
+///
+/// Example:
+///
+/// ```json
+/// {
+///     "rules": [
+///         {
+///             "on": { "type": "zone-entered", "zone": "trap" },
+///             "do": [
+///                 { "type": "print", "text": "ha, ha!" },
+///                 { "type": "spawn", "what": "moth-monster", "at": "monster-1", "name": "moths" },
+///                 { "type": "start-timer", "timer": "outro", "secs": 5.0 }
+///             ]
+///         },
+///         {
+///             "on": { "type": "entity-died", "entity": "moths" },
+///             "do": [
+///                 { "type": "open-door", "door": "exit" }
+///             ]
+///         }
+///     ]
+/// }
+/// ```
+#[derive(Clone, Debug, Deserialize)]
+pub struct LevelScript {
+    #[serde(default)]
+    rules: Vec<Rule>,
+}
+
+impl LevelScript {
+    pub fn from_json(data: &'static str) -> Self {
+        serde_json::from_str(data).expect("Couldn't deserialize level script")
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct Rule {
+    on: Trigger,
+
+    /// When set, the rule fires each time its trigger happens instead of just
+    /// the first time.
+    #[serde(default)]
+    repeat: bool,
+
+    /// When set, the rule fires only while the level's code is at given stage
+    /// (see [`LevelScriptState::set_stage()`]).
+    #[serde(default)]
+    stage: Option<String>,
+
+    #[serde(rename = "do")]
+    actions: Vec<Action>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+enum Trigger {
+    LevelStarted,
+
+    /// Fires when the player enters the zone; with `by` set (e.g. to
+    /// `enemy`), fires when that kind of entity enters the zone instead.
+    ZoneEntered {
+        zone: String,
+        #[serde(default)]
+        by: Option<ZoneTarget>,
+    },
+    ZoneLeft {
+        zone: String,
+        #[serde(default)]
+        by: Option<ZoneTarget>,
+    },
+    DoorOpened {
+        door: String,
+    },
+    KeyPicked {
+        key: String,
+    },
+
+    /// Fires when a secret wall gets opened or a destructible one destroyed.
+    WallRemoved {
+        wall: String,
+    },
+
+    /// Fires when all entities behind given name are dead; the name refers
+    /// either to entities spawned by the script or to the map's objects (e.g.
+    /// `enemy:boss`).
+    EntityDied {
+        entity: String,
+    },
+
+    TimerElapsed {
+        timer: String,
+    },
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+enum Action {
+    Print {
+        text: String,
+    },
+
+    Spawn {
+        #[serde(deserialize_with = "from_str")]
+        what: Spawnable,
+        at: String,
+        name: Option<String>,
+    },
+
+    Despawn {
+        entity: String,
+    },
+
+    OpenDoor {
+        door: String,
+    },
+
+    /// Starts the mover or, if it has already reached its last keyframe (and
+    /// doesn't loop), sends it back - e.g. to call an elevator.
+    StartMover {
+        mover: String,
+    },
+    StopMover {
+        mover: String,
+    },
+
+    SwitchTrack {
+        #[serde(deserialize_with = "from_str")]
+        track: MusicTrack,
+    },
+
+    FadeLight {
+        light: String,
+        fade: FadeDirection,
+        #[serde(default = "default_fade_duration")]
+        duration: f32,
+    },
+
+    GotoLevel {
+        #[serde(deserialize_with = "from_str")]
+        level: LevelRef,
+    },
+
+    StartTimer {
+        timer: String,
+        secs: f32,
+    },
+
+    /// Escape hatch for anything else the console understands, e.g.
+    /// `lock-input`.
+    Command {
+        #[serde(deserialize_with = "from_str")]
+        command: Command,
+    },
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum FadeDirection {
+    In,
+    Out,
+}
+
+fn default_fade_duration() -> f32 {
+    1.0
+}
+
+fn from_str<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: Display,
+{
+    String::deserialize(deserializer)?
+        .parse()
+        .map_err(serde::de::Error::custom)
+}
+
+#[derive(Component)]
+pub struct LevelScriptState {
+    script: LevelScript,
+    locator: LevelLocator,
+    stage: &'static str,
+    started: bool,
+    fired: Vec<bool>,
+    groups: HashMap<String, Vec<Entity>>,
+    timers: HashMap<String, Timer>,
+}
+
+impl LevelScriptState {
+    pub fn new(script: LevelScript, locator: LevelLocator) -> Self {
+        let mut this = Self {
+            fired: vec![false; script.rules.len()],
+            script,
+            locator,
+            stage: "",
+            started: false,
+            groups: Default::default(),
+            timers: Default::default(),
+        };
+
+        this.track_map_entities();
+        this
+    }
+
+    /// Reports stage the level's code is at, e.g. `inside-corridor`, which
+    /// rules can then depend on.
+    pub fn set_stage(&mut self, stage: &'static str) {
+        self.stage = stage;
+    }
+
+    pub fn process(
+        time: Res<Time>,
+        mut commands: Commands,
+        assets: Res<Assets>,
+        mut scripts: Query<&mut LevelScriptState>,
+        mut level_rx: EventReader<LevelGameplayEvent>,
+        mut zone_rx: EventReader<ZoneEvent>,
+        mut death_rx: EventReader<Death>,
+        door_children: Query<&Children>,
+        mut movers: Query<&mut Mover>,
+        mut typewriter_tx: EventWriter<TypewriterPrint>,
+        mut game_commands: EventWriter<Command>,
+        mut goto_level_tx: EventWriter<GotoLevel>,
+    ) {
+        let Ok(mut this) = scripts.get_single_mut() else { return };
+
+        let this = &mut *this;
+
+        // -----
+
+        let mut triggers = VecDeque::new();
+
+        if !this.started {
+            this.started = true;
+            triggers.push_back(Trigger::LevelStarted);
+        }
+
+        for event in level_rx.iter() {
+            triggers.push_back(match event {
+                LevelGameplayEvent::DoorOpened(door) => {
+                    Trigger::DoorOpened { door: door.clone() }
+                }
+                LevelGameplayEvent::KeyPicked(key) => {
+                    Trigger::KeyPicked { key: key.clone() }
+                }
+                LevelGameplayEvent::ZoneEntered(zone) => Trigger::ZoneEntered {
+                    zone: zone.clone(),
+                    by: None,
+                },
+                LevelGameplayEvent::ZoneLeft(zone) => Trigger::ZoneLeft {
+                    zone: zone.clone(),
+                    by: None,
+                },
+                LevelGameplayEvent::WallRemoved(wall) => {
+                    Trigger::WallRemoved { wall: wall.clone() }
+                }
+            });
+        }
+
+        for event in zone_rx.iter() {
+            let zone = event.zone.clone();
+            let by = Some(event.target);
+
+            triggers.push_back(match event.kind {
+                ZoneEventKind::Enter => Trigger::ZoneEntered { zone, by },
+                ZoneEventKind::Exit => Trigger::ZoneLeft { zone, by },
+                ZoneEventKind::Stay => continue,
+            });
+        }
+
+        for Death(entity) in death_rx.iter() {
+            for (name, entities) in &mut this.groups {
+                let was_alive = !entities.is_empty();
+
+                entities.retain(|candidate| candidate != entity);
+
+                if was_alive && entities.is_empty() {
+                    triggers.push_back(Trigger::EntityDied {
+                        entity: name.to_owned(),
+                    });
+                }
+            }
+        }
+
+        for (name, timer) in &mut this.timers {
+            if timer.tick(time.delta()).just_finished() {
+                triggers.push_back(Trigger::TimerElapsed {
+                    timer: name.to_owned(),
+                });
+            }
+        }
+
+        // -----
+
+        while let Some(trigger) = triggers.pop_front() {
+            let stage = this.stage;
+
+            let actions: Vec<_> = this
+                .script
+                .rules
+                .iter()
+                .zip(&mut this.fired)
+                .filter(|(rule, fired)| {
+                    rule.on == trigger
+                        && (rule.repeat || !**fired)
+                        && rule.stage.as_deref().map_or(true, |s| s == stage)
+                })
+                .flat_map(|(rule, fired)| {
+                    *fired = true;
+                    rule.actions.iter().cloned()
+                })
+                .collect();
+
+            for action in actions {
+                log::debug!("Executing script action: {:?}", action);
+
+                match action {
+                    Action::Print { text } => {
+                        typewriter_tx.send(TypewriterPrint::new(text));
+                    }
+
+                    Action::Spawn { what, at, name } => {
+                        let entity = what.spawn(
+                            &assets,
+                            &mut commands,
+                            this.locator.tag(at),
+                        );
+
+                        if let Some(name) = name {
+                            this.groups.entry(name).or_default().push(entity);
+                        }
+                    }
+
+                    Action::Despawn { entity } => {
+                        for entity in this.resolve(&entity) {
+                            if let Some(entity) = commands.get_entity(entity) {
+                                entity.despawn_recursive();
+                            }
+                        }
+                    }
+
+                    Action::OpenDoor { door } => {
+                        let entity = this.locator.door(&door);
+
+                        Door::open(
+                            &mut commands,
+                            entity,
+                            door_children.get(entity).ok(),
+                        );
+
+                        triggers.push_back(Trigger::DoorOpened { door });
+                    }
+
+                    Action::StartMover { mover } => {
+                        for entity in this.locator.mover(&mover) {
+                            if let Ok(mut mover) = movers.get_mut(*entity) {
+                                mover.start();
+                            }
+                        }
+                    }
+
+                    Action::StopMover { mover } => {
+                        for entity in this.locator.mover(&mover) {
+                            if let Ok(mut mover) = movers.get_mut(*entity) {
+                                mover.stop();
+                            }
+                        }
+                    }
+
+                    Action::SwitchTrack { track } => {
+                        game_commands.send(Command::SwitchTrack { track });
+                    }
+
+                    Action::FadeLight {
+                        light,
+                        fade,
+                        duration,
+                    } => {
+                        let fade = match fade {
+                            FadeDirection::In => Fade::fade_in(duration),
+                            FadeDirection::Out => Fade::fade_out(duration),
+                        };
+
+                        for light in this.resolve(&light) {
+                            commands.entity(light).insert(fade);
+                        }
+                    }
+
+                    Action::GotoLevel { level } => {
+                        goto_level_tx.send(GotoLevel::new(level));
+                    }
+
+                    Action::StartTimer { timer, secs } => {
+                        this.timers.insert(
+                            timer,
+                            Timer::new(
+                                Duration::from_secs_f32(secs),
+                                TimerMode::Once,
+                            ),
+                        );
+                    }
+
+                    Action::Command { command } => {
+                        game_commands.send(command);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Makes sure `entity-died` triggers referring to the map's objects (e.g.
+    /// `enemy:boss`) are tracked the same way as entities spawned by the
+    /// script.
+    fn track_map_entities(&mut self) {
+        for rule in &self.script.rules {
+            let Trigger::EntityDied { entity } = &rule.on else { continue };
+
+            if self.groups.contains_key(entity) {
+                continue;
+            }
+
+            if let Some(entities) = self.locator.entities(entity) {
+                self.groups.insert(entity.to_owned(), entities);
+            }
+        }
+    }
+
+    fn resolve(&self, name: &str) -> Vec<Entity> {
+        self.groups
+            .get(name)
+            .cloned()
+            .or_else(|| self.locator.entities(name))
+            .unwrap_or_else(|| {
+                panic!("Level script refers to unknown entity: {}", name)
+            })
+    }
+}
@@ -0,0 +1,213 @@
+use std::collections::HashSet;
+
+use doome_levels::generator::Generator;
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
+
+use crate::prelude::*;
+
+/// Upper limit for the number of rooms, so that the generated maps don't run
+/// out of lights or dynamic geometry.
+const MAX_ROOMS: usize = 12;
+
+/// Endless mode played after the campaign - each floor is a generated map and
+/// reaching its exit moves the player to the next, slightly harder, floor.
+#[derive(Resource, Default)]
+pub struct Survival {
+    floor: usize,
+
+    /// Seed of the current floor; kept so that restarting the level (e.g.
+    /// after dying) yields the same map
+    seed: Option<u64>,
+}
+
+pub fn init(
+    mut commands: Commands,
+    assets: Res<Assets>,
+    mut rng: ResMut<RngState>,
+    mut survival: ResMut<Survival>,
+    mut save_point: ResMut<SavePoint>,
+    mut player: Query<(&mut Player, &mut Transform)>,
+) {
+    let mut visited_rooms = HashSet::default();
+
+    if let Some(stage) = save_point.take_resume() {
+        if let Some((floor, seed, rooms)) = LevelState::resume(&stage) {
+            survival.floor = floor;
+            survival.seed = Some(seed);
+            visited_rooms = rooms;
+        }
+    }
+
+    let floor = survival.floor;
+    let seed = *survival.seed.get_or_insert_with(|| rng.gen());
+
+    log::info!("Generating floor {} (seed={})", floor, seed);
+
+    let map = Generator::default()
+        .with_rooms((6 + floor).min(MAX_ROOMS))
+        .with_locked_doors(1 + floor / 2)
+        .with_enemies_per_room(1 + floor / 3)
+        .generate(&mut SmallRng::seed_from_u64(seed), &LevelLoader::tileset());
+
+    let mut lvl = LevelBuilder::new(&mut commands, &assets);
+    let locator = LevelLoader::from_map(map).spawn(&mut lvl);
+    let exit = locator.tag("exit");
+
+    lvl.point_light(exit + vec3(0.0, 1.5, 0.0), Color::hex(0x00ff00), 1.0);
+
+    let (mut player, mut player_xform) = player.single_mut();
+
+    player.can_move = true;
+    *player_xform = Transform::from_translation(locator.tag("start"));
+
+    // -----
+
+    lvl.complete(LevelState {
+        locator,
+        exit,
+        visited_rooms,
+        stage: LevelStage::Intro,
+    });
+}
+
+#[derive(Component)]
+pub struct LevelState {
+    locator: LevelLocator,
+    exit: Vec3,
+    visited_rooms: HashSet<String>,
+    stage: LevelStage,
+}
+
+impl LevelState {
+    /// See: [`SavePoint`].
+    ///
+    /// Since the map is generated, the save point remembers the floor and its
+    /// seed, as well as rooms whose enemies have been already spawned.
+    fn save_point(&self, survival: &Survival) -> Option<String> {
+        let LevelStage::Exploring = self.stage else { return None };
+
+        let mut rooms: Vec<_> =
+            self.visited_rooms.iter().map(String::as_str).collect();
+
+        rooms.sort();
+
+        Some(format!(
+            "exploring:{}:{}:{}",
+            survival.floor,
+            survival.seed?,
+            rooms.join(",")
+        ))
+    }
+
+    fn resume(save_point: &str) -> Option<(usize, u64, HashSet<String>)> {
+        let mut parts = save_point.splitn(4, ':');
+
+        if parts.next()? != "exploring" {
+            return None;
+        }
+
+        let floor = parts.next()?.parse().ok()?;
+        let seed = parts.next()?.parse().ok()?;
+
+        let rooms = parts
+            .next()?
+            .split(',')
+            .filter(|room| !room.is_empty())
+            .map(ToOwned::to_owned)
+            .collect();
+
+        Some((floor, seed, rooms))
+    }
+}
+
+enum LevelStage {
+    Intro,
+    Exploring,
+    Leaving,
+}
+
+pub fn process(
+    mut commands: Commands,
+    assets: Res<Assets>,
+    mut survival: ResMut<Survival>,
+    mut save_point: ResMut<SavePoint>,
+    mut campaign: ResMut<Campaign>,
+    mut level: Query<&mut LevelState>,
+    mut level_rx: EventReader<LevelGameplayEvent>,
+    mut typewriter_tx: EventWriter<TypewriterPrint>,
+    mut sync_nav_data_tx: EventWriter<SyncNavData>,
+    mut goto_level_tx: EventWriter<GotoLevel>,
+    player: Query<(&Transform, &Weapon), With<Player>>,
+    inventory: Query<&Inventory>,
+) {
+    let Ok(mut level) = level.get_single_mut() else { return };
+    let level = &mut *level;
+
+    save_point.set(level.save_point(&survival));
+
+    match level.stage {
+        LevelStage::Intro => {
+            sync_nav_data_tx.send(SyncNavData::default());
+
+            typewriter_tx.send(TypewriterPrint::new(format!(
+                "floor {} -- find the green light",
+                survival.floor + 1
+            )));
+
+            level.stage = LevelStage::Exploring;
+        }
+
+        LevelStage::Exploring => {
+            // Enemies are spawned once the player enters their room, so that
+            // they don't all come running at once
+            for event in level_rx.iter() {
+                let LevelGameplayEvent::ZoneEntered(room) = event else {
+                    continue;
+                };
+
+                if !room.starts_with("room-")
+                    || !level.visited_rooms.insert(room.to_owned())
+                {
+                    continue;
+                }
+
+                let spawn_prefix = format!("{}.spawn-", room);
+
+                for (tag_name, tag_position) in level.locator.tags() {
+                    if tag_name.starts_with(&spawn_prefix) {
+                        MothMonster::spawn(
+                            &assets,
+                            &mut commands,
+                            tag_position,
+                        );
+                    }
+                }
+            }
+
+            let Ok((player_xform, weapon)) = player.get_single() else {
+                return;
+            };
+
+            let Ok(inventory) = inventory.get_single() else { return };
+
+            if player_xform.translation.xz().distance(level.exit.xz()) > 0.75 {
+                return;
+            }
+
+            // Carry whatever the player has found so far onto the next floor
+            campaign.set_loadout(Loadout::capture(weapon, inventory));
+
+            survival.floor += 1;
+            survival.seed = None;
+
+            goto_level_tx.send(GotoLevel::new("survival"));
+
+            level.stage = LevelStage::Leaving;
+        }
+
+        LevelStage::Leaving => {
+            //
+        }
+    }
+}
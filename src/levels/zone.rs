@@ -1,20 +1,391 @@
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+use anyhow::anyhow;
+use serde::Deserialize;
+
 use crate::prelude::*;
 
+/// Area of the level that tracks entities going through it, e.g. to trigger a
+/// trap when the player enters a room.
+///
+/// By default zones track just the player, but they can also track enemies
+/// and bullets - see [`Self::with_filter()`].
 #[derive(Component)]
 pub struct LevelZone {
     pub name: String,
-    pub x1: f32,
-    pub z1: f32,
-    pub x2: f32,
-    pub z2: f32,
-    pub contains_player: bool,
+    pub shape: ZoneShape,
+    pub filter: Vec<ZoneTarget>,
+
+    /// Whether [`ZoneEventKind::Stay`] should be sent for this zone; off by
+    /// default, since otherwise each zone would send an event per occupant per
+    /// frame
+    pub stay_events: bool,
+
+    /// Entities that were inside the zone during the previous frame
+    occupants: HashMap<Entity, ZoneTarget>,
 }
 
 impl LevelZone {
+    pub fn new(name: impl ToString, shape: ZoneShape) -> Self {
+        Self {
+            name: name.to_string(),
+            shape,
+            filter: vec![ZoneTarget::Player],
+            stay_events: false,
+            occupants: Default::default(),
+        }
+    }
+
+    pub fn with_filter(mut self, filter: Vec<ZoneTarget>) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    pub fn with_stay_events(mut self) -> Self {
+        self.stay_events = true;
+        self
+    }
+
     pub fn contains(&self, obj: &Transform) -> bool {
-        let obj = obj.translation;
+        self.shape.contains(obj.translation.xz())
+    }
+
+    /// Sends [`ZoneEvent`]s for entities of given kind; zones that don't track
+    /// this kind of entities are skipped.
+    ///
+    /// For the player, [`LevelGameplayEvent::ZoneEntered`] and
+    /// [`LevelGameplayEvent::ZoneLeft`] are sent as well, so that levels and
+    /// scripts can react to them; everything else consumes [`ZoneEvent`]s.
+    pub fn track<T>(
+        entities: Query<(Entity, &Transform), With<T>>,
+        mut zones: Query<&mut LevelZone>,
+        mut zone_tx: EventWriter<ZoneEvent>,
+        mut level_tx: EventWriter<LevelGameplayEvent>,
+    ) where
+        T: ZoneTracked,
+    {
+        for mut zone in zones.iter_mut() {
+            let zone = &mut *zone;
+
+            if !zone.filter.contains(&T::TARGET) {
+                continue;
+            }
+
+            let inside: HashSet<_> = entities
+                .iter()
+                .filter(|(_, xform)| zone.contains(xform))
+                .map(|(entity, _)| entity)
+                .collect();
+
+            // Entities that went away or have been despawned
+            let left: Vec<_> = zone
+                .occupants
+                .iter()
+                .filter(|(entity, target)| {
+                    **target == T::TARGET && !inside.contains(entity)
+                })
+                .map(|(entity, _)| *entity)
+                .collect();
+
+            for entity in left {
+                log::trace!("Zone left: {} ({:?})", zone.name, entity);
+
+                zone.occupants.remove(&entity);
+
+                zone_tx.send(ZoneEvent {
+                    zone: zone.name.clone(),
+                    entity,
+                    target: T::TARGET,
+                    kind: ZoneEventKind::Exit,
+                });
+
+                if T::TARGET == ZoneTarget::Player {
+                    level_tx
+                        .send(LevelGameplayEvent::ZoneLeft(zone.name.clone()));
+                }
+            }
+
+            for entity in inside {
+                let kind = if zone.occupants.insert(entity, T::TARGET).is_none()
+                {
+                    log::trace!("Zone entered: {} ({:?})", zone.name, entity);
+
+                    if T::TARGET == ZoneTarget::Player {
+                        level_tx.send(LevelGameplayEvent::ZoneEntered(
+                            zone.name.clone(),
+                        ));
+                    }
+
+                    ZoneEventKind::Enter
+                } else if zone.stay_events {
+                    ZoneEventKind::Stay
+                } else {
+                    continue;
+                };
+
+                zone_tx.send(ZoneEvent {
+                    zone: zone.name.clone(),
+                    entity,
+                    target: T::TARGET,
+                    kind,
+                });
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ZoneShape {
+    Rect {
+        x1: f32,
+        z1: f32,
+        x2: f32,
+        z2: f32,
+    },
+    Circle {
+        center: Vec2,
+        radius: f32,
+    },
+
+    /// Arbitrary (i.e. not necessarily convex) polygon
+    Polygon(Vec<Vec2>),
+}
+
+impl ZoneShape {
+    /// Checks whether given point (on the `xz` plane) lies inside the shape.
+    pub fn contains(&self, point: Vec2) -> bool {
+        match self {
+            ZoneShape::Rect { x1, z1, x2, z2 } => {
+                (*x1..*x2).contains(&point.x) && (*z1..*z2).contains(&point.y)
+            }
+
+            ZoneShape::Circle { center, radius } => {
+                center.distance_squared(point) < radius * radius
+            }
+
+            ZoneShape::Polygon(points) => {
+                // Even-odd rule - we cast a ray towards +x and count how many
+                // edges it crosses
+                let mut inside = false;
+
+                for (idx, a) in points.iter().enumerate() {
+                    let b = points[(idx + 1) % points.len()];
+
+                    if (a.y > point.y) != (b.y > point.y) {
+                        let x =
+                            a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x);
+
+                        if point.x < x {
+                            inside = !inside;
+                        }
+                    }
+                }
+
+                inside
+            }
+        }
+    }
+}
+
+/// Kind of entities tracked by a zone.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ZoneTarget {
+    Player,
+    #[serde(alias = "enemies")]
+    Enemy,
+    #[serde(alias = "bullets")]
+    Bullet,
+}
+
+impl ZoneTarget {
+    pub fn name(self) -> &'static str {
+        match self {
+            ZoneTarget::Player => "player",
+            ZoneTarget::Enemy => "enemy",
+            ZoneTarget::Bullet => "bullet",
+        }
+    }
+}
+
+impl FromStr for ZoneTarget {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "player" => Ok(Self::Player),
+            "enemy" | "enemies" => Ok(Self::Enemy),
+            "bullet" | "bullets" => Ok(Self::Bullet),
+            _ => Err(anyhow!("Invalid zone target: {s}")),
+        }
+    }
+}
+
+/// Component marking entities that zones can track; each implementation has
+/// to have its [`LevelZone::track()`] system registered.
+pub trait ZoneTracked: Component {
+    const TARGET: ZoneTarget;
+}
+
+impl ZoneTracked for Player {
+    const TARGET: ZoneTarget = ZoneTarget::Player;
+}
+
+impl ZoneTracked for Enemy {
+    const TARGET: ZoneTarget = ZoneTarget::Enemy;
+}
+
+impl ZoneTracked for Bullet {
+    const TARGET: ZoneTarget = ZoneTarget::Bullet;
+}
+
+#[derive(Clone, Debug)]
+pub struct ZoneEvent {
+    pub zone: String,
+    pub entity: Entity,
+    pub target: ZoneTarget,
+    pub kind: ZoneEventKind,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ZoneEventKind {
+    Enter,
+    Exit,
+
+    /// Sent during each frame the entity remains inside the zone, for zones
+    /// with [`LevelZone::stay_events`] enabled
+    Stay,
+}
+
+impl ZoneEventKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            ZoneEventKind::Enter => "enter",
+            ZoneEventKind::Exit => "exit",
+            ZoneEventKind::Stay => "stay",
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn rect() -> ZoneShape {
+        ZoneShape::Rect {
+            x1: -1.0,
+            z1: -2.0,
+            x2: 3.0,
+            z2: 4.0,
+        }
+    }
+
+    /// U-shaped polygon, opened towards +z:
+    ///
+    /// ```text
+    /// (0,4)  (1,4)  (3,4)  (4,4)
+    ///   +------+      +------+
+    ///   |      |      |      |
+    ///   |      +------+      |
+    ///   |    (1,1)  (3,1)    |
+    ///   +--------------------+
+    /// (0,0)                (4,0)
+    /// ```
+    fn concave() -> ZoneShape {
+        ZoneShape::Polygon(vec![
+            vec2(0.0, 0.0),
+            vec2(0.0, 4.0),
+            vec2(1.0, 4.0),
+            vec2(1.0, 1.0),
+            vec2(3.0, 1.0),
+            vec2(3.0, 4.0),
+            vec2(4.0, 4.0),
+            vec2(4.0, 0.0),
+        ])
+    }
+
+    #[test]
+    fn rect_contains() {
+        let rect = rect();
+
+        assert!(rect.contains(vec2(0.0, 0.0)));
+        assert!(rect.contains(vec2(2.9, 3.9)));
+        assert!(!rect.contains(vec2(-1.1, 0.0)));
+        assert!(!rect.contains(vec2(0.0, 4.1)));
+    }
+
+    #[test]
+    fn rect_bounds() {
+        let rect = rect();
+
+        // `x1` and `z1` are inclusive, `x2` and `z2` exclusive
+        assert!(rect.contains(vec2(-1.0, 0.0)));
+        assert!(rect.contains(vec2(0.0, -2.0)));
+        assert!(rect.contains(vec2(-1.0, -2.0)));
+        assert!(!rect.contains(vec2(3.0, 0.0)));
+        assert!(!rect.contains(vec2(0.0, 4.0)));
+        assert!(!rect.contains(vec2(3.0, 4.0)));
+        assert!(!rect.contains(vec2(-1.0, 4.0)));
+    }
+
+    #[test]
+    fn circle_contains() {
+        let circle = ZoneShape::Circle {
+            center: vec2(1.0, 1.0),
+            radius: 2.0,
+        };
+
+        assert!(circle.contains(vec2(1.0, 1.0)));
+        assert!(circle.contains(vec2(2.9, 1.0)));
+        assert!(!circle.contains(vec2(3.0, 1.0)));
+        assert!(!circle.contains(vec2(2.5, 2.5)));
+    }
+
+    #[test]
+    fn concave_polygon_contains() {
+        let polygon = concave();
+
+        // Arms and the base
+        assert!(polygon.contains(vec2(0.5, 3.0)));
+        assert!(polygon.contains(vec2(3.5, 3.0)));
+        assert!(polygon.contains(vec2(2.0, 0.5)));
+
+        // Notch between the arms - the ray cast from there crosses the right
+        // arm twice
+        assert!(!polygon.contains(vec2(2.0, 2.0)));
+        assert!(!polygon.contains(vec2(2.0, 3.9)));
+
+        // Outside
+        assert!(!polygon.contains(vec2(-0.5, 2.0)));
+        assert!(!polygon.contains(vec2(4.5, 2.0)));
+        assert!(!polygon.contains(vec2(2.0, -0.5)));
+        assert!(!polygon.contains(vec2(2.0, 4.5)));
+    }
+
+    #[test]
+    fn polygon_edges_and_vertices() {
+        let polygon = concave();
+
+        // Points lying exactly on edges or vertices are inside for the edges
+        // facing -x and -z, and outside for the ones facing +x and +z - so
+        // neighbouring zones sharing an edge never both contain a point, just
+        // like for rects
+        assert!(polygon.contains(vec2(0.0, 2.0)));
+        assert!(polygon.contains(vec2(2.0, 0.0)));
+        assert!(polygon.contains(vec2(0.0, 0.0)));
+        assert!(!polygon.contains(vec2(4.0, 2.0)));
+        assert!(!polygon.contains(vec2(0.5, 4.0)));
+        assert!(!polygon.contains(vec2(4.0, 4.0)));
+
+        // Inner edges of the notch
+        assert!(polygon.contains(vec2(3.0, 2.0)));
+        assert!(!polygon.contains(vec2(1.0, 2.0)));
+        assert!(!polygon.contains(vec2(2.0, 1.0)));
+    }
 
-        (self.x1..self.x2).contains(&obj.x)
-            && (self.z1..self.z2).contains(&obj.z)
+    #[test]
+    fn empty_polygon_contains_nothing() {
+        assert!(!ZoneShape::Polygon(Vec::new()).contains(Vec2::ZERO));
     }
 }
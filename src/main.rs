@@ -10,6 +10,7 @@ mod args;
 mod bullets;
 mod charon;
 mod commands;
+mod editor;
 mod enemies;
 mod explosions;
 mod interaction;
@@ -22,6 +23,8 @@ mod objects;
 mod pickable;
 mod player;
 mod rng;
+mod save;
+mod scripting;
 mod settings;
 mod sounds;
 mod ui;
@@ -39,6 +42,7 @@ mod prelude {
     pub use crate::levels::*;
     pub use crate::objects::*;
     pub use crate::rng::*;
+    pub use crate::scripting::*;
     pub use crate::settings::*;
     pub use crate::ui::*;
     pub use crate::units::*;
@@ -106,6 +110,7 @@ fn main() {
         .add_system(doome_bevy::model_animation::animate)
         // ===== //
         // doome //
+        .insert_resource(args)
         .insert_resource(settings::Settings::default())
         .add_plugin(rng::RngPlugin)
         .add_plugin(units::UnitsPlugin)
@@ -116,10 +121,13 @@ fn main() {
         .add_plugin(charon::CharonPlugin)
         .add_plugin(player::PlayerPlugin)
         .add_plugin(commands::CommandsPlugin)
+        .add_plugin(scripting::ScriptingPlugin)
         .add_plugin(levels::LevelsPlugin)
         .add_plugin(ui::UiPlugin)
         .add_plugin(pickable::PickablePlugin)
         .add_plugin(inventory::InventoryPlugin)
+        .add_plugin(save::SavePlugin)
+        .add_plugin(editor::EditorPlugin)
         .add_plugin(objects::ObjectsPlugin)
         .add_plugin(enemies::EnemiesPlugin)
         .add_system(explosions::update)
@@ -143,6 +151,6 @@ fn toggle_options(
     }
 
     if keys.just_pressed(KeyCode::L) {
-        game_commands.send(Command::ToggleDebug);
+        game_commands.send(Command::ToggleDebug { overlay: None });
     }
 }
@@ -4,6 +4,7 @@ mod flashlight;
 mod gate;
 mod key;
 mod picker;
+mod removable_wall;
 mod torch;
 
 pub use self::column::*;
@@ -12,6 +13,7 @@ pub use self::flashlight::*;
 pub use self::gate::*;
 pub use self::key::*;
 pub use self::picker::*;
+pub use self::removable_wall::*;
 pub use self::torch::*;
 use crate::prelude::*;
 
@@ -30,6 +32,12 @@ impl Plugin for ObjectsPlugin {
 
         app.insert_resource(LockedDoorsState { txt_unlock })
             .add_system(LockedDoor::process)
+            .add_system(
+                RemovableWall::open_secret.after(crate::enemies::sync_nav_data),
+            )
+            .add_system(
+                RemovableWall::destroy.after(crate::enemies::sync_nav_data),
+            )
             .add_system(Torch::process);
     }
 }
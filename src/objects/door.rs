@@ -81,6 +81,22 @@ impl Door {
 
         door.id()
     }
+
+    pub fn open(
+        commands: &mut Commands,
+        door: Entity,
+        door_children: Option<&Children>,
+    ) {
+        commands
+            .entity(door)
+            .remove::<Collider>()
+            .remove::<LockedDoor>()
+            .insert(Fade::fade_out(1.25));
+
+        for door_child in door_children.into_iter().flatten() {
+            commands.entity(*door_child).insert(Fade::fade_out(1.25));
+        }
+    }
 }
 
 #[derive(Component)]
@@ -128,15 +144,7 @@ impl LockedDoor {
 
         if keys.pressed(KeyCode::F) {
             inventory.remove_key(&door.key);
-
-            commands
-                .entity(door_entity)
-                .remove::<Collider>()
-                .insert(Fade::fade_out(1.25));
-
-            for door_child in door_children {
-                commands.entity(*door_child).insert(Fade::fade_out(1.25));
-            }
+            Door::open(&mut commands, door_entity, Some(door_children));
 
             level_tx.send(LevelGameplayEvent::DoorOpened(
                 door.key.name().to_owned(),
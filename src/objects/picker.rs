@@ -92,7 +92,9 @@ impl Picker {
                 .with_texture(texture)
                 .emissive()
                 .with_uv_transparency(),
-            Collider::circle(1.25, 6).detector(),
+            Collider::circle(1.25)
+                .detector()
+                .with_layers(CollisionLayers::TRIGGER),
         ));
 
         if let Some(on_pickup) = self.on_pickup {
@@ -0,0 +1,165 @@
+use doome_bevy::audio::Audio;
+use doome_bevy::nav::NavObstacle;
+
+use crate::explosions::spawn_explosion;
+use crate::player::AddScreenShake;
+use crate::prelude::*;
+
+/// How far in front of the player a secret wall has to be for it to open.
+const SECRET_WALL_REACH: f32 = 1.0;
+
+/// Wall that can disappear during the gameplay - either a secret one (opened
+/// by pressing F in front of it) or a destructible one (shot down).
+///
+/// The entity itself holds just the wall's collider, spanning all of its
+/// parts (i.e. the models it's made of), so that bullets can damage the wall
+/// no matter which side they hit.
+#[derive(Component)]
+pub struct RemovableWall {
+    name: String,
+    kind: RemovableWallKind,
+    parts: Vec<Entity>,
+    half_extents: Vec2,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RemovableWallKind {
+    Secret,
+    Destructible { health: f32 },
+}
+
+impl RemovableWall {
+    pub fn new(name: impl ToString, kind: RemovableWallKind) -> Self {
+        Self {
+            name: name.to_string(),
+            kind,
+            parts: Default::default(),
+            half_extents: Default::default(),
+        }
+    }
+
+    pub fn with_parts(mut self, val: Vec<Entity>) -> Self {
+        self.parts = val;
+        self
+    }
+
+    /// Spawns the wall's collider centered at `position` (on the XZ plane).
+    pub fn spawn(
+        mut self,
+        commands: &mut Commands,
+        position: Vec2,
+        half_extents: Vec2,
+    ) -> Entity {
+        self.half_extents = half_extents;
+
+        let kind = self.kind;
+
+        let mut entity = commands.spawn((
+            Transform::from_translation(vec3(position.x, 0.0, position.y)),
+            Collider::rect(half_extents.x, half_extents.y),
+            NavObstacle,
+            GcAfterLevelUnloaded,
+            self,
+        ));
+
+        if let RemovableWallKind::Destructible { health } = kind {
+            entity.insert(Health::new(health, health));
+        }
+
+        entity.id()
+    }
+
+    pub(super) fn open_secret(
+        mut commands: Commands,
+        keys: Res<Input<KeyCode>>,
+        player: Query<&Transform, With<Player>>,
+        walls: Query<(Entity, &Transform, &RemovableWall)>,
+        mut level_tx: EventWriter<LevelGameplayEvent>,
+        mut sync_nav_data_tx: EventWriter<SyncNavData>,
+    ) {
+        if !keys.just_pressed(KeyCode::F) {
+            return;
+        }
+
+        let Ok(player_xform) = player.get_single() else { return };
+
+        let reach = player_xform.translation.xz()
+            + player_xform.forward().xz() * SECRET_WALL_REACH;
+
+        for (entity, xform, wall) in walls.iter() {
+            if wall.kind != RemovableWallKind::Secret {
+                continue;
+            }
+
+            let dist = (reach - xform.translation.xz()).abs();
+
+            if dist.x > wall.half_extents.x || dist.y > wall.half_extents.y {
+                continue;
+            }
+
+            for part in &wall.parts {
+                commands.entity(*part).insert(Fade::fade_out(1.0));
+            }
+
+            wall.remove(
+                &mut commands,
+                entity,
+                &mut level_tx,
+                &mut sync_nav_data_tx,
+            );
+        }
+    }
+
+    pub(super) fn destroy(
+        mut commands: Commands,
+        assets: Res<Assets>,
+        mut audio: ResMut<Audio>,
+        mut death_rx: EventReader<Death>,
+        walls: Query<(&Transform, &RemovableWall)>,
+        mut level_tx: EventWriter<LevelGameplayEvent>,
+        mut sync_nav_data_tx: EventWriter<SyncNavData>,
+        mut screen_shake_tx: EventWriter<AddScreenShake>,
+    ) {
+        for Death(entity) in death_rx.iter() {
+            let Ok((xform, wall)) = walls.get(*entity) else { continue };
+
+            let scale = wall.half_extents.max_element() * 4.0;
+
+            spawn_explosion(
+                &mut commands,
+                &assets,
+                &mut audio,
+                Transform::from_translation(xform.translation + Vec3::Y)
+                    .with_scale(Vec3::ONE * scale),
+            );
+
+            screen_shake_tx.send(AddScreenShake(0.5));
+
+            for part in &wall.parts {
+                commands.entity(*part).despawn();
+            }
+
+            wall.remove(
+                &mut commands,
+                *entity,
+                &mut level_tx,
+                &mut sync_nav_data_tx,
+            );
+        }
+    }
+
+    fn remove(
+        &self,
+        commands: &mut Commands,
+        entity: Entity,
+        level_tx: &mut EventWriter<LevelGameplayEvent>,
+        sync_nav_data_tx: &mut EventWriter<SyncNavData>,
+    ) {
+        log::info!("Wall removed: {}", self.name);
+
+        commands.entity(entity).despawn();
+
+        level_tx.send(LevelGameplayEvent::WallRemoved(self.name.clone()));
+        sync_nav_data_tx.send(SyncNavData::default());
+    }
+}
@@ -9,6 +9,11 @@ const ACCELERATION_SPEED: f32 = 8.0;
 const BRAKING_SPEED: f32 = 24.0;
 const SWAY_FREQ: f32 = 4.0;
 const MAX_SWAY: f32 = 0.3;
+const JUMP_SPEED: f32 = 7.0;
+const CAMERA_HEIGHT: f32 = 1.2;
+
+/// How deep the player can fall into a pit before dying
+const PIT_DEPTH: f32 = -10.0;
 
 pub struct PlayerPlugin;
 
@@ -27,6 +32,7 @@ impl Plugin for PlayerPlugin {
         app.add_system(update_screen_shake);
         app.add_system(add_screen_shake_on_damage);
         app.add_system(handle_player_death);
+        app.add_system(handle_falling_into_pits);
     }
 }
 
@@ -41,13 +47,10 @@ pub fn spawn(mut commands: Commands, prefab_weapons: Res<PrefabWeapons>) {
     commands.spawn((
         Player::new(),
         Transform::default(),
-        Body {
-            acceleration: Vec2::ZERO,
-            velocity: Vec2::ZERO,
-            body_type: BodyType::Kinematic,
-        },
+        Body::kinematic(),
+        Vertical::new(),
         Weapon::new(prefab_weapons.handgun.0.clone()),
-        Collider::circle(0.35, 16),
+        Collider::circle(0.35).with_layers(CollisionLayers::PLAYER),
         Health::new(100.0, 100.0),
     ));
 }
@@ -58,9 +61,9 @@ pub fn process_movement(
     settings: Res<Settings>,
     input_lock: Res<InputLock>,
     mut mouse_motion_rx: EventReader<MouseMotion>,
-    mut player: Query<(&Player, &mut Body, &mut Transform)>,
+    mut player: Query<(&Player, &mut Body, &mut Vertical, &mut Transform)>,
 ) {
-    let (player, mut body, mut transform) = player.single_mut();
+    let (player, mut body, mut vertical, mut transform) = player.single_mut();
     let delta = time.delta_seconds();
     let mut desired_velocity = Vec2::ZERO;
 
@@ -96,6 +99,10 @@ pub fn process_movement(
 
             desired_velocity += graphical_to_physical(transform.left() * sign);
         }
+
+        if keys.just_pressed(KeyCode::LShift) && player.can_move {
+            vertical.jump(JUMP_SPEED);
+        }
     }
 
     let desired_velocity =
@@ -116,7 +123,7 @@ pub fn process_movement(
     }
 }
 
-fn sync_camera(
+pub fn sync_camera(
     time: Res<Time>,
     screenshake: Res<ScreenShake>,
     mut camera: Query<&mut Camera>,
@@ -133,7 +140,7 @@ fn sync_camera(
     let camera_effects =
         map_to_player_transform(sway + screen_shake, transform);
 
-    camera.origin = camera_effects + vec3(pos.x, 1.2, pos.z);
+    camera.origin = camera_effects + vec3(pos.x, pos.y + CAMERA_HEIGHT, pos.z);
     camera.look_at = camera.origin + transform.forward() * 5.0;
 }
 
@@ -204,7 +211,12 @@ pub fn handle_shooting(
     }
 
     if mouse.pressed(MouseButton::Left) || keys.pressed(KeyCode::Space) {
-        weapon.shoot(&mut commands, &transform, transform.forward());
+        weapon.shoot(
+            &mut commands,
+            &transform,
+            transform.forward(),
+            CollisionLayers::PLAYER_PROJECTILE,
+        );
         shots.send(PlayerShot);
     }
 }
@@ -227,12 +239,20 @@ fn handle_player_death(
     mut game_commands: EventWriter<Command>,
     mut death_events: EventReader<Death>,
     player: Query<(Entity, &Player)>,
+    campaign: Res<Campaign>,
 ) {
     let (player_entity, _) = player.single();
 
     for ev in death_events.iter() {
         if ev.0 == player_entity {
-            game_commands.send(Command::GotoLevel { level: Level::l0() });
+            if campaign.checkpoint().is_some() {
+                game_commands.send(Command::Respawn);
+            } else {
+                game_commands.send(Command::GotoLevel {
+                    level: LevelRegistry::GAME_OVER.into(),
+                });
+            }
+
             game_commands.send(Command::SetHealth {
                 entity: EntityOrPlayer::Player,
                 health: 100.0,
@@ -240,3 +260,22 @@ fn handle_player_death(
         }
     }
 }
+
+fn handle_falling_into_pits(
+    mut has_fallen: Local<bool>,
+    mut death_tx: EventWriter<Death>,
+    player: Query<(Entity, &Transform), With<Player>>,
+) {
+    let Ok((player_entity, transform)) = player.get_single() else { return };
+
+    // (checking the flag, so that the player dies once instead of during
+    // each frame until respawned)
+    if transform.translation.y < PIT_DEPTH {
+        if !*has_fallen {
+            *has_fallen = true;
+            death_tx.send(Death(player_entity));
+        }
+    } else {
+        *has_fallen = false;
+    }
+}
@@ -0,0 +1,449 @@
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::pickable::Pickable;
+use crate::prelude::*;
+use crate::ui;
+use crate::weapons::{PrefabWeapons, Weapon};
+
+const SAVE_DIR: &str = "saves";
+
+pub const QUICK_SAVE: &str = "quicksave";
+
+pub struct SavePlugin;
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SaveGame>()
+            .add_event::<LoadGame>()
+            .init_resource::<PendingLoad>()
+            .add_system(handle_keys)
+            .add_system(SaveGame::handle)
+            .add_system(LoadGame::handle)
+            .add_system(LoadGame::apply);
+    }
+}
+
+pub struct SaveGame(pub String);
+
+pub struct LoadGame(pub String);
+
+#[derive(Resource, Default)]
+struct PendingLoad(Option<Snapshot>);
+
+/// State of the game, as written into the save file.
+///
+/// Loading a save rebuilds the level from its save point (see: [`SavePoint`])
+/// and then applies the snapshot on top of it, putting the player, doors,
+/// pickables and enemies where they were.
+#[derive(Debug, Serialize, Deserialize)]
+struct Snapshot {
+    level: String,
+    stage: String,
+    player: PlayerSnapshot,
+    opened_doors: Vec<String>,
+
+    /// Pickables that were still lying around when the game was saved; all the
+    /// other ones have been picked up.
+    pickables: Vec<String>,
+
+    enemies: Vec<EnemySnapshot>,
+
+    /// Enemies spawned by the level's logic (as compared to the ones placed
+    /// on the map).
+    spawned_enemies: Vec<SpawnedEnemySnapshot>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PlayerSnapshot {
+    translation: Vec3,
+    rotation: Quat,
+    health: f32,
+    weapon: String,
+    ammo: Option<usize>,
+    has_flashlight: bool,
+    keys: Vec<KeySnapshot>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct KeySnapshot {
+    name: String,
+    color: [f32; 3],
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct EnemySnapshot {
+    id: String,
+    translation: Vec3,
+    rotation: Quat,
+    health: f32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SpawnedEnemySnapshot {
+    /// Name of the enemy, as understood by [`Spawnable`]
+    kind: String,
+    translation: Vec3,
+    health: f32,
+    max_health: f32,
+}
+
+fn handle_keys(
+    keys: Res<Input<KeyCode>>,
+    mut game_commands: EventWriter<Command>,
+) {
+    if keys.just_pressed(KeyCode::F5) {
+        game_commands.send(Command::SaveGame {
+            name: QUICK_SAVE.into(),
+        });
+    }
+
+    if keys.just_pressed(KeyCode::F9) {
+        game_commands.send(Command::LoadGame {
+            name: QUICK_SAVE.into(),
+        });
+    }
+}
+
+impl SaveGame {
+    fn handle(
+        mut save_rx: EventReader<SaveGame>,
+        mut output_tx: EventWriter<CommandOutput>,
+        coordinator: Res<LevelsCoordinator>,
+        registry: Res<LevelRegistry>,
+        save_point: Res<SavePoint>,
+        player: Query<(&Transform, &Health, &Weapon), With<Player>>,
+        inventory: Query<&Inventory>,
+        objects: Query<(
+            &MapObject,
+            &Transform,
+            Option<&Health>,
+            Option<&Collider>,
+            Option<&Pickable>,
+        )>,
+        spawned_enemies: Query<
+            (&Transform, &Health, Option<&Doome>),
+            (With<Enemy>, Without<MapObject>),
+        >,
+    ) {
+        for SaveGame(name) in save_rx.iter() {
+            let result = (|| {
+                if coordinator.is_game_over {
+                    bail!("Can't save the game now");
+                }
+
+                let Some(stage) = save_point.current() else {
+                    bail!("Can't save the game now");
+                };
+
+                let (player_xform, player_health, weapon) =
+                    player.get_single().context("Missing player")?;
+
+                let inventory =
+                    inventory.get_single().context("Missing inventory")?;
+
+                let mut snapshot = Snapshot {
+                    level: registry.name(coordinator.current_level).into(),
+                    stage: stage.into(),
+                    player: PlayerSnapshot {
+                        translation: player_xform.translation,
+                        rotation: player_xform.rotation,
+                        health: player_health.health,
+                        weapon: weapon.definition.name.into(),
+                        ammo: weapon.ammo,
+                        has_flashlight: inventory.has_flashlight,
+                        keys: inventory
+                            .keys
+                            .iter()
+                            .map(|key| KeySnapshot {
+                                name: key.name().into(),
+                                color: {
+                                    let color = key.color();
+
+                                    [color.r, color.g, color.b]
+                                },
+                            })
+                            .collect(),
+                    },
+                    opened_doors: Default::default(),
+                    pickables: Default::default(),
+                    enemies: Default::default(),
+                    spawned_enemies: Default::default(),
+                };
+
+                for (obj, xform, health, collider, pickable) in objects.iter() {
+                    if obj.is_door() {
+                        if collider.is_none() {
+                            snapshot.opened_doors.push(obj.id().into());
+                        }
+                    } else if obj.is_enemy() {
+                        if let Some(health) = health {
+                            if !health.is_dead {
+                                snapshot.enemies.push(EnemySnapshot {
+                                    id: obj.id().into(),
+                                    translation: xform.translation,
+                                    rotation: xform.rotation,
+                                    health: health.health,
+                                });
+                            }
+                        }
+                    } else if pickable.is_some() {
+                        snapshot.pickables.push(obj.id().into());
+                    }
+                }
+
+                for (xform, health, doome) in spawned_enemies.iter() {
+                    if health.is_dead {
+                        continue;
+                    }
+
+                    let kind = if doome.is_some() {
+                        "doome"
+                    } else {
+                        "moth-monster"
+                    };
+
+                    snapshot.spawned_enemies.push(SpawnedEnemySnapshot {
+                        kind: kind.into(),
+                        translation: xform.translation,
+                        health: health.health,
+                        max_health: health.max_health,
+                    });
+                }
+
+                write(name, &snapshot)
+            })();
+
+            let output = match result {
+                Ok(()) => format!("Game saved: {}", name),
+                Err(err) => format!("Couldn't save the game: {:#}", err),
+            };
+
+            log::info!("{}", output);
+            output_tx.send(CommandOutput(output));
+        }
+    }
+}
+
+impl LoadGame {
+    fn handle(
+        mut load_rx: EventReader<LoadGame>,
+        mut output_tx: EventWriter<CommandOutput>,
+        mut goto_level_tx: EventWriter<GotoLevel>,
+        mut pending: ResMut<PendingLoad>,
+        mut save_point: ResMut<SavePoint>,
+        registry: Res<LevelRegistry>,
+    ) {
+        for LoadGame(name) in load_rx.iter() {
+            let result = read(name).and_then(|snapshot| {
+                let level = LevelRef::from(snapshot.level.as_str());
+
+                if registry.resolve(&level).is_none() {
+                    bail!("Save refers to unknown level: {}", snapshot.level);
+                }
+
+                goto_level_tx.send(GotoLevel::new(level));
+                save_point.resume(&snapshot.level, &snapshot.stage);
+                pending.0 = Some(snapshot);
+
+                Ok(())
+            });
+
+            let output = match result {
+                Ok(()) => format!("Loading game: {}", name),
+                Err(err) => format!("Couldn't load the game: {:#}", err),
+            };
+
+            log::info!("{}", output);
+            output_tx.send(CommandOutput(output));
+        }
+    }
+
+    /// Applies pending snapshot once its level has been rebuilt.
+    fn apply(
+        mut commands: Commands,
+        assets: Res<Assets>,
+        mut pending: ResMut<PendingLoad>,
+        coordinator: Res<LevelsCoordinator>,
+        registry: Res<LevelRegistry>,
+        mut campaign: ResMut<Campaign>,
+        prefab_weapons: Res<PrefabWeapons>,
+        mut weapon_sprites: ResMut<ui::gun::State>,
+        mut sync_nav_data_tx: EventWriter<SyncNavData>,
+        mut player: Query<
+            (&mut Transform, &mut Health, &mut Weapon),
+            With<Player>,
+        >,
+        mut inventory: Query<&mut Inventory>,
+        mut objects: Query<
+            (
+                Entity,
+                &MapObject,
+                &mut Transform,
+                Option<&mut Health>,
+                Option<&Children>,
+                Option<&Pickable>,
+            ),
+            Without<Player>,
+        >,
+    ) {
+        let Some(snapshot) = &pending.0 else { return };
+        let Some(level) = coordinator.loading_level else { return };
+
+        if registry.resolve(&snapshot.level.as_str().into()) != Some(level) {
+            return;
+        }
+
+        let Some(snapshot) = pending.0.take() else { return };
+
+        // -----
+
+        let Ok((mut player_xform, mut player_health, mut weapon)) =
+            player.get_single_mut() else { return };
+
+        let Ok(mut inventory) = inventory.get_single_mut() else { return };
+
+        player_xform.translation = snapshot.player.translation;
+        player_xform.rotation = snapshot.player.rotation;
+        player_health.health = snapshot.player.health;
+        player_health.is_dead = false;
+
+        inventory.keys = snapshot
+            .player
+            .keys
+            .iter()
+            .map(|key| {
+                let [r, g, b] = key.color;
+
+                Key::new(&key.name, Color::srgb(r, g, b))
+            })
+            .collect();
+
+        if let Some((definition, _)) =
+            prefab_weapons.get(&snapshot.player.weapon)
+        {
+            let loadout = Loadout {
+                weapon: definition.name,
+                ammo: snapshot.player.ammo,
+                has_flashlight: snapshot.player.has_flashlight,
+            };
+
+            loadout.apply(
+                &prefab_weapons,
+                &mut weapon,
+                &mut inventory,
+                &mut weapon_sprites,
+            );
+
+            campaign.set_loadout(loadout);
+        } else {
+            log::warn!(
+                "Save refers to unknown weapon: {}",
+                snapshot.player.weapon
+            );
+        }
+
+        // -----
+
+        for (entity, obj, mut xform, health, children, pickable) in
+            objects.iter_mut()
+        {
+            let id = obj.id();
+
+            if obj.is_door() {
+                if snapshot.opened_doors.iter().any(|door| door == id) {
+                    Door::open(&mut commands, entity, children);
+                }
+            } else if obj.is_enemy() {
+                let enemy =
+                    snapshot.enemies.iter().find(|enemy| enemy.id == id);
+
+                if let Some(enemy) = enemy {
+                    xform.translation = enemy.translation;
+                    xform.rotation = enemy.rotation;
+
+                    if let Some(mut health) = health {
+                        health.health = enemy.health;
+                    }
+                } else {
+                    commands.entity(entity).despawn_recursive();
+                }
+            } else if pickable.is_some()
+                && !snapshot.pickables.iter().any(|pickable| pickable == id)
+            {
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+
+        for enemy in &snapshot.spawned_enemies {
+            let Ok(spawnable) = enemy.kind.parse::<Spawnable>() else {
+                log::warn!("Save refers to unknown enemy: {}", enemy.kind);
+                continue;
+            };
+
+            let entity =
+                spawnable.spawn(&assets, &mut commands, enemy.translation);
+
+            commands
+                .entity(entity)
+                .insert(Health::new(enemy.health, enemy.max_health));
+        }
+
+        // The level might've changed its shape since it got loaded (e.g.
+        // opened doors), so the enemies have to find their ways anew
+        sync_nav_data_tx.send(SyncNavData::default());
+
+        log::info!("Game loaded");
+    }
+}
+
+fn path(name: &str) -> Result<PathBuf> {
+    let is_valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_');
+
+    if !is_valid {
+        bail!("Invalid save name: {}", name);
+    }
+
+    Ok(PathBuf::from(SAVE_DIR).join(format!("{}.json", name)))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn write(name: &str, snapshot: &Snapshot) -> Result<()> {
+    let path = path(name)?;
+
+    std::fs::create_dir_all(SAVE_DIR)
+        .context("Couldn't create save directory")?;
+
+    let snapshot = serde_json::to_string_pretty(snapshot)?;
+
+    std::fs::write(&path, snapshot)
+        .with_context(|| format!("Couldn't write {}", path.display()))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn read(name: &str) -> Result<Snapshot> {
+    let path = path(name)?;
+
+    let snapshot = std::fs::read_to_string(&path)
+        .with_context(|| format!("Couldn't read {}", path.display()))?;
+
+    serde_json::from_str(&snapshot)
+        .with_context(|| format!("Couldn't parse {}", path.display()))
+}
+
+#[cfg(target_arch = "wasm32")]
+fn write(name: &str, _: &Snapshot) -> Result<()> {
+    path(name)?;
+    bail!("Saving is not supported in the browser")
+}
+
+#[cfg(target_arch = "wasm32")]
+fn read(name: &str) -> Result<Snapshot> {
+    path(name)?;
+    bail!("Loading is not supported in the browser")
+}
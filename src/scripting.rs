@@ -0,0 +1,135 @@
+mod api;
+mod level;
+
+use std::collections::HashMap;
+use std::mem;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use anyhow::anyhow;
+use rhai::{Dynamic, Engine};
+
+pub use self::level::*;
+use crate::prelude::*;
+
+pub struct ScriptingPlugin;
+
+impl Plugin for ScriptingPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ScriptEngine::new())
+            .add_system(LevelRhaiScript::process)
+            .add_system(ScriptEngine::flush.after(LevelRhaiScript::process));
+    }
+}
+
+/// Rhai runtime shared by level scripts and the console.
+///
+/// Functions exposed to scripts don't have access to the world - instead they
+/// queue effects which get applied later by [`ScriptEngine::flush()`].
+#[derive(Resource)]
+pub struct ScriptEngine {
+    engine: Engine,
+    ctxt: Arc<Mutex<ScriptContext>>,
+}
+
+#[derive(Default)]
+struct ScriptContext {
+    effects: Vec<ScriptEffect>,
+    output: Vec<String>,
+    tags: HashMap<String, Vec3>,
+    stage: &'static str,
+    enemies_alive: usize,
+    keys_held: usize,
+}
+
+enum ScriptEffect {
+    Command(Command),
+    Emit(LevelGameplayEvent),
+    Say(String),
+    Spawn {
+        spawnable: Spawnable,
+        position: Vec3,
+    },
+    StartTimer {
+        name: String,
+        secs: f32,
+    },
+}
+
+impl ScriptEngine {
+    fn new() -> Self {
+        let ctxt = Arc::new(Mutex::new(ScriptContext::default()));
+        let mut engine = Engine::new();
+
+        api::register(&mut engine, &ctxt);
+
+        Self { engine, ctxt }
+    }
+
+    /// Evaluates code coming from the console, returning whatever it printed
+    /// together with its result.
+    pub fn eval(&self, code: &str) -> anyhow::Result<String> {
+        let result = self
+            .engine
+            .eval::<Dynamic>(code)
+            .map_err(|err| anyhow!("{}", err));
+
+        let mut output = self.take_output();
+        let result = result?;
+
+        if !result.is_unit() {
+            output.push(result.to_string());
+        }
+
+        Ok(output.join("\n"))
+    }
+
+    fn ctxt(&self) -> MutexGuard<'_, ScriptContext> {
+        self.ctxt.lock().unwrap()
+    }
+
+    fn take_output(&self) -> Vec<String> {
+        mem::take(&mut self.ctxt().output)
+    }
+
+    fn flush(
+        this: Res<Self>,
+        mut commands: Commands,
+        assets: Res<Assets>,
+        mut game_commands: EventWriter<Command>,
+        mut level_tx: EventWriter<LevelGameplayEvent>,
+        mut typewriter_tx: EventWriter<TypewriterPrint>,
+    ) {
+        let effects = mem::take(&mut this.ctxt().effects);
+
+        for effect in effects {
+            match effect {
+                ScriptEffect::Command(cmd) => {
+                    game_commands.send(cmd);
+                }
+
+                ScriptEffect::Emit(event) => {
+                    level_tx.send(event);
+                }
+
+                ScriptEffect::Say(text) => {
+                    typewriter_tx.send(TypewriterPrint::new(text));
+                }
+
+                ScriptEffect::Spawn {
+                    spawnable,
+                    position,
+                } => {
+                    spawnable.spawn(&assets, &mut commands, position);
+                }
+
+                ScriptEffect::StartTimer { name, .. } => {
+                    log::warn!(
+                        "Timer `{}` was started outside of a level script; \
+                         ignoring it",
+                        name
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use std::sync::{Arc, Mutex};
+
+use rhai::{Engine, EvalAltResult, INT};
+
+use super::{ScriptContext, ScriptEffect};
+use crate::prelude::*;
+
+type ScriptResult<T> = Result<T, Box<EvalAltResult>>;
+
+/// Registers functions available to scripts:
+///
+/// - `command(text)` - executes a console command, e.g. `command("noclip")`,
+/// - `say(text)` - prints text using the typewriter,
+/// - `emit(kind, name)` - sends a gameplay event, e.g.
+///   `emit("zone-entered", "trap")`,
+/// - `spawn_at(what, position)` - spawns an enemy or a pickup, e.g.
+///   `spawn_at("moth-monster", tag("monster-1"))`,
+/// - `tag(name)`, `has_tag(name)` - query the current map's tags,
+/// - `stage()` - returns the current level's stage, as reported by the level
+///   (see [`LevelRhaiScript::set_stage()`]),
+/// - `timer(name, secs)` - calls level's `on_timer(name)` after given time,
+/// - `enemies_alive()` - returns number of enemies that are still alive,
+/// - `keys_held()` - returns number of keys in the player's inventory,
+/// - `vec3(x, y, z)` - creates a vector (supports `+`, `-` and `*`).
+pub(super) fn register(engine: &mut Engine, ctxt: &Arc<Mutex<ScriptContext>>) {
+    engine
+        .register_type_with_name::<Vec3>("Vec3")
+        .register_fn("vec3", |x: f32, y: f32, z: f32| vec3(x, y, z))
+        .register_get("x", |v: &mut Vec3| v.x)
+        .register_get("y", |v: &mut Vec3| v.y)
+        .register_get("z", |v: &mut Vec3| v.z)
+        .register_fn("+", |a: Vec3, b: Vec3| a + b)
+        .register_fn("-", |a: Vec3, b: Vec3| a - b)
+        .register_fn("*", |a: Vec3, b: f32| a * b)
+        .register_fn("to_string", |v: &mut Vec3| v.to_string())
+        .register_fn("to_debug", |v: &mut Vec3| v.to_string());
+
+    engine.on_print({
+        let ctxt = Arc::clone(ctxt);
+
+        move |text| {
+            log::info!("[script] {}", text);
+            ctxt.lock().unwrap().output.push(text.to_owned());
+        }
+    });
+
+    engine.register_fn("command", {
+        let ctxt = Arc::clone(ctxt);
+
+        move |cmd: &str| -> ScriptResult<()> {
+            let cmd = cmd.parse().map_err(|err| format!("{:#}", err))?;
+
+            ctxt.lock()
+                .unwrap()
+                .effects
+                .push(ScriptEffect::Command(cmd));
+
+            Ok(())
+        }
+    });
+
+    engine.register_fn("say", {
+        let ctxt = Arc::clone(ctxt);
+
+        move |text: &str| {
+            ctxt.lock()
+                .unwrap()
+                .effects
+                .push(ScriptEffect::Say(text.to_owned()));
+        }
+    });
+
+    engine.register_fn("emit", {
+        let ctxt = Arc::clone(ctxt);
+
+        move |kind: &str, name: &str| -> ScriptResult<()> {
+            let name = name.to_owned();
+
+            let event = match kind {
+                "door-opened" => LevelGameplayEvent::DoorOpened(name),
+                "key-picked" => LevelGameplayEvent::KeyPicked(name),
+                "zone-entered" => LevelGameplayEvent::ZoneEntered(name),
+                "zone-left" => LevelGameplayEvent::ZoneLeft(name),
+                "wall-removed" => LevelGameplayEvent::WallRemoved(name),
+                _ => {
+                    return Err(format!("Unknown event: {}", kind).into());
+                }
+            };
+
+            ctxt.lock().unwrap().effects.push(ScriptEffect::Emit(event));
+
+            Ok(())
+        }
+    });
+
+    engine.register_fn("spawn_at", {
+        let ctxt = Arc::clone(ctxt);
+
+        move |spawnable: &str, position: Vec3| -> ScriptResult<()> {
+            let spawnable =
+                spawnable.parse().map_err(|err| format!("{:#}", err))?;
+
+            ctxt.lock().unwrap().effects.push(ScriptEffect::Spawn {
+                spawnable,
+                position,
+            });
+
+            Ok(())
+        }
+    });
+
+    engine.register_fn("tag", {
+        let ctxt = Arc::clone(ctxt);
+
+        move |name: &str| -> ScriptResult<Vec3> {
+            ctxt.lock().unwrap().tags.get(name).copied().ok_or_else(|| {
+                format!("Map contains no tag called `{}`", name).into()
+            })
+        }
+    });
+
+    engine.register_fn("has_tag", {
+        let ctxt = Arc::clone(ctxt);
+
+        move |name: &str| ctxt.lock().unwrap().tags.contains_key(name)
+    });
+
+    engine.register_fn("stage", {
+        let ctxt = Arc::clone(ctxt);
+
+        move || ctxt.lock().unwrap().stage.to_owned()
+    });
+
+    engine.register_fn("timer", {
+        let ctxt = Arc::clone(ctxt);
+
+        move |name: &str, secs: f32| {
+            ctxt.lock().unwrap().effects.push(ScriptEffect::StartTimer {
+                name: name.to_owned(),
+                secs,
+            });
+        }
+    });
+
+    engine.register_fn("enemies_alive", {
+        let ctxt = Arc::clone(ctxt);
+
+        move || ctxt.lock().unwrap().enemies_alive as INT
+    });
+
+    engine.register_fn("keys_held", {
+        let ctxt = Arc::clone(ctxt);
+
+        move || ctxt.lock().unwrap().keys_held as INT
+    });
+}
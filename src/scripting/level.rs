@@ -0,0 +1,183 @@
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use rhai::{CallFnOptions, Dynamic, FuncArgs, Map, Scope, AST};
+
+use super::{ScriptEffect, ScriptEngine};
+use crate::prelude::*;
+
+/// Level logic written in Rhai, loaded together with the map.
+///
+/// Script can define any of the following functions, all of which get called
+/// with `this` bound to an object map that persists for the level's lifetime:
+///
+/// ```rhai
+/// fn init() { }
+/// fn update(dt) { }
+/// fn on_event(kind, name) { }
+/// fn on_zone(kind, zone, target, entity) { }
+/// fn on_timer(name) { }
+/// ```
+///
+/// `on_zone()` gets called for each [`ZoneEvent`], e.g. `("enter", "gate",
+/// "enemy", "12v0")` - the entity can be passed to commands such as `despawn`.
+///
+/// Hooks get called regardless of what the level's code is doing at the
+/// moment - when that matters, the level can report its stage through
+/// [`Self::set_stage()`] and the script can check it with `stage()`.
+#[derive(Component)]
+pub struct LevelRhaiScript {
+    code: &'static str,
+    stage: &'static str,
+    tags: HashMap<String, Vec3>,
+    compiled: Option<CompiledScript>,
+    timers: HashMap<String, Timer>,
+}
+
+struct CompiledScript {
+    ast: AST,
+    fns: HashSet<String>,
+    this: Dynamic,
+}
+
+impl LevelRhaiScript {
+    pub fn new(code: &'static str, locator: &LevelLocator) -> Self {
+        Self {
+            code,
+            stage: "",
+            tags: locator
+                .tags()
+                .map(|(name, pos)| (name.to_owned(), pos))
+                .collect(),
+            compiled: None,
+            timers: Default::default(),
+        }
+    }
+
+    pub fn set_stage(&mut self, stage: &'static str) {
+        self.stage = stage;
+    }
+
+    pub(super) fn process(
+        time: Res<Time>,
+        engine: Res<ScriptEngine>,
+        mut scripts: Query<&mut LevelRhaiScript>,
+        mut level_rx: EventReader<LevelGameplayEvent>,
+        mut zone_rx: EventReader<ZoneEvent>,
+        enemies: Query<&Health, With<Enemy>>,
+        inventory: Query<&Inventory>,
+    ) {
+        let Ok(mut this) = scripts.get_single_mut() else { return };
+        let this = &mut *this;
+
+        {
+            let mut ctxt = engine.ctxt();
+
+            ctxt.tags.clone_from(&this.tags);
+            ctxt.stage = this.stage;
+
+            ctxt.enemies_alive =
+                enemies.iter().filter(|health| !health.is_dead).count();
+
+            ctxt.keys_held =
+                inventory.get_single().map_or(0, |inv| inv.keys.len());
+        }
+
+        let compiled = this.compiled.get_or_insert_with(|| {
+            let ast = engine.engine.compile(this.code).unwrap_or_else(|err| {
+                panic!("Couldn't compile level script: {}", err)
+            });
+
+            let fns = ast.iter_functions().map(|f| f.name.to_owned()).collect();
+
+            let mut compiled = CompiledScript {
+                ast,
+                fns,
+                this: Map::new().into(),
+            };
+
+            compiled.call(&engine, "init", ());
+            compiled
+        });
+
+        for event in level_rx.iter() {
+            let (kind, name) = match event {
+                LevelGameplayEvent::DoorOpened(name) => ("door-opened", name),
+                LevelGameplayEvent::KeyPicked(name) => ("key-picked", name),
+                LevelGameplayEvent::ZoneEntered(name) => ("zone-entered", name),
+                LevelGameplayEvent::ZoneLeft(name) => ("zone-left", name),
+                LevelGameplayEvent::WallRemoved(name) => ("wall-removed", name),
+            };
+
+            compiled.call(
+                &engine,
+                "on_event",
+                (kind.to_owned(), name.to_owned()),
+            );
+        }
+
+        for event in zone_rx.iter() {
+            compiled.call(
+                &engine,
+                "on_zone",
+                (
+                    event.kind.name().to_owned(),
+                    event.zone.clone(),
+                    event.target.name().to_owned(),
+                    EntityHandle(event.entity).to_string(),
+                ),
+            );
+        }
+
+        for (name, timer) in &mut this.timers {
+            if timer.tick(time.delta()).just_finished() {
+                compiled.call(&engine, "on_timer", (name.to_owned(),));
+            }
+        }
+
+        compiled.call(&engine, "update", (time.delta_seconds(),));
+
+        // -----
+
+        let mut ctxt = engine.ctxt();
+
+        ctxt.effects.retain(|effect| {
+            if let ScriptEffect::StartTimer { name, secs } = effect {
+                this.timers.insert(
+                    name.to_owned(),
+                    Timer::new(Duration::from_secs_f32(*secs), TimerMode::Once),
+                );
+
+                false
+            } else {
+                true
+            }
+        });
+    }
+}
+
+impl CompiledScript {
+    fn call(&mut self, engine: &ScriptEngine, name: &str, args: impl FuncArgs) {
+        if !self.fns.contains(name) {
+            return;
+        }
+
+        let options = CallFnOptions::new()
+            .eval_ast(false)
+            .bind_this_ptr(&mut self.this);
+
+        let result = engine.engine.call_fn_with_options::<Dynamic>(
+            options,
+            &mut Scope::new(),
+            &self.ast,
+            name,
+            args,
+        );
+
+        if let Err(err) = result {
+            log::error!("Level script failed in `{}()`: {}", name, err);
+        }
+
+        engine.take_output();
+    }
+}
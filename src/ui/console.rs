@@ -75,6 +75,16 @@ pub fn update(
         state.current.pop();
     }
 
+    if keys.just_pressed(KeyCode::Return)
+        && (keys.pressed(KeyCode::LShift) || keys.pressed(KeyCode::RShift))
+    {
+        // Shift+Enter continues the input in a new line, which comes handy
+        // for `eval`-ing multi-line scripts
+        state.current.retain(|ch| ch != '\r');
+        state.current.push('\n');
+        return;
+    }
+
     if keys.just_pressed(KeyCode::Return) {
         match state.current.trim().parse::<Command>() {
             Ok(cmd) => {
@@ -119,17 +129,31 @@ pub fn render(
 
     canvas.rect(0, 0, WIDTH, HEIGHT, Color::hex(0x000000ee));
 
-    let prompt = {
-        let caret = if time.elapsed_seconds() % 1.00 < 0.5 {
-            '_'
+    let caret = if time.elapsed_seconds() % 1.00 < 0.5 {
+        '_'
+    } else {
+        ' '
+    };
+
+    let prompt: Vec<_> = state.current.split('\n').collect();
+
+    for (i, line) in prompt.iter().rev().enumerate() {
+        let line = if i == 0 {
+            format!("{}{}", line, caret)
         } else {
-            ' '
+            line.to_string()
         };
 
-        format!("$ {}{}", state.current, caret)
-    };
+        let line = if i == prompt.len() - 1 {
+            format!("$ {}", line)
+        } else {
+            format!("  {}", line)
+        };
+
+        canvas.text(5, (HEIGHT - 21) as i16 - 12 * i as i16, line, false);
+    }
 
-    canvas.text(5, (HEIGHT - 21) as i16, prompt, false);
+    let prompt_offset = 12 * (prompt.len() - 1) as i16;
 
     for (i, line) in state.buffer.iter().rev().enumerate() {
         if i > 10 {
@@ -138,8 +162,8 @@ pub fn render(
 
         canvas.text(
             5,
-            (HEIGHT as i16) - 28 - 12 * (i + 1) as i16,
-            &line.text,
+            (HEIGHT as i16) - 28 - prompt_offset - 12 * (i + 1) as i16,
+            &line.text.replace('\n', " "),
             false,
         );
     }
@@ -7,6 +7,7 @@ use doome_engine::{TextCanvas, HEIGHT, WIDTH};
 use doome_surface::Color;
 
 use crate::prelude::*;
+use crate::save::QUICK_SAVE;
 
 #[derive(Default, Resource)]
 pub struct Menu {
@@ -51,6 +52,8 @@ impl MenuBuilder {
 enum MenuItem {
     MainContinueGame,
     MainRestartCurrentLevel,
+    MainQuickSave,
+    MainQuickLoad,
     MainMouseSettings,
     MainDisplaySettings,
     MainQuitGame,
@@ -69,6 +72,8 @@ impl MenuItem {
         match self {
             MenuItem::MainContinueGame => "Continue game",
             MenuItem::MainRestartCurrentLevel => "Restart current level",
+            MenuItem::MainQuickSave => "Quick save (F5)",
+            MenuItem::MainQuickLoad => "Quick load (F9)",
             MenuItem::MainMouseSettings => "Mouse settings",
             MenuItem::MainDisplaySettings => "Display settings",
             MenuItem::MainQuitGame => "Quit game",
@@ -116,7 +121,25 @@ impl MenuItem {
 
             MenuItem::MainRestartCurrentLevel => {
                 game_commands.send(Command::GotoLevel {
-                    level: levels_coordinator.current_level,
+                    level: levels_coordinator.current_level.into(),
+                });
+
+                state.is_visible = false;
+                lock.is_locked = state.is_visible;
+            }
+
+            MenuItem::MainQuickSave => {
+                game_commands.send(Command::SaveGame {
+                    name: QUICK_SAVE.into(),
+                });
+
+                state.is_visible = false;
+                lock.is_locked = state.is_visible;
+            }
+
+            MenuItem::MainQuickLoad => {
+                game_commands.send(Command::LoadGame {
+                    name: QUICK_SAVE.into(),
                 });
 
                 state.is_visible = false;
@@ -185,6 +208,8 @@ pub fn setup(mut commands: Commands) {
     menu.add(|menu| {
         menu.add(MenuItem::MainContinueGame);
         menu.add(MenuItem::MainRestartCurrentLevel);
+        menu.add_if(!is_wasm, MenuItem::MainQuickSave);
+        menu.add_if(!is_wasm, MenuItem::MainQuickLoad);
         menu.add(MenuItem::MainMouseSettings);
         menu.add_if(!is_wasm, MenuItem::MainDisplaySettings);
         menu.add_if(!is_wasm, MenuItem::MainQuitGame);
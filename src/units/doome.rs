@@ -43,17 +43,8 @@ impl Doome {
                     .with_scale(Vec3::splat(0.0)),
                 Billboard,
                 Health::new(2500.0, 2500.0),
-                RayCast {
-                    origin: Vec2::ZERO,
-                    direction: Vec2::NEG_Y * 20.0,
-                    hit: None,
-                },
-                Collider::circle(1.0, 12),
-                Body {
-                    acceleration: Vec2::ZERO,
-                    velocity: Vec2::ZERO,
-                    body_type: BodyType::Kinematic,
-                },
+                Collider::circle(1.0).with_layers(CollisionLayers::ENEMY),
+                Body::kinematic().with_mass(f32::INFINITY),
                 Weapon::new(Arc::new(weapon)),
             ))
             .id()
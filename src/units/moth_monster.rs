@@ -23,17 +23,8 @@ impl MothMonster {
                 Transform::from_translation(position),
                 Billboard,
                 Health::new(100.0, 100.0),
-                RayCast {
-                    origin: Vec2::ZERO,
-                    direction: Vec2::NEG_Y * 20.0,
-                    hit: None,
-                },
-                Collider::circle(0.75, 12),
-                Body {
-                    acceleration: Vec2::ZERO,
-                    velocity: Vec2::ZERO,
-                    body_type: BodyType::Kinematic,
-                },
+                Collider::circle(0.75).with_layers(CollisionLayers::ENEMY),
+                Body::kinematic().with_mass(2.0),
                 Weapon::new(Arc::new(weapon)), // TODO: arc is inefficient here
             ))
             .id()
@@ -36,6 +36,17 @@ pub struct PrefabWeapons {
     pub rpg: (Arc<WeaponDefinition>, Arc<WeaponSprites>),
 }
 
+impl PrefabWeapons {
+    pub fn get(
+        &self,
+        name: &str,
+    ) -> Option<&(Arc<WeaponDefinition>, Arc<WeaponSprites>)> {
+        [&self.handgun, &self.rifle, &self.rpg]
+            .into_iter()
+            .find(|(definition, _)| definition.name == name)
+    }
+}
+
 #[derive(Component)]
 pub struct Weapon {
     pub definition: Arc<WeaponDefinition>,
@@ -72,6 +83,7 @@ impl Weapon {
         commands: &mut Commands,
         transform: &Transform,
         direction: Vec3,
+        layers: CollisionLayers,
     ) {
         self.cooldown_timer = self.definition.cooldown;
 
@@ -92,14 +104,12 @@ impl Weapon {
         let mut cmds = commands.spawn((
             bullet_transform,
             Material::default().with_uv_transparency().emissive(),
-            Collider::circle(self.definition.collider_radius, 6).detector(),
-            Body {
-                acceleration: Vec2::ZERO,
-                velocity: graphical_to_physical(
-                    direction.normalize() * self.definition.bullet_speed,
-                ),
-                body_type: BodyType::Ethereal,
-            },
+            Collider::circle(self.definition.collider_radius)
+                .detector()
+                .with_layers(layers),
+            Body::ethereal().with_velocity(graphical_to_physical(
+                direction.normalize() * self.definition.bullet_speed,
+            )),
             GeometryType::Dynamic,
             Bullet::new(
                 self.definition.bullet_damage,
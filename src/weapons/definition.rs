@@ -2,6 +2,7 @@ use doome_bevy::assets::{AssetHandle, Model};
 
 #[derive(Debug, Clone)]
 pub struct WeaponDefinition {
+    pub name: &'static str,
     pub cooldown: f32,
     pub bullet_model: Option<AssetHandle<Model>>,
     pub bullet_speed: f32,
@@ -17,13 +18,19 @@ pub struct WeaponDefinition {
 #[derive(Debug, Clone, Copy)]
 pub enum BulletType {
     Bullet,
-    Rocket { explosion_radius: f32 },
+    Rocket {
+        explosion_radius: f32,
+        /// Damage dealt at the center of the explosion, falling off towards
+        /// its edge; the entity hit directly gets the bullet's damage instead
+        splash_damage: f32,
+    },
 }
 
 #[allow(unused)]
 impl WeaponDefinition {
     pub fn new() -> Self {
         Self {
+            name: "unnamed",
             cooldown: 0.5,
             forward_offset: 1.5,
             height_offset: 1.0,
@@ -37,6 +44,11 @@ impl WeaponDefinition {
         }
     }
 
+    pub fn with_name(mut self, name: &'static str) -> Self {
+        self.name = name;
+        self
+    }
+
     pub fn with_model(mut self, model: AssetHandle<Model>) -> Self {
         self.bullet_model = Some(model);
         self
@@ -82,8 +94,15 @@ impl WeaponDefinition {
         self
     }
 
-    pub fn with_rocket(mut self, explosion_radius: f32) -> Self {
-        self.bullet_type = BulletType::Rocket { explosion_radius };
+    pub fn with_rocket(
+        mut self,
+        explosion_radius: f32,
+        splash_damage: f32,
+    ) -> Self {
+        self.bullet_type = BulletType::Rocket {
+            explosion_radius,
+            splash_damage,
+        };
         self
     }
 }
@@ -19,6 +19,7 @@ pub fn rifle(assets: &Assets) -> (Arc<WeaponDefinition>, Arc<WeaponSprites>) {
     };
 
     let definition = WeaponDefinition::new()
+        .with_name("rifle")
         .with_model(assets.load_model("bullet"))
         .with_cooldown(0.3)
         .with_speed(50.0)
@@ -45,6 +46,7 @@ pub fn handgun(assets: &Assets) -> (Arc<WeaponDefinition>, Arc<WeaponSprites>) {
     };
 
     let definition = WeaponDefinition::new()
+        .with_name("handgun")
         .with_model(assets.load_model("bullet"))
         .with_cooldown(0.6)
         .with_speed(50.0)
@@ -67,8 +69,9 @@ pub fn rpg(assets: &Assets) -> (Arc<WeaponDefinition>, Arc<WeaponSprites>) {
     };
 
     let definition = WeaponDefinition::new()
+        .with_name("rpg")
         .with_model(assets.load_model("fireball"))
-        .with_rocket(6.0)
+        .with_rocket(6.0, 150.0)
         .with_cooldown(0.5)
         .with_speed(20.0)
         .with_damage(500.0)
@@ -79,6 +82,7 @@ pub fn rpg(assets: &Assets) -> (Arc<WeaponDefinition>, Arc<WeaponSprites>) {
 
 pub fn enemy_fire_spew(assets: &Assets) -> WeaponDefinition {
     WeaponDefinition::new()
+        .with_name("enemy-fire-spew")
         .with_model(assets.load_model("fireball"))
         .with_cooldown(1.0)
         .with_speed(17.5)
@@ -88,6 +92,7 @@ pub fn enemy_fire_spew(assets: &Assets) -> WeaponDefinition {
 
 pub fn doome_fire_spew(assets: &Assets) -> WeaponDefinition {
     WeaponDefinition::new()
+        .with_name("doome-fire-spew")
         .with_model(assets.load_model("fireball"))
         .with_cooldown(1.0)
         .with_forward_offset(10.0)